@@ -1,7 +1,21 @@
 import { Transform } from "./math/Transform.js";
 import { TFTree } from "./TFTree.js";
+import { TFError } from "./TFError.js";
+import { OutOfRangeError } from "./OutOfRangeError.js";
 import { CycleDetectedError } from "./CycleDetectedError.js";
-import type { TransformStamped, BufferedTFTreeOptions } from "./types.js";
+import type {
+  TransformStamped,
+  BufferedTFTreeOptions,
+  RemoveFrameOptions,
+  StampedUpdateOptions,
+  UpdateSourceOptions,
+} from "./types.js";
+
+/** A `receivedAt − timestamp` sample of an update source's clock offset. */
+interface OffsetSample {
+  receivedAt: number;
+  offset: number;
+}
 
 // ── internal per-frame buffer ─────────────────────────────────────────────────
 
@@ -23,6 +37,15 @@ class TransformBuffer {
     return this.entries.length;
   }
 
+  /** Returns an independent copy of this buffer. */
+  clone(): TransformBuffer {
+    const copy = new TransformBuffer(this.maxDuration);
+    for (const entry of this.entries) {
+      copy.entries.push(entry);
+    }
+    return copy;
+  }
+
   /**
    * Insert a new time-stamped transform.  Maintains ascending timestamp order
    * and prunes entries that have aged out.
@@ -47,12 +70,12 @@ class TransformBuffer {
    */
   interpolateAt(timestamp: number): Transform {
     if (this.entries.length === 0) {
-      throw new Error("Transform buffer is empty.");
+      throw new TFError("INVALID_STATE", "Transform buffer is empty.");
     }
 
     const oldest = this.entries[0].timestamp;
     if (timestamp < oldest) {
-      throw new RangeError(
+      throw new OutOfRangeError(
         `Requested timestamp ${timestamp} is before the oldest buffered entry ` +
           `(${oldest}). The data may have been pruned.`,
       );
@@ -151,6 +174,19 @@ class TransformBuffer {
 export class BufferedTFTree extends TFTree {
   private readonly buffers = new Map<string, TransformBuffer>();
   private readonly maxBufferDuration: number;
+  private buffersAtBegin: Map<string, TransformBuffer> | undefined;
+  private readonly updateSources = new Map<string, Required<UpdateSourceOptions>>();
+  /** The source whose update each frame last accepted, with its corrected timestamp. */
+  private readonly frameHolders = new Map<string, { source: string; timestamp: number }>();
+  private frameHoldersAtBegin: Map<string, { source: string; timestamp: number }> | undefined;
+  /**
+   * Per source estimating its clock offset: the `receivedAt − timestamp`
+   * samples of the buffer window that may still become its minimum, with
+   * increasing offsets.  The arrays are replaced, never mutated.
+   */
+  private readonly offsetSamples = new Map<string, OffsetSample[]>();
+  private offsetSamplesAtBegin: Map<string, OffsetSample[]> | undefined;
+  private clockOffsetsAtBegin: Map<string, number> | undefined;
 
   constructor(options?: BufferedTFTreeOptions) {
     super();
@@ -166,28 +202,136 @@ export class BufferedTFTree extends TFTree {
    * {@link TFTree.getTransform} (the non-temporal API) continues to reflect the
    * most recently provided transform.
    *
+   * With `{ source }` naming a source registered with
+   * {@link setUpdateSource}, `timestamp` is on that source's clock: it is
+   * corrected by the source's clock offset and latency before buffering, and
+   * the update is ignored if a higher-priority source holds the frame.  For
+   * a source that estimates its clock offset, the update is corrected by the
+   * estimate refined with its `receivedAt` time, which is kept once the
+   * update is accepted.
+   *
    * @param id        Identifier of the frame to update.
    * @param transform New transform of this frame relative to its parent.
    * @param timestamp Timestamp in milliseconds (e.g. `Date.now()`).
+   * @returns `false` if the update was ignored in favour of a higher-priority
+   *          source, `true` otherwise.
    *
    * @throws {Error} if `id` is not registered.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `source` is not registered.
    */
-  setTransform(id: string, transform: Transform, timestamp: number): void {
-    if (!this.hasFrame(id)) {
-      throw new Error(`Frame "${id}" not found.`);
+  setTransform(
+    id: string,
+    transform: Transform,
+    timestamp: number,
+    options: StampedUpdateOptions = {},
+  ): boolean {
+    const frameId = this.getFrameNode(id).id;
+    const { source } = options;
+    let holder: { source: string; timestamp: number } | undefined;
+    let timing: Required<UpdateSourceOptions> | undefined;
+    let samples: OffsetSample[] | undefined;
+    if (source !== undefined) {
+      timing = this.updateSources.get(source);
+      if (timing === undefined) {
+        throw new TFError("INVALID_ARGUMENT", `Update source "${source}" is not registered.`, {
+          frameIds: [frameId],
+          operation: "setTransform",
+        });
+      }
+      let clockOffset = timing.clockOffset;
+      if (timing.estimateClockOffset) {
+        samples = this.observeClockOffset(source, timestamp, options.receivedAt ?? this.now());
+        clockOffset = samples[0].offset;
+      }
+      timestamp += clockOffset - timing.latency;
+      const current = this.frameHolders.get(frameId);
+      if (
+        current !== undefined &&
+        current.source !== source &&
+        this.updateSources.get(current.source)!.priority > timing.priority &&
+        timestamp - current.timestamp < this.maxBufferDuration
+      ) {
+        return false;
+      }
+      holder = {
+        source,
+        timestamp:
+          current?.source === source ? Math.max(current.timestamp, timestamp) : timestamp,
+      };
     }
 
     // Keep the base-class current transform in sync so the non-temporal API
     // remains usable.
-    this.updateFrame(id, transform);
+    this.updateTransform(id, transform, options);
+    if (samples !== undefined) {
+      this.offsetSamples.set(source!, samples);
+      timing!.clockOffset = samples[0].offset;
+    }
 
     // Append to (or create) the per-frame time-stamped buffer.
-    let buffer = this.buffers.get(id);
+    let buffer = this.buffers.get(frameId);
     if (buffer === undefined) {
       buffer = new TransformBuffer(this.maxBufferDuration);
-      this.buffers.set(id, buffer);
+      this.buffers.set(frameId, buffer);
     }
     buffer.push({ timestamp, transform });
+    if (holder !== undefined) {
+      this.frameHolders.set(frameId, holder);
+    }
+    return true;
+  }
+
+  /**
+   * Record a time-stamped rotation for an existing frame, keeping its current
+   * translation: the stamped counterpart of {@link TFTree.updateFrameRotation}
+   * for IMU-driven orientation frames.  Otherwise behaves as
+   * {@link setTransform}.
+   *
+   * @returns `false` if the update was ignored in favour of a higher-priority
+   *          source, `true` otherwise.
+   * @throws {Error} if `id` is not registered.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `source` is not registered.
+   * @throws {InvalidTransformError} if a component is not finite or the
+   *                                 quaternion has zero norm.
+   */
+  setRotation(
+    id: string,
+    qx: number,
+    qy: number,
+    qz: number,
+    qw: number,
+    timestamp: number,
+    options: StampedUpdateOptions = {},
+  ): boolean {
+    return this.setTransform(id, this.withRotation(id, qx, qy, qz, qw), timestamp, options);
+  }
+
+  /**
+   * Register an update source — e.g. a live robot feed, a simulator or a
+   * replay — for {@link setTransform}, or update its clock offset, latency
+   * and priority.  With `undefined` the source is removed and releases the
+   * frames it held.  Either way, a clock offset estimated so far is reset.
+   */
+  setUpdateSource(name: string, options: UpdateSourceOptions | undefined): void {
+    this.offsetSamples.delete(name);
+    if (options === undefined) {
+      this.updateSources.delete(name);
+      for (const [id, holder] of this.frameHolders) {
+        if (holder.source === name) this.frameHolders.delete(id);
+      }
+      return;
+    }
+    this.updateSources.set(name, {
+      clockOffset: options.clockOffset ?? 0,
+      latency: options.latency ?? 0,
+      priority: options.priority ?? 0,
+      estimateClockOffset: options.estimateClockOffset ?? false,
+    });
+  }
+
+  /** Returns every registered update source with its settings. */
+  getUpdateSources(): Record<string, Required<UpdateSourceOptions>> {
+    return Object.fromEntries(this.updateSources);
   }
 
   /**
@@ -210,12 +354,8 @@ export class BufferedTFTree extends TFTree {
    *                      entry for any frame along the path (data pruned).
    */
   getTransformAt(from: string, to: string, timestamp: number): Transform {
-    if (!this.hasFrame(from)) {
-      throw new Error(`Frame "${from}" not found.`);
-    }
-    if (!this.hasFrame(to)) {
-      throw new Error(`Frame "${to}" not found.`);
-    }
+    from = this.getFrameNode(from).id;
+    to = this.getFrameNode(to).id;
     if (from === to) {
       return Transform.identity();
     }
@@ -225,15 +365,107 @@ export class BufferedTFTree extends TFTree {
       .compose(this.worldTransformAt(to, timestamp));
   }
 
-  // ── override to clean up per-frame buffer ─────────────────────────────────
+  // ── overrides to keep per-frame buffers in sync ───────────────────────────
 
-  override removeFrame(id: string): void {
-    super.removeFrame(id);
+  protected override deleteFrame(id: string, options: RemoveFrameOptions): void {
+    super.deleteFrame(id, options);
     this.buffers.delete(id);
+    this.frameHolders.delete(id);
+  }
+
+  override begin(): void {
+    super.begin();
+    this.buffersAtBegin = new Map();
+    for (const [id, buffer] of this.buffers) {
+      this.buffersAtBegin.set(id, buffer.clone());
+    }
+    this.frameHoldersAtBegin = new Map(this.frameHolders);
+    this.offsetSamplesAtBegin = new Map(this.offsetSamples);
+    this.clockOffsetsAtBegin = new Map();
+    for (const [name, timing] of this.updateSources) {
+      this.clockOffsetsAtBegin.set(name, timing.clockOffset);
+    }
+  }
+
+  override commit(): void {
+    super.commit();
+    this.buffersAtBegin = undefined;
+    this.frameHoldersAtBegin = undefined;
+    this.offsetSamplesAtBegin = undefined;
+    this.clockOffsetsAtBegin = undefined;
+  }
+
+  override rollback(): void {
+    super.rollback();
+    this.buffers.clear();
+    for (const [id, buffer] of this.buffersAtBegin!) {
+      this.buffers.set(id, buffer);
+    }
+    this.buffersAtBegin = undefined;
+    this.frameHolders.clear();
+    for (const [id, holder] of this.frameHoldersAtBegin!) {
+      this.frameHolders.set(id, holder);
+    }
+    this.frameHoldersAtBegin = undefined;
+    this.offsetSamples.clear();
+    for (const [name, samples] of this.offsetSamplesAtBegin!) {
+      this.offsetSamples.set(name, samples);
+    }
+    this.offsetSamplesAtBegin = undefined;
+    for (const [name, clockOffset] of this.clockOffsetsAtBegin!) {
+      const timing = this.updateSources.get(name);
+      if (timing !== undefined) timing.clockOffset = clockOffset;
+    }
+    this.clockOffsetsAtBegin = undefined;
+  }
+
+  override clear(): void {
+    super.clear();
+    this.buffers.clear();
+    this.frameHolders.clear();
+  }
+
+  protected override changeFrameId(oldId: string, newId: string): void {
+    super.changeFrameId(oldId, newId);
+    const buffer = this.buffers.get(oldId);
+    if (buffer !== undefined && oldId !== newId) {
+      this.buffers.delete(oldId);
+      this.buffers.set(newId, buffer);
+    }
+    const holder = this.frameHolders.get(oldId);
+    if (holder !== undefined && oldId !== newId) {
+      this.frameHolders.delete(oldId);
+      this.frameHolders.set(newId, holder);
+    }
   }
 
   // ── private helpers ───────────────────────────────────────────────────────
 
+  /**
+   * Return the offset samples of `source` once an update stamped `timestamp`
+   * received at `receivedAt` is taken into account, without recording them.
+   * The first sample holds the estimated clock offset: the smallest
+   * `receivedAt − timestamp` within the buffer window.
+   */
+  private observeClockOffset(
+    source: string,
+    timestamp: number,
+    receivedAt: number,
+  ): OffsetSample[] {
+    const samples = [...(this.offsetSamples.get(source) ?? [])];
+    const offset = receivedAt - timestamp;
+    // A sample with a larger offset can no longer be the minimum once a
+    // later sample with a smaller one arrived.
+    while (samples.length > 0 && samples[samples.length - 1].offset >= offset) {
+      samples.pop();
+    }
+    samples.push({ receivedAt, offset });
+    while (samples[0].receivedAt < receivedAt - this.maxBufferDuration) {
+      samples.shift();
+    }
+    return samples;
+  }
+
   /**
    * Recursively compute the world transform (accumulated from the subtree root
    * down to `id`) at the given timestamp.
@@ -244,7 +476,7 @@ export class BufferedTFTree extends TFTree {
     }
     visiting.add(id);
 
-    const frame = this.getFrameNode(id);
+    const frame = this.frameNodeById(id);
     const local = this.localTransformAt(id, timestamp);
 
     if (frame.parentId === undefined) {
@@ -260,7 +492,7 @@ export class BufferedTFTree extends TFTree {
   private localTransformAt(id: string, timestamp: number): Transform {
     const buffer = this.buffers.get(id);
     if (buffer === undefined || buffer.size === 0) {
-      return this.getFrameNode(id).transform;
+      return this.frameNodeById(id).transform;
     }
     return buffer.interpolateAt(timestamp);
   }
@@ -0,0 +1,74 @@
+import type { Transform } from "./math/Transform.js";
+import type { ChangePacket } from "./types.js";
+
+/**
+ * Binary layout of a change packet (native byte order):
+ *
+ * | Offset                | Type                  | Content                          |
+ * | --------------------- | --------------------- | -------------------------------- |
+ * | 0                     | `Float64`             | batch sequence number            |
+ * | 8                     | `Uint32`              | frame count `n`                  |
+ * | 12                    | `Uint32`              | string table length `m` (chars)  |
+ * | 16                    | `Float64 × 7n`        | world transforms                 |
+ * | 16 + 56n              | `Uint32 × n`          | length of each frame id          |
+ * | 16 + 60n              | `Uint16 × m`          | frame ids (UTF-16 code units)    |
+ */
+const HEADER_BYTES = 16;
+
+/**
+ * Encode a change batch into a single self-contained `ArrayBuffer` that can
+ * be handed to a worker as a transferable (`postMessage(buffer, [buffer])`)
+ * and read back with {@link decodeChangePacket}.
+ *
+ * @param seq    Sequence number of the batch.
+ * @param frames Each changed frame id paired with its world transform.
+ */
+export function encodeChangePacket(
+  seq: number,
+  frames: ReadonlyArray<readonly [string, Transform]>,
+): ArrayBuffer {
+  const count = frames.length;
+  const chars = frames.reduce((total, [id]) => total + id.length, 0);
+  const lengthsOffset = HEADER_BYTES + count * 7 * 8;
+  const charsOffset = lengthsOffset + count * 4;
+  const buffer = new ArrayBuffer(charsOffset + chars * 2);
+
+  new Float64Array(buffer, 0, 1)[0] = seq;
+  new Uint32Array(buffer, 8, 2).set([count, chars]);
+  const data = new Float64Array(buffer, HEADER_BYTES, count * 7);
+  const lengths = new Uint32Array(buffer, lengthsOffset, count);
+  const table = new Uint16Array(buffer, charsOffset, chars);
+  let offset = 0;
+  frames.forEach(([id, { translation: t, rotation: r }], i) => {
+    data.set([t.x, t.y, t.z, r.x, r.y, r.z, r.w], i * 7);
+    lengths[i] = id.length;
+    for (let c = 0; c < id.length; c++) {
+      table[offset++] = id.charCodeAt(c);
+    }
+  });
+  return buffer;
+}
+
+/**
+ * Decode a buffer produced by {@link encodeChangePacket}.  Needs nothing but
+ * the buffer, so it can run in a worker without a {@link TFTree}.  `data` is
+ * a view into `buffer`, not a copy.
+ */
+export function decodeChangePacket(buffer: ArrayBuffer): ChangePacket {
+  const seq = new Float64Array(buffer, 0, 1)[0];
+  const [count, chars] = new Uint32Array(buffer, 8, 2);
+  const data = new Float64Array(buffer, HEADER_BYTES, count * 7);
+  const lengthsOffset = HEADER_BYTES + count * 7 * 8;
+  const lengths = new Uint32Array(buffer, lengthsOffset, count);
+  const table = new Uint16Array(buffer, lengthsOffset + count * 4, chars);
+  const frameIds: string[] = [];
+  let offset = 0;
+  for (const length of lengths) {
+    let id = "";
+    for (let c = 0; c < length; c++) {
+      id += String.fromCharCode(table[offset++]);
+    }
+    frameIds.push(id);
+  }
+  return { seq, frameIds, data };
+}
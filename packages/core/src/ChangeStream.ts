@@ -0,0 +1,88 @@
+/**
+ * An async iterator over batches of changed frame ids, created with
+ * {@link TFTree.changes}.  Each batch is the list of frames whose world
+ * transform changed in one mutation (or committed transaction / flush).
+ *
+ * Batches are buffered in an internal queue until consumed.  Producers are
+ * never blocked; instead, once `highWaterMark` batches are queued, further
+ * changes are merged (deduplicated) into the last queued batch so memory
+ * stays bounded while a slow consumer catches up.
+ *
+ * Being an async iterable, the stream works with `for await` and can be
+ * wrapped with `ReadableStream.from(stream)` where available.
+ *
+ * @example
+ * ```ts
+ * for await (const frameIds of tf.changes({ pattern: "robot1/**" })) {
+ *   redraw(frameIds);
+ * }
+ * ```
+ */
+export class ChangeStream implements AsyncIterableIterator<string[]> {
+  private readonly queue: string[][] = [];
+  private readonly waiting: Array<(result: IteratorResult<string[]>) => void> = [];
+  private readonly highWaterMark: number;
+  private readonly unsubscribe: () => void;
+  private closed = false;
+
+  /**
+   * @param subscribe     Registers the stream's push function with the source
+   *                      and returns an unsubscribe function.
+   * @param highWaterMark Number of batches queued before new changes are
+   *                      merged into the last one.
+   */
+  constructor(
+    subscribe: (push: (frameIds: string[]) => void) => () => void,
+    highWaterMark: number,
+  ) {
+    this.highWaterMark = Math.max(1, highWaterMark);
+    this.unsubscribe = subscribe((frameIds) => this.push(frameIds));
+  }
+
+  /** Number of batches waiting to be consumed. */
+  get size(): number {
+    return this.queue.length;
+  }
+
+  next(): Promise<IteratorResult<string[]>> {
+    const batch = this.queue.shift();
+    if (batch !== undefined) {
+      return Promise.resolve({ value: batch, done: false });
+    }
+    if (this.closed) {
+      return Promise.resolve({ value: undefined, done: true });
+    }
+    return new Promise((resolve) => this.waiting.push(resolve));
+  }
+
+  /** Stop listening for changes.  Batches already queued are discarded. */
+  return(): Promise<IteratorResult<string[]>> {
+    if (!this.closed) {
+      this.closed = true;
+      this.unsubscribe();
+      this.queue.length = 0;
+      for (const resolve of this.waiting.splice(0)) {
+        resolve({ value: undefined, done: true });
+      }
+    }
+    return Promise.resolve({ value: undefined, done: true });
+  }
+
+  [Symbol.asyncIterator](): AsyncIterableIterator<string[]> {
+    return this;
+  }
+
+  // ── private ───────────────────────────────────────────────────────────────
+
+  private push(frameIds: string[]): void {
+    const resolve = this.waiting.shift();
+    if (resolve !== undefined) {
+      resolve({ value: frameIds, done: false });
+    } else if (this.queue.length < this.highWaterMark) {
+      this.queue.push(frameIds);
+    } else {
+      const last = this.queue[this.queue.length - 1];
+      this.queue[this.queue.length - 1] = Array.from(new Set([...last, ...frameIds]));
+    }
+  }
+}
@@ -1,10 +1,20 @@
+import { TFError } from "./TFError.js";
+
 /**
  * Thrown when a cyclic dependency is detected in the transform tree,
  * which would violate the Directed Acyclic Graph (DAG) invariant.
  */
-export class CycleDetectedError extends Error {
+export class CycleDetectedError extends TFError {
+  /** Frame at which the cycle would be closed. */
+  readonly frameId: string;
+
   constructor(frameId: string) {
-    super(`Cycle detected in the transform tree at frame "${frameId}".`);
+    super(
+      "CYCLE_DETECTED",
+      `Cycle detected in the transform tree at frame "${frameId}".`,
+      { frameIds: [frameId] },
+    );
     this.name = "CycleDetectedError";
+    this.frameId = frameId;
   }
 }
@@ -0,0 +1,23 @@
+import { TFError } from "./TFError.js";
+
+/**
+ * Thrown when a transform is requested between two frames that live in
+ * different trees.
+ */
+export class DisconnectedFramesError extends TFError {
+  /** First of the two frames. */
+  readonly frameA: string;
+  /** Second of the two frames. */
+  readonly frameB: string;
+
+  constructor(frameA: string, frameB: string) {
+    super(
+      "DISCONNECTED_FRAMES",
+      `Frames "${frameA}" and "${frameB}" are not connected in the same tree.`,
+      { frameIds: [frameA, frameB] },
+    );
+    this.name = "DisconnectedFramesError";
+    this.frameA = frameA;
+    this.frameB = frameB;
+  }
+}
@@ -0,0 +1,15 @@
+import { TFError } from "./TFError.js";
+
+/**
+ * Thrown when registering (or renaming to) a frame id that is already in use.
+ */
+export class DuplicateFrameError extends TFError {
+  /** The id that is already registered. */
+  readonly frameId: string;
+
+  constructor(frameId: string) {
+    super("DUPLICATE_FRAME", `Frame "${frameId}" is already registered.`, { frameIds: [frameId] });
+    this.name = "DuplicateFrameError";
+    this.frameId = frameId;
+  }
+}
@@ -0,0 +1,20 @@
+import { TFError } from "./TFError.js";
+
+/**
+ * Thrown when an operation would replace the transform of a frame that was
+ * locked with {@link TFTree.lockFrame}.
+ */
+export class FrameLockedError extends TFError {
+  /** Id of the locked frame. */
+  readonly frameId: string;
+
+  constructor(frameId: string) {
+    super(
+      "FRAME_LOCKED",
+      `Frame "${frameId}" is locked; unlock it before updating its transform.`,
+      { frameIds: [frameId] },
+    );
+    this.name = "FrameLockedError";
+    this.frameId = frameId;
+  }
+}
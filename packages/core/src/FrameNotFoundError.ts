@@ -0,0 +1,15 @@
+import { TFError } from "./TFError.js";
+
+/**
+ * Thrown when an operation refers to a frame id that is not registered.
+ */
+export class FrameNotFoundError extends TFError {
+  /** The unknown frame id. */
+  readonly frameId: string;
+
+  constructor(frameId: string, message = `Frame "${frameId}" not found.`) {
+    super("FRAME_NOT_FOUND", message, { frameIds: [frameId] });
+    this.name = "FrameNotFoundError";
+    this.frameId = frameId;
+  }
+}
@@ -0,0 +1,21 @@
+import { TFError } from "./TFError.js";
+
+/**
+ * Thrown when a frame id is rejected by the policy set with
+ * {@link TFTree.setFrameIdPolicy}.
+ */
+export class InvalidFrameIdError extends TFError {
+  /** The rejected id. */
+  readonly frameId: string;
+  /** Why the id was rejected. */
+  readonly reason: string;
+
+  constructor(frameId: string, reason: string) {
+    super("INVALID_FRAME_ID", `Frame id "${frameId}" is not allowed: ${reason}.`, {
+      frameIds: [frameId],
+    });
+    this.name = "InvalidFrameIdError";
+    this.frameId = frameId;
+    this.reason = reason;
+  }
+}
@@ -0,0 +1,23 @@
+import { TFError } from "./TFError.js";
+
+/**
+ * Thrown when a transform passed to the tree is unusable, e.g. contains a
+ * `NaN` or infinite component, or has a rotation rejected by the rotation
+ * norm policy.
+ */
+export class InvalidTransformError extends TFError {
+  /** Frame the transform was meant for. */
+  readonly frameId: string;
+  /**
+   * The offending component, e.g. `"translation.x"` or `"rotation.w"`, or
+   * `"rotation"` for a rotation with the wrong norm.
+   */
+  readonly component: string;
+
+  constructor(frameId: string, component: string, message: string) {
+    super("INVALID_TRANSFORM", message, { frameIds: [frameId] });
+    this.name = "InvalidTransformError";
+    this.frameId = frameId;
+    this.component = component;
+  }
+}
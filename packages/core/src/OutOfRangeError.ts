@@ -0,0 +1,20 @@
+import type { TFErrorCode, TFErrorContext } from "./types.js";
+
+/**
+ * Thrown when requested data is outside what is currently retained, e.g. a
+ * timestamp older than the transform buffer.  Extends `RangeError` rather
+ * than {@link TFError}, but carries the same `code` and `context` fields.
+ */
+export class OutOfRangeError extends RangeError {
+  /** Always `"OUT_OF_RANGE"`. */
+  readonly code: TFErrorCode;
+  /** Frames involved and, where known, the operation that failed. */
+  readonly context: TFErrorContext;
+
+  constructor(message: string, context: TFErrorContext = { frameIds: [] }) {
+    super(message);
+    this.name = "OutOfRangeError";
+    this.code = "OUT_OF_RANGE";
+    this.context = context;
+  }
+}
@@ -0,0 +1,31 @@
+import { TFError } from "./TFError.js";
+import type { TreeQuotas } from "./types.js";
+
+/**
+ * Thrown when an operation would exceed a limit set with
+ * {@link TFTree.setQuotas} or {@link TFTree.setNamespacePolicy}.
+ */
+export class QuotaExceededError extends TFError {
+  /** The quota that would be exceeded. */
+  readonly quota: keyof TreeQuotas;
+  /** The configured limit. */
+  readonly limit: number;
+  /** Prefix of the namespace the limit applies to, if not the whole tree. */
+  readonly namespace: string | undefined;
+
+  constructor(
+    quota: keyof TreeQuotas,
+    limit: number,
+    frameIds: readonly string[],
+    namespace?: string,
+  ) {
+    const scope = namespace === undefined ? "" : ` in namespace "${namespace}"`;
+    super("QUOTA_EXCEEDED", `Quota exceeded: ${quota}${scope} is limited to ${limit}.`, {
+      frameIds,
+    });
+    this.name = "QuotaExceededError";
+    this.quota = quota;
+    this.limit = limit;
+    this.namespace = namespace;
+  }
+}
@@ -0,0 +1,24 @@
+import { TFError } from "./TFError.js";
+
+/**
+ * Thrown when an operation would add a frame to, or remove, rename, reparent
+ * or replace the transform of a frame in, a namespace made read-only with
+ * {@link TFTree.setNamespacePolicy}.
+ */
+export class ReadOnlyNamespaceError extends TFError {
+  /** Id of the frame. */
+  readonly frameId: string;
+  /** Prefix of the read-only namespace. */
+  readonly namespace: string;
+
+  constructor(frameId: string, namespace: string) {
+    super(
+      "READ_ONLY_NAMESPACE",
+      `Frame "${frameId}" is in the read-only namespace "${namespace}".`,
+      { frameIds: [frameId] },
+    );
+    this.name = "ReadOnlyNamespaceError";
+    this.frameId = frameId;
+    this.namespace = namespace;
+  }
+}
@@ -0,0 +1,182 @@
+import { Transform } from "./math/Transform.js";
+import { Vec3 } from "./math/Vec3.js";
+import { Quaternion } from "./math/Quaternion.js";
+import { TFError } from "./TFError.js";
+import { FrameNotFoundError } from "./FrameNotFoundError.js";
+import type { TFTree } from "./TFTree.js";
+import type { FloatArray } from "./types.js";
+
+/**
+ * Memory layout (native byte order):
+ *
+ * | Offset       | Type            | Content                                    |
+ * | ------------ | --------------- | ------------------------------------------ |
+ * | 0            | `Int32`         | index of the front slot (0 or 1)           |
+ * | 4            | `Int32 × 2`     | per-slot write counter (odd while writing) |
+ * | 12           | `Int32`         | generation: number of swaps so far         |
+ * | 16           | `Float64 × 7n`  | slot 0 transforms                          |
+ * | 16 + 56n     | `Float64 × 7n`  | slot 1 transforms                          |
+ */
+const HEADER_BYTES = 16;
+const FRONT = 0;
+const GENERATION = 3;
+
+/**
+ * Double-buffered transforms of a fixed list of frames, laid out in a
+ * `SharedArrayBuffer` so that workers can read them without torn reads.
+ *
+ * A single writer fills the back slot with {@link write} and publishes it
+ * with {@link swap}; readers only ever see the front slot.  Each slot carries
+ * a write counter, and readers retry when a swap and a new write overtook
+ * them mid-read, so every read returns one complete published state.
+ *
+ * @example
+ * ```ts
+ * // Main thread
+ * const shared = new SharedTransformBuffer(["robot", "camera"]);
+ * worker.postMessage({ buffer: shared.buffer, frameIds: shared.frameIds });
+ * tf.onAnyChange(() => {
+ *   shared.write(tf, "world");
+ *   shared.swap();
+ * });
+ *
+ * // Worker
+ * const shared = new SharedTransformBuffer(frameIds, buffer);
+ * const camera = shared.getTransform("camera");
+ * ```
+ */
+export class SharedTransformBuffer {
+  /** The underlying memory, to be sent to readers along with {@link frameIds}. */
+  readonly buffer: SharedArrayBuffer | ArrayBuffer;
+  /** The frames stored, in slot order. */
+  readonly frameIds: readonly string[];
+  private readonly header: Int32Array;
+  private readonly slots: readonly [Float64Array, Float64Array];
+  private readonly index: Map<string, number>;
+
+  /**
+   * @param frameIds Frames to store.  Readers must pass the same list.
+   * @param buffer   Memory of an existing buffer to attach to, e.g. one
+   *                 received by a worker.  When omitted, a
+   *                 `SharedArrayBuffer` (or, where unavailable, an
+   *                 `ArrayBuffer`) is allocated.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `buffer` has the wrong size
+   *                   for `frameIds`.
+   */
+  constructor(frameIds: readonly string[], buffer?: SharedArrayBuffer | ArrayBuffer) {
+    const byteLength = SharedTransformBuffer.byteLength(frameIds.length);
+    if (buffer === undefined) {
+      buffer =
+        typeof SharedArrayBuffer === "undefined"
+          ? new ArrayBuffer(byteLength)
+          : new SharedArrayBuffer(byteLength);
+    } else if (buffer.byteLength !== byteLength) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Buffer of ${buffer.byteLength} bytes does not fit ${frameIds.length} frames.`,
+        { frameIds: [], operation: "SharedTransformBuffer" },
+      );
+    }
+    this.buffer = buffer;
+    this.frameIds = frameIds.slice();
+    this.index = new Map(frameIds.map((id, i) => [id, i]));
+    this.header = new Int32Array(buffer, 0, 4);
+    const size = frameIds.length * 7;
+    this.slots = [
+      new Float64Array(buffer, HEADER_BYTES, size),
+      new Float64Array(buffer, HEADER_BYTES + size * 8, size),
+    ];
+  }
+
+  /** Returns the number of bytes needed to store `frameCount` frames. */
+  static byteLength(frameCount: number): number {
+    return HEADER_BYTES + 2 * frameCount * 7 * 8;
+  }
+
+  /**
+   * Fill the back slot with the transforms of {@link frameIds} relative to
+   * `referenceFrame`.  Readers don't see them until {@link swap}.  Only one
+   * thread may write, and a write that threw leaves the back slot
+   * incomplete, so don't swap until a write has succeeded.
+   *
+   * @throws {FrameNotFoundError} if a frame is not registered in `tree`.
+   * @throws {DisconnectedFramesError} if a frame is not connected to
+   *                                   `referenceFrame`.
+   */
+  write(tree: TFTree, referenceFrame: string): void {
+    const back = 1 - Atomics.load(this.header, FRONT);
+    const slot = this.slots[back];
+    Atomics.add(this.header, 1 + back, 1);
+    try {
+      this.frameIds.forEach((id, i) => {
+        const { translation: t, rotation: r } = tree.getTransform(referenceFrame, id);
+        slot.set([t.x, t.y, t.z, r.x, r.y, r.z, r.w], i * 7);
+      });
+    } finally {
+      Atomics.add(this.header, 1 + back, 1);
+    }
+  }
+
+  /**
+   * Publish the back slot written by {@link write}: readers see all of its
+   * transforms from now on.  The former front slot becomes the back slot,
+   * so call {@link write} again before the next swap.
+   *
+   * @returns The new {@link generation}.
+   */
+  swap(): number {
+    Atomics.store(this.header, FRONT, 1 - Atomics.load(this.header, FRONT));
+    return Atomics.add(this.header, GENERATION, 1) + 1;
+  }
+
+  /** Returns the number of swaps so far, e.g. to skip unchanged reads. */
+  generation(): number {
+    return Atomics.load(this.header, GENERATION);
+  }
+
+  /**
+   * Copy every published transform into `out`: seven numbers per frame in
+   * the order of {@link frameIds}, translation `x, y, z` then rotation
+   * `x, y, z, w`.
+   *
+   * @param out Destination for the `7 × frameIds.length` numbers; a new
+   *            `Float64Array` is allocated when omitted.
+   * @returns `out`.
+   */
+  read<T extends FloatArray = Float64Array>(
+    out: T = new Float64Array(this.frameIds.length * 7) as T,
+  ): T {
+    this.consistentRead((slot) => out.set(slot));
+    return out;
+  }
+
+  /**
+   * Returns the published transform of `id` relative to the reference frame
+   * it was written with.
+   *
+   * @throws {FrameNotFoundError} if `id` is not one of {@link frameIds}.
+   */
+  getTransform(id: string): Transform {
+    const i = this.index.get(id);
+    if (i === undefined) {
+      throw new FrameNotFoundError(id);
+    }
+    const v = new Float64Array(7);
+    this.consistentRead((slot) => v.set(slot.subarray(i * 7, i * 7 + 7)));
+    return new Transform(new Vec3(v[0], v[1], v[2]), new Quaternion(v[3], v[4], v[5], v[6]));
+  }
+
+  /**
+   * Run `copy` on the front slot, retrying until no write touched the slot
+   * while it ran.
+   */
+  private consistentRead(copy: (slot: Float64Array) => void): void {
+    for (;;) {
+      const front = Atomics.load(this.header, FRONT);
+      const before = Atomics.load(this.header, 1 + front);
+      if (before % 2 !== 0) continue;
+      copy(this.slots[front]);
+      if (Atomics.load(this.header, 1 + front) === before) return;
+    }
+  }
+}
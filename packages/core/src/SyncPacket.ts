@@ -0,0 +1,275 @@
+import { TFError } from "./TFError.js";
+import { OutOfRangeError } from "./OutOfRangeError.js";
+import type { JournalOperation, SyncPacket, TransformJSON } from "./types.js";
+
+/**
+ * Binary layout of a sync packet (little-endian, so that packets can cross
+ * the network between machines of any byte order):
+ *
+ * | Offset | Type                  | Content                                    |
+ * | ------ | --------------------- | ------------------------------------------ |
+ * | 0      | `Float64`             | journal sequence number                    |
+ * | 8      | `Float64`             | translation quantization step              |
+ * | 16     | `Uint32`              | id count `n`                               |
+ * | 20     | `Uint32`              | operation count                            |
+ * | 24     | `n` × id              | `Uint16` length, then UTF-16 code units    |
+ * | …      | operations            | `Uint8` opcode, then its fields (below)    |
+ *
+ * Ids are written once and referenced by `Uint32` index; a missing parent is
+ * `0xffffffff`.  A transform takes 20 bytes: translation as three `Int32`
+ * multiples of the quantization step, rotation as four `Int16` components
+ * scaled by 32767.
+ *
+ * | Opcode | Operation | Fields                      |
+ * | ------ | --------- | --------------------------- |
+ * | 0      | `add`     | id, parent, transform       |
+ * | 1      | `update`  | id, transform               |
+ * | 2      | `relink`  | id, parent, transform       |
+ * | 3      | `remove`  | id                          |
+ * | 4      | `rename`  | id, new id                  |
+ * | 5      | `clear`   | –                           |
+ */
+const HEADER_BYTES = 24;
+const TRANSFORM_BYTES = 20;
+const NO_PARENT = 0xffffffff;
+const MAX_ID_LENGTH = 0xffff;
+const ROTATION_SCALE = 32767;
+const OPCODES = ["add", "update", "relink", "remove", "rename", "clear"] as const;
+
+/** Default translation quantization step: 0.1 mm. */
+const DEFAULT_TRANSLATION_STEP = 1e-4;
+
+/**
+ * Encode journal operations into a compact `ArrayBuffer` for replicating a
+ * tree over a WebSocket or WebRTC data channel.  Read it back with
+ * {@link decodeSyncPacket}.
+ *
+ * Transforms are quantized: translations to multiples of `translationStep`
+ * and rotation components to 1/32767, so a decoded transform can differ
+ * slightly from the encoded one.
+ *
+ * @param seq             Journal sequence number the packet brings a replica
+ *                        up to.
+ * @param operations      Operations to encode, in order.
+ * @param translationStep Translation quantization step, in tree units.
+ * @throws {TFError} (`INVALID_ARGUMENT`) if `translationStep` is not a
+ *                   positive finite number, or if an id is longer than
+ *                   65535 UTF-16 code units.
+ * @throws {OutOfRangeError} if a translation component is too large to be
+ *                           quantized with `translationStep`.
+ */
+export function encodeSyncPacket(
+  seq: number,
+  operations: readonly JournalOperation[],
+  translationStep = DEFAULT_TRANSLATION_STEP,
+): ArrayBuffer {
+  if (!(translationStep > 0 && Number.isFinite(translationStep))) {
+    throw new TFError("INVALID_ARGUMENT", `Invalid translation step ${translationStep}.`, {
+      frameIds: [],
+      operation: "encodeSyncPacket",
+    });
+  }
+  const ids = new Map<string, number>();
+  let size = HEADER_BYTES;
+  const intern = (id: string) => {
+    if (!ids.has(id)) {
+      if (id.length > MAX_ID_LENGTH) {
+        throw new TFError(
+          "INVALID_ARGUMENT",
+          `Frame id of ${id.length} characters exceeds the sync packet limit of ${MAX_ID_LENGTH}.`,
+          { frameIds: [id], operation: "encodeSyncPacket" },
+        );
+      }
+      ids.set(id, ids.size);
+      size += 2 + id.length * 2;
+    }
+  };
+  for (const op of operations) {
+    size += 1;
+    if (op.op === "clear") continue;
+    intern(op.id);
+    size += 4;
+    if (op.op === "add" || op.op === "relink") {
+      if (op.parentId !== null) intern(op.parentId);
+      size += 4 + TRANSFORM_BYTES;
+    } else if (op.op === "update") {
+      size += TRANSFORM_BYTES;
+    } else if (op.op === "rename") {
+      intern(op.newId);
+      size += 4;
+    }
+  }
+
+  const buffer = new ArrayBuffer(size);
+  const view = new DataView(buffer);
+  view.setFloat64(0, seq, true);
+  view.setFloat64(8, translationStep, true);
+  view.setUint32(16, ids.size, true);
+  view.setUint32(20, operations.length, true);
+  let offset = HEADER_BYTES;
+  for (const id of ids.keys()) {
+    view.setUint16(offset, id.length, true);
+    offset += 2;
+    for (let c = 0; c < id.length; c++, offset += 2) {
+      view.setUint16(offset, id.charCodeAt(c), true);
+    }
+  }
+
+  const writeId = (id: string | null) => {
+    view.setUint32(offset, id === null ? NO_PARENT : ids.get(id)!, true);
+    offset += 4;
+  };
+  const writeTransform = (id: string, { translation, rotation }: TransformJSON) => {
+    for (const v of translation) {
+      const steps = Math.round(v / translationStep);
+      if (!(Math.abs(steps) <= 0x7fffffff)) {
+        throw new OutOfRangeError(
+          `Translation ${v} of frame "${id}" cannot be quantized with step ${translationStep}.`,
+          { frameIds: [id], operation: "encodeSyncPacket" },
+        );
+      }
+      view.setInt32(offset, steps, true);
+      offset += 4;
+    }
+    for (const v of rotation) {
+      view.setInt16(offset, Math.round(v * ROTATION_SCALE), true);
+      offset += 2;
+    }
+  };
+  for (const op of operations) {
+    view.setUint8(offset++, OPCODES.indexOf(op.op));
+    switch (op.op) {
+      case "add":
+      case "relink":
+        writeId(op.id);
+        writeId(op.parentId);
+        writeTransform(op.id, op.transform);
+        break;
+      case "update":
+        writeId(op.id);
+        writeTransform(op.id, op.transform);
+        break;
+      case "remove":
+        writeId(op.id);
+        break;
+      case "rename":
+        writeId(op.id);
+        writeId(op.newId);
+        break;
+      case "clear":
+        break;
+    }
+  }
+  return buffer;
+}
+
+/**
+ * Decode a buffer produced by {@link encodeSyncPacket}.  Rotations are
+ * renormalized after dequantization.
+ *
+ * @throws {TFError} (`INVALID_ARGUMENT`) if the buffer is truncated or
+ *                   malformed, e.g. holds a zero-norm rotation.
+ */
+export function decodeSyncPacket(buffer: ArrayBuffer): SyncPacket {
+  try {
+    return readSyncPacket(buffer);
+  } catch (e) {
+    // DataView reads past the end throw a plain RangeError.
+    throw e instanceof RangeError ? malformedPacket("Sync packet is truncated.") : e;
+  }
+}
+
+/** Error for a sync packet that {@link decodeSyncPacket} cannot read. */
+function malformedPacket(message: string): TFError {
+  return new TFError("INVALID_ARGUMENT", message, {
+    frameIds: [],
+    operation: "decodeSyncPacket",
+  });
+}
+
+/** Decode `buffer`, letting reads past its end throw. */
+function readSyncPacket(buffer: ArrayBuffer): SyncPacket {
+  const view = new DataView(buffer);
+  const seq = view.getFloat64(0, true);
+  const step = view.getFloat64(8, true);
+  const idCount = view.getUint32(16, true);
+  const opCount = view.getUint32(20, true);
+  let offset = HEADER_BYTES;
+  const ids: string[] = [];
+  for (let i = 0; i < idCount; i++) {
+    const length = view.getUint16(offset, true);
+    offset += 2;
+    let id = "";
+    for (let c = 0; c < length; c++, offset += 2) {
+      id += String.fromCharCode(view.getUint16(offset, true));
+    }
+    ids.push(id);
+  }
+
+  const readId = (): string => {
+    const index = view.getUint32(offset, true);
+    offset += 4;
+    if (index >= ids.length) {
+      throw malformedPacket(`Sync packet references unknown id #${index}.`);
+    }
+    return ids[index];
+  };
+  const readParent = (): string | null => {
+    if (view.getUint32(offset, true) === NO_PARENT) {
+      offset += 4;
+      return null;
+    }
+    return readId();
+  };
+  const readTransform = (): TransformJSON => {
+    const t: number[] = [];
+    for (let i = 0; i < 3; i++, offset += 4) {
+      t.push(view.getInt32(offset, true) * step);
+    }
+    const r: number[] = [];
+    for (let i = 0; i < 4; i++, offset += 2) {
+      r.push(view.getInt16(offset, true) / ROTATION_SCALE);
+    }
+    const norm = Math.hypot(...r);
+    if (norm === 0) {
+      throw malformedPacket("Sync packet contains a zero-norm rotation.");
+    }
+    return {
+      translation: [t[0], t[1], t[2]],
+      rotation: [r[0] / norm, r[1] / norm, r[2] / norm, r[3] / norm],
+    };
+  };
+
+  const operations: JournalOperation[] = [];
+  for (let i = 0; i < opCount; i++) {
+    const op = OPCODES[view.getUint8(offset++)];
+    switch (op) {
+      case "add":
+      case "relink": {
+        const id = readId();
+        const parentId = readParent();
+        operations.push({ op, id, parentId, transform: readTransform() });
+        break;
+      }
+      case "update": {
+        const id = readId();
+        operations.push({ op, id, transform: readTransform() });
+        break;
+      }
+      case "remove":
+        operations.push({ op, id: readId() });
+        break;
+      case "rename": {
+        const id = readId();
+        operations.push({ op, id, newId: readId() });
+        break;
+      }
+      case "clear":
+        operations.push({ op });
+        break;
+      default:
+        throw malformedPacket(`Sync packet contains an unknown opcode.`);
+    }
+  }
+  return { seq, operations };
+}
@@ -0,0 +1,21 @@
+import type { TFErrorCode, TFErrorContext } from "./types.js";
+
+/**
+ * Base class of the errors thrown by this package.  Besides the
+ * human-readable message, every error carries a stable {@link code} and the
+ * ids of the frames involved, so that callers (and telemetry) can tell
+ * errors apart without parsing messages, whose wording may change.
+ */
+export class TFError extends Error {
+  /** Stable machine-readable error code. */
+  readonly code: TFErrorCode;
+  /** Frames involved and, where known, the operation that failed. */
+  readonly context: TFErrorContext;
+
+  constructor(code: TFErrorCode, message: string, context: TFErrorContext = { frameIds: [] }) {
+    super(message);
+    this.name = "TFError";
+    this.code = code;
+    this.context = context;
+  }
+}
@@ -0,0 +1,215 @@
+import { Transform } from "./math/Transform.js";
+import { TFTree } from "./TFTree.js";
+import { TFError } from "./TFError.js";
+import { FrameNotFoundError } from "./FrameNotFoundError.js";
+import { DisconnectedFramesError } from "./DisconnectedFramesError.js";
+import type { ForestBridge, ForestFrame } from "./types.js";
+
+/**
+ * TFForest – a set of named {@link TFTree}s linked by bridge frames.
+ *
+ * Scenes with several robots or mapping sessions often don't fit a single
+ * tree: each keeps its own tree, and a bridge records how a frame of one
+ * tree sits relative to a frame of another (e.g. where each robot's `map`
+ * lies in a shared `world`).  {@link getTransform} routes across as many
+ * bridges as needed.
+ *
+ * @example
+ * ```ts
+ * const forest = new TFForest();
+ * forest.addTree("robot1", robot1Tree);
+ * forest.addTree("robot2", robot2Tree);
+ * forest.addBridge(
+ *   { tree: "robot1", frame: "map" },
+ *   { tree: "robot2", frame: "map" },
+ *   new Transform(new Vec3(10, 0, 0)),
+ * );
+ * const t = forest.getTransform(
+ *   { tree: "robot1", frame: "base_link" },
+ *   { tree: "robot2", frame: "camera" },
+ * );
+ * ```
+ */
+export class TFForest {
+  private readonly trees = new Map<string, TFTree>();
+  private readonly bridgeList: ForestBridge[] = [];
+
+  // ── trees ──────────────────────────────────────────────────────────────────
+
+  /**
+   * Register a tree under `name`.
+   *
+   * @param tree Tree to manage; a new empty one is created when omitted.
+   * @returns The registered tree.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `name` is already in use.
+   */
+  addTree(name: string, tree: TFTree = new TFTree()): TFTree {
+    if (this.trees.has(name)) {
+      throw new TFError("INVALID_ARGUMENT", `Tree "${name}" is already registered.`, {
+        frameIds: [],
+        operation: "addTree",
+      });
+    }
+    this.trees.set(name, tree);
+    return tree;
+  }
+
+  /**
+   * Returns the tree registered under `name`.
+   *
+   * @throws {TFError} (`INVALID_ARGUMENT`) if no tree has that name.
+   */
+  getTree(name: string): TFTree {
+    const tree = this.trees.get(name);
+    if (tree === undefined) {
+      throw new TFError("INVALID_ARGUMENT", `Tree "${name}" is not registered.`, {
+        frameIds: [],
+        operation: "getTree",
+      });
+    }
+    return tree;
+  }
+
+  /** Returns `true` if a tree is registered under `name`. */
+  hasTree(name: string): boolean {
+    return this.trees.has(name);
+  }
+
+  /** Returns the names of all trees, in registration order. */
+  treeNames(): string[] {
+    return Array.from(this.trees.keys());
+  }
+
+  /**
+   * Unregister a tree, together with every bridge touching it.
+   *
+   * @throws {TFError} (`INVALID_ARGUMENT`) if no tree has that name.
+   */
+  removeTree(name: string): void {
+    this.getTree(name);
+    this.trees.delete(name);
+    this.removeBridges((bridge) => bridge.a.tree === name || bridge.b.tree === name);
+  }
+
+  // ── bridges ────────────────────────────────────────────────────────────────
+
+  /**
+   * Link frame `a` of one tree to frame `b` of another.  `transform` is the
+   * pose of `b` relative to `a`, as for a child added with
+   * {@link TFTree.addFrame}.
+   *
+   * @throws {TFError} (`INVALID_ARGUMENT`) if either tree is not registered
+   *                   or both frames are in the same tree.
+   * @throws {FrameNotFoundError} if either frame is not registered in its
+   *                              tree.
+   */
+  addBridge(a: ForestFrame, b: ForestFrame, transform: Transform = Transform.identity()): void {
+    for (const { tree, frame } of [a, b]) {
+      if (!this.getTree(tree).hasFrame(frame)) {
+        throw new FrameNotFoundError(frame);
+      }
+    }
+    if (a.tree === b.tree) {
+      throw new TFError("INVALID_ARGUMENT", `A bridge must link two different trees.`, {
+        frameIds: [a.frame, b.frame],
+        operation: "addBridge",
+      });
+    }
+    this.bridgeList.push({ a: { ...a }, b: { ...b }, transform });
+  }
+
+  /**
+   * Remove every bridge between trees `treeA` and `treeB`.
+   *
+   * @returns The number of bridges removed.
+   */
+  removeBridge(treeA: string, treeB: string): number {
+    return this.removeBridges(
+      ({ a, b }) =>
+        (a.tree === treeA && b.tree === treeB) || (a.tree === treeB && b.tree === treeA),
+    );
+  }
+
+  /** Returns every bridge, in the order added. */
+  bridges(): ForestBridge[] {
+    return this.bridgeList.slice();
+  }
+
+  // ── query ──────────────────────────────────────────────────────────────────
+
+  /**
+   * Compute the transform between frames of (possibly) different trees,
+   * with the same convention as {@link TFTree.getTransform}.  Within one
+   * tree the query is delegated to it; otherwise the fewest bridges linking
+   * the two trees are followed.
+   *
+   * @throws {TFError} (`INVALID_ARGUMENT`) if either tree is not registered.
+   * @throws {FrameNotFoundError} if a frame is not registered in its tree.
+   * @throws {DisconnectedFramesError} if no chain of bridges links the frames.
+   */
+  getTransform(from: ForestFrame, to: ForestFrame): Transform {
+    const route = this.route(from.tree, to.tree);
+    if (route === undefined) {
+      throw new DisconnectedFramesError(`${from.tree}:${from.frame}`, `${to.tree}:${to.frame}`);
+    }
+    let result = Transform.identity();
+    let current = from;
+    for (const { a, b, transform } of route) {
+      // Bridges can be crossed in either direction.
+      const [exit, entry, hop] =
+        a.tree === current.tree ? [a, b, transform] : [b, a, transform.invert()];
+      result = result
+        .compose(this.getTree(current.tree).getTransform(current.frame, exit.frame))
+        .compose(hop);
+      current = entry;
+    }
+    return result.compose(this.getTree(to.tree).getTransform(current.frame, to.frame));
+  }
+
+  /** Returns `true` if a chain of bridges links the two trees. */
+  areTreesLinked(treeA: string, treeB: string): boolean {
+    return this.route(treeA, treeB) !== undefined;
+  }
+
+  // ── private helpers ────────────────────────────────────────────────────────
+
+  /**
+   * Breadth-first search over the bridges for the shortest sequence linking
+   * `fromTree` to `toTree`, or `undefined` if there is none.
+   */
+  private route(fromTree: string, toTree: string): ForestBridge[] | undefined {
+    this.getTree(fromTree);
+    this.getTree(toTree);
+    const via = new Map<string, ForestBridge | undefined>([[fromTree, undefined]]);
+    const queue = [fromTree];
+    for (let i = 0; i < queue.length && !via.has(toTree); i++) {
+      const tree = queue[i];
+      for (const bridge of this.bridgeList) {
+        const next =
+          bridge.a.tree === tree ? bridge.b.tree : bridge.b.tree === tree ? bridge.a.tree : tree;
+        if (!via.has(next)) {
+          via.set(next, bridge);
+          queue.push(next);
+        }
+      }
+    }
+    if (!via.has(toTree)) {
+      return undefined;
+    }
+    const route: ForestBridge[] = [];
+    for (let tree = toTree; tree !== fromTree; ) {
+      const bridge = via.get(tree)!;
+      route.unshift(bridge);
+      tree = bridge.a.tree === tree ? bridge.b.tree : bridge.a.tree;
+    }
+    return route;
+  }
+
+  /** Remove the bridges matching `predicate`, returning how many there were. */
+  private removeBridges(predicate: (bridge: ForestBridge) => boolean): number {
+    const before = this.bridgeList.length;
+    const kept = this.bridgeList.filter((bridge) => !predicate(bridge));
+    this.bridgeList.splice(0, before, ...kept);
+    return before - kept.length;
+  }
+}
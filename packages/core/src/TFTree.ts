@@ -5,9 +5,559 @@ import {
   type FrameNode,
   type ITransformTree,
   type TFTreeJSON,
+  type FrameNodeJSON,
+  type JSONRepair,
+  type RepairedJSON,
   type ChangeCallback,
+  type ChangeSubscriptionOptions,
+  type BatchChangeCallback,
+  type ChangeBatch,
+  type BatchSubscriptionOptions,
+  type ChangeStreamOptions,
+  type StructureChangeCallback,
+  type StructureEvent,
+  type FrameResolver,
+  type RegionCallback,
+  type ConnectivityCallback,
+  type TraversalOrder,
+  type CoveringSubtree,
+  type AABB,
+  type OBB,
+  type FloatArray,
+  type PointCloudOptions,
+  type CameraIntrinsics,
+  type JointType,
+  type JointOptions,
+  type JointLimits,
+  type Joint,
+  type JointLimitCallback,
+  type DhLink,
+  type ChainLink,
+  type KinematicChain,
+  type IkOptions,
+  type IkFailureReason,
+  type IkResult,
+  type DhChainOptions,
+  type Calibration,
+  type CalibrationMetadata,
+  type CalibrationAuditOptions,
+  type CalibrationIssue,
+  type HandEyeOptions,
+  type GeoAnchor,
+  type HandEyeResult,
+  type DhConvention,
+  type ProjectedPoints,
+  type Ray,
+  type FrameDistance,
+  type FrameComponent,
+  type ValidationIssue,
+  type ValidationReport,
+  type Diagnostic,
+  type CacheMismatch,
+  type DiagnoseOptions,
+  type DebugDumpOptions,
+  type RemoveFrameOptions,
+  type ExtractSubtreeOptions,
+  type ReattachOptions,
+  type RotationNormMode,
+  type FrameIdPolicy,
+  type RosNamingOptions,
+  type TreeQuotas,
+  type NamespacePolicy,
+  type NamespaceStats,
+  type TransformJSON,
+  type JournalEntry,
+  type JournalOperation,
+  type TreeChange,
+  type BatchOptions,
+  type SnapshotDiffOptions,
+  type SnapshotDifference,
+  type MergeOptions,
+  type MergeReport,
+  type MergeConflict,
+  type MergeSide,
+  type MergeResolution,
+  type SyncOptions,
+  type ExportFilter,
+  type UpdateOptions,
+  type AuthorityPolicy,
+  type WatchedPairChanges,
+  type WatchPriority,
 } from "./types.js";
+import { TFError } from "./TFError.js";
+import { OutOfRangeError } from "./OutOfRangeError.js";
 import { CycleDetectedError } from "./CycleDetectedError.js";
+import { FrameLockedError } from "./FrameLockedError.js";
+import { InvalidTransformError } from "./InvalidTransformError.js";
+import { InvalidFrameIdError } from "./InvalidFrameIdError.js";
+import { QuotaExceededError } from "./QuotaExceededError.js";
+import { UnauthorizedSourceError } from "./UnauthorizedSourceError.js";
+import { ReadOnlyNamespaceError } from "./ReadOnlyNamespaceError.js";
+import { FrameNotFoundError } from "./FrameNotFoundError.js";
+import { DuplicateFrameError } from "./DuplicateFrameError.js";
+import { DisconnectedFramesError } from "./DisconnectedFramesError.js";
+import { ChangeStream } from "./ChangeStream.js";
+import { TFTreeSnapshot } from "./TFTreeSnapshot.js";
+import { encodeChangePacket } from "./ChangePacket.js";
+import { encodeSyncPacket, decodeSyncPacket } from "./SyncPacket.js";
+
+// ── internal helpers ──────────────────────────────────────────────────────────
+
+/**
+ * Compile a glob-style frame pattern into an anchored regular expression.
+ *
+ * - `**` matches any sequence of characters, including `/`.
+ * - `*` matches any sequence of characters within a single `/`-separated segment.
+ * - `?` matches exactly one character other than `/`.
+ *
+ * All other characters match literally.
+ */
+function compileFramePattern(pattern: string): RegExp {
+  let source = "";
+  for (let i = 0; i < pattern.length; i++) {
+    const ch = pattern[i];
+    if (ch === "*") {
+      if (pattern[i + 1] === "*") {
+        source += ".*";
+        i++;
+      } else {
+        source += "[^/]*";
+      }
+    } else if (ch === "?") {
+      source += "[^/]";
+    } else {
+      source += ch.replace(/[.+^${}()|[\]\\]/g, "\\$&");
+    }
+  }
+  return new RegExp(`^${source}$`);
+}
+
+/** Convert a {@link Transform} to its JSON-serializable form. */
+function transformToJSON(transform: Transform): TransformJSON {
+  return {
+    translation: transform.translation.toArray(),
+    rotation: transform.rotation.toArray(),
+  };
+}
+
+/** Rebuild a {@link Transform} from its JSON-serializable form. */
+function transformFromJSON(json: TransformJSON): Transform {
+  return new Transform(Vec3.fromArray(json.translation), Quaternion.fromArray(json.rotation));
+}
+
+/** Returns `true` if both serialized transforms are component-wise identical. */
+function sameTransformJSON(a: TransformJSON, b: TransformJSON): boolean {
+  return (
+    a.translation.every((v, i) => v === b.translation[i]) &&
+    a.rotation.every((v, i) => v === b.rotation[i])
+  );
+}
+
+/**
+ * Wrap `callback` so that it only fires once the world transform has moved
+ * by more than one of the thresholds since the last delivered notification.
+ */
+function thresholdFilter(
+  callback: ChangeCallback,
+  initial: Transform,
+  { minTranslation = 0, minRotation = 0 }: ChangeSubscriptionOptions,
+): ChangeCallback {
+  let delivered = initial;
+  return (frameId, { current }) => {
+    const moved = current.translation.subtract(delivered.translation).length();
+    const turned = current.rotation.angleTo(delivered.rotation);
+    if (moved <= minTranslation && turned <= minRotation) {
+      return;
+    }
+    const previous = delivered;
+    delivered = current;
+    callback(frameId, { previous, current });
+  };
+}
+
+/**
+ * Returns the name of the first `NaN` or infinite component of `transform`,
+ * e.g. `"translation.y"`, or `undefined` if every component is finite.
+ */
+function nonFiniteComponent({ translation: t, rotation: r }: Transform): string | undefined {
+  const components: Array<[string, number]> = [
+    ["translation.x", t.x],
+    ["translation.y", t.y],
+    ["translation.z", t.z],
+    ["rotation.x", r.x],
+    ["rotation.y", r.y],
+    ["rotation.z", r.z],
+    ["rotation.w", r.w],
+  ];
+  return components.find(([, value]) => !Number.isFinite(value))?.[0];
+}
+
+/**
+ * Apply the column-major matrix `m` to the points of the interleaved buffer
+ * `src`, writing them to the same positions of `dst` (which may be `src`).
+ */
+function transformPacked(
+  m: Float32Array,
+  src: FloatArray,
+  dst: FloatArray,
+  stride: number,
+  offset: number,
+): void {
+  for (let i = offset; i < src.length; i += stride) {
+    const x = src[i];
+    const y = src[i + 1];
+    const z = src[i + 2];
+    dst[i] = m[0] * x + m[4] * y + m[8] * z + m[12];
+    dst[i + 1] = m[1] * x + m[5] * y + m[9] * z + m[13];
+    dst[i + 2] = m[2] * x + m[6] * y + m[10] * z + m[14];
+  }
+}
+
+/**
+ * Drop every `update` overwritten by a later `update` or `relink` of the same
+ * frame with no other structural operation in between.
+ */
+function compactOperations(operations: readonly JournalOperation[]): JournalOperation[] {
+  const overwritten = new Set<string>();
+  const kept: JournalOperation[] = [];
+  for (let i = operations.length - 1; i >= 0; i--) {
+    const op = operations[i];
+    if (op.op === "update" || op.op === "relink") {
+      if (op.op === "update" && overwritten.has(op.id)) continue;
+      overwritten.add(op.id);
+    } else {
+      overwritten.clear();
+    }
+    kept.push(op);
+  }
+  return kept.reverse();
+}
+
+/** Number of position coordinates of each joint type. */
+const JOINT_DOF: Record<JointType, number> = { revolute: 1, prismatic: 1, planar: 3, floating: 7 };
+
+/** Position of a joint of `type` at which its frame is at the origin. */
+function zeroJointPosition(type: JointType): number[] {
+  return type === "floating" ? [0, 0, 0, 0, 0, 0, 1] : new Array<number>(JOINT_DOF[type]).fill(0);
+}
+
+/** Motion of `joint` at position `p`, relative to its origin. */
+function jointMotion({ type, axis }: Joint, p: readonly number[]): Transform {
+  switch (type) {
+    case "revolute":
+      return new Transform(Vec3.zero(), Quaternion.fromAxisAngle(axis, p[0]));
+    case "prismatic":
+      return new Transform(axis.scale(p[0]));
+    case "planar": {
+      const e = Math.abs(axis.x) < 0.9 ? new Vec3(1, 0, 0) : new Vec3(0, 1, 0);
+      const u = e.subtract(axis.scale(e.dot(axis))).normalize();
+      const v = axis.cross(u);
+      return new Transform(u.scale(p[0]).add(v.scale(p[1])), Quaternion.fromAxisAngle(axis, p[2]));
+    }
+    case "floating":
+      return new Transform(
+        new Vec3(p[0], p[1], p[2]),
+        new Quaternion(p[3], p[4], p[5], p[6]).normalize(),
+      );
+  }
+}
+
+/** Transform of a link relative to the previous one, from its DH parameters. */
+function dhTransform({ a, alpha, d, theta }: DhLink, convention: DhConvention): Transform {
+  const z = new Transform(new Vec3(0, 0, d), Quaternion.fromAxisAngle(new Vec3(0, 0, 1), theta));
+  const x = new Transform(new Vec3(a, 0, 0), Quaternion.fromAxisAngle(new Vec3(1, 0, 0), alpha));
+  return convention === "standard" ? z.compose(x) : x.compose(z);
+}
+
+/** Rotation vector (unit axis times angle) of `q`, the shorter way round. */
+function rotationVector(q: Quaternion): Vec3 {
+  const sign = q.w < 0 ? -1 : 1;
+  const v = new Vec3(q.x * sign, q.y * sign, q.z * sign);
+  const sin = v.length();
+  return sin < 1e-12 ? v.scale(2) : v.scale((2 * Math.atan2(sin, q.w * sign)) / sin);
+}
+
+/**
+ * Solve the linear system `a · x = b` by Gaussian elimination with partial
+ * pivoting.  `a` and `b` are overwritten.
+ */
+function solveLinearSystem(a: number[][], b: number[]): number[] {
+  const n = b.length;
+  for (let col = 0; col < n; col++) {
+    let pivot = col;
+    for (let row = col + 1; row < n; row++) {
+      if (Math.abs(a[row][col]) > Math.abs(a[pivot][col])) pivot = row;
+    }
+    [a[col], a[pivot]] = [a[pivot], a[col]];
+    [b[col], b[pivot]] = [b[pivot], b[col]];
+    for (let row = col + 1; row < n; row++) {
+      const f = a[row][col] / a[col][col];
+      for (let k = col; k < n; k++) a[row][k] -= f * a[col][k];
+      b[row] -= f * b[col];
+    }
+  }
+  const x = new Array<number>(n).fill(0);
+  for (let row = n - 1; row >= 0; row--) {
+    let sum = b[row];
+    for (let k = row + 1; k < n; k++) sum -= a[row][k] * x[k];
+    x[row] = sum / a[row][row];
+  }
+  return x;
+}
+
+/**
+ * Eigen-decomposition of the symmetric matrix `m` by cyclic Jacobi
+ * rotations.  Returns the eigenvalues and, in the same order, the unit
+ * eigenvectors.
+ */
+function symmetricEigen(m: readonly number[][]): { values: number[]; vectors: number[][] } {
+  const n = m.length;
+  const a = m.map((row) => row.slice());
+  const v = a.map((_, i) => a.map((_, j) => (i === j ? 1 : 0)));
+  // Multiply columns `p` and `q` of `x` by the Jacobi rotation (c, s).
+  const rotateColumns = (x: number[][], p: number, q: number, c: number, s: number) => {
+    for (const row of x) {
+      [row[p], row[q]] = [c * row[p] - s * row[q], s * row[p] + c * row[q]];
+    }
+  };
+  for (let sweep = 0; sweep < 50; sweep++) {
+    let off = 0;
+    let diagonal = 0;
+    for (let p = 0; p < n; p++) {
+      diagonal += a[p][p] ** 2;
+      for (let q = p + 1; q < n; q++) off += a[p][q] ** 2;
+    }
+    if (off <= 1e-30 * diagonal) break;
+    for (let p = 0; p < n; p++) {
+      for (let q = p + 1; q < n; q++) {
+        if (a[p][q] === 0) continue;
+        const theta = (a[q][q] - a[p][p]) / (2 * a[p][q]);
+        const t = (theta < 0 ? -1 : 1) / (Math.abs(theta) + Math.sqrt(theta * theta + 1));
+        const c = 1 / Math.sqrt(t * t + 1);
+        const s = t * c;
+        // a ← Jᵀ a J, v ← v J
+        rotateColumns(a, p, q, c, s);
+        [a[p], a[q]] = [
+          a[p].map((x, k) => c * x - s * a[q][k]),
+          a[q].map((x, k) => s * a[p][k] + c * x),
+        ];
+        rotateColumns(v, p, q, c, s);
+      }
+    }
+  }
+  return { values: a.map((row, i) => row[i]), vectors: a.map((_, j) => v.map((row) => row[j])) };
+}
+
+/** Hamilton product `a · b` in double precision, unlike {@link Quaternion.multiply}. */
+function multiplyExact(a: Quaternion, b: Quaternion): Quaternion {
+  return new Quaternion(
+    a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+    a.w * b.y + a.y * b.w + a.z * b.x - a.x * b.z,
+    a.w * b.z + a.z * b.w + a.x * b.y - a.y * b.x,
+    a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+  );
+}
+
+/** `v` rotated by the unit quaternion `q`, in double precision. */
+function rotateExact(q: Quaternion, v: Vec3): Vec3 {
+  // v + 2w(u × v) + 2u × (u × v)
+  const ux = 2 * (q.y * v.z - q.z * v.y);
+  const uy = 2 * (q.z * v.x - q.x * v.z);
+  const uz = 2 * (q.x * v.y - q.y * v.x);
+  return new Vec3(
+    v.x + q.w * ux + (q.y * uz - q.z * uy),
+    v.y + q.w * uy + (q.z * ux - q.x * uz),
+    v.z + q.w * uz + (q.x * uy - q.y * ux),
+  );
+}
+
+/**
+ * Pose reached from `pose` by moving for `dt` at the constant twist
+ * (`linear`, `angular`) expressed in the moving frame itself: the
+ * exponential map of SE(3), evaluated in double precision since the
+ * gl-matrix backed math classes round to single precision.
+ */
+function twistStep(pose: Transform, linear: Vec3, angular: Vec3, dt: number): Transform {
+  const { translation: t, rotation: q } = pose;
+  const [px, py, pz] = [angular.x * dt, angular.y * dt, angular.z * dt];
+  const [rx, ry, rz] = [linear.x * dt, linear.y * dt, linear.z * dt];
+  const theta = Math.hypot(px, py, pz);
+  const theta2 = theta * theta;
+  // Coefficients of the left Jacobian I + b·[φ]× + c·[φ]×² and of the
+  // rotation's quaternion, by their Taylor series near zero.
+  const small = theta < 1e-4;
+  const b = small ? 0.5 - theta2 / 24 : (1 - Math.cos(theta)) / theta2;
+  const c = small ? 1 / 6 - theta2 / 120 : (theta - Math.sin(theta)) / (theta2 * theta);
+  const s = small ? 0.5 - theta2 / 48 : Math.sin(theta / 2) / theta;
+  // φ × ρ and φ × (φ × ρ)
+  const [ax, ay, az] = [py * rz - pz * ry, pz * rx - px * rz, px * ry - py * rx];
+  const [bx, by, bz] = [py * az - pz * ay, pz * ax - px * az, px * ay - py * ax];
+  const motion = new Vec3(rx + b * ax + c * bx, ry + b * ay + c * by, rz + b * az + c * bz);
+  const step = rotateExact(q, motion);
+  const turn = new Quaternion(px * s, py * s, pz * s, Math.cos(theta / 2));
+  const { x, y, z, w } = multiplyExact(q, turn);
+  const norm = Math.hypot(x, y, z, w);
+  return new Transform(
+    new Vec3(t.x + step.x, t.y + step.y, t.z + step.z),
+    new Quaternion(x / norm, y / norm, z / norm, w / norm),
+  );
+}
+
+/** WGS84 semi-major axis, in meters. */
+const WGS84_A = 6378137;
+/** WGS84 first eccentricity squared. */
+const WGS84_E2 = (1 / 298.257223563) * (2 - 1 / 298.257223563);
+
+/**
+ * Pose in ECEF (earth-centred, earth-fixed) coordinates of the local
+ * east-north-up frame at the given coordinates, turned counterclockwise
+ * about up by its heading.
+ */
+function geoAnchorPose({ latitude, longitude, altitude, heading }: GeoAnchor): Transform {
+  const phi = (latitude * Math.PI) / 180;
+  const lambda = (longitude * Math.PI) / 180;
+  const n = WGS84_A / Math.sqrt(1 - WGS84_E2 * Math.sin(phi) ** 2);
+  const translation = new Vec3(
+    (n + altitude) * Math.cos(phi) * Math.cos(lambda),
+    (n + altitude) * Math.cos(phi) * Math.sin(lambda),
+    (n * (1 - WGS84_E2) + altitude) * Math.sin(phi),
+  );
+  // Rz(λ + 90°) · Rx(90° − φ) · Rz(heading)
+  const aboutZ = (angle: number) => new Quaternion(0, 0, Math.sin(angle / 2), Math.cos(angle / 2));
+  const tilt = (Math.PI / 2 - phi) / 2;
+  const up = multiplyExact(
+    aboutZ(lambda + Math.PI / 2),
+    new Quaternion(Math.sin(tilt), 0, 0, Math.cos(tilt)),
+  );
+  const rotation = multiplyExact(up, aboutZ((heading * Math.PI) / 180));
+  return new Transform(translation, rotation);
+}
+
+/**
+ * Apply the ROS naming rules `rosNaming`, if enabled, then the longest
+ * matching prefix of `remaps` to `id` (see {@link TFTree.setRosNaming} and
+ * {@link TFTree.setRemap}).
+ */
+function normalizeFrameId(
+  id: string,
+  rosNaming: RosNamingOptions | undefined,
+  remaps: ReadonlyMap<string, string>,
+): string {
+  if (rosNaming !== undefined) {
+    const prefix = rosNaming.tfPrefix;
+    if (id.startsWith("/")) {
+      id = id.replace(/^\/+/, "");
+    } else if (prefix !== undefined && prefix !== "" && !id.startsWith(`${prefix}/`)) {
+      id = `${prefix}/${id}`;
+    }
+  }
+  let match: string | undefined;
+  for (const fromPrefix of remaps.keys()) {
+    if (id.startsWith(fromPrefix) && fromPrefix.length > (match?.length ?? -1)) {
+      match = fromPrefix;
+    }
+  }
+  return match === undefined ? id : remaps.get(match)! + id.slice(match.length);
+}
+
+/**
+ * Resolve a looked-up `id` as {@link normalizeFrameId} does, except that a
+ * relative id falls back to its absolute form when only that one is `known`
+ * — so that `"map"` finds a shared `"/map"` under a tf_prefix.
+ */
+function resolveFrameId(
+  id: string,
+  rosNaming: RosNamingOptions | undefined,
+  remaps: ReadonlyMap<string, string>,
+  known: (id: string) => boolean,
+): string {
+  const normalized = normalizeFrameId(id, rosNaming, remaps);
+  if (rosNaming?.tfPrefix === undefined || id.startsWith("/") || known(normalized)) {
+    return normalized;
+  }
+  const absolute = normalizeFrameId(`/${id}`, rosNaming, remaps);
+  return known(absolute) ? absolute : normalized;
+}
+
+/** Move the value stored under `from` (if any) to `to`. */
+function moveKey<V>(map: Map<string, V>, from: string, to: string): void {
+  if (map.has(from)) {
+    map.set(to, map.get(from)!);
+    map.delete(from);
+  }
+}
+
+function assignMap<K, V>(target: Map<K, V>, source: ReadonlyMap<K, V>): void {
+  target.clear();
+  for (const [key, value] of source) {
+    target.set(key, value);
+  }
+}
+
+/** Where a frame detached with {@link TFTree.detach} used to be. */
+interface DetachedFrame {
+  readonly parentId: string;
+  readonly worldTransform: Transform;
+}
+
+/** A namespace registered with {@link TFTree.setNamespacePolicy}. */
+interface Namespace {
+  policy: NamespacePolicy;
+  mutations: number;
+  rejected: number;
+}
+
+/** A `(from, to)` pair registered with {@link TFTree.watchTransform}. */
+interface WatchedPair {
+  from: string;
+  to: string;
+  readonly priority: WatchPriority;
+  /** Value reported by the last {@link TFTree.flushWatches}, if any. */
+  last: Transform | undefined;
+  /**
+   * Current value of a `"high"` priority pair, kept up to date on every
+   * change; `undefined` while the frames are not connected.
+   */
+  current: Transform | undefined;
+}
+
+/**
+ * State captured by {@link TFTree.begin} so that {@link TFTree.rollback} can
+ * restore the tree exactly, plus the change notifications deferred until
+ * {@link TFTree.commit}.
+ */
+interface TransactionState {
+  readonly frames: FrameNode[];
+  readonly children: Map<string, string[]>;
+  readonly subtreeSizes: Map<string, number>;
+  /**
+   * The listener set of each frame at `begin()`.  Subscriptions are not
+   * transactional: the sets are shared, and rollback only re-binds those of
+   * frames removed or renamed since then.
+   */
+  readonly listenerSets: Map<string, Set<ChangeCallback>>;
+  /** Batch listeners dropped by {@link TFTree.clear} since `begin()`. */
+  readonly clearedBatchListeners: Set<BatchChangeCallback>;
+  readonly aliases: Map<string, string>;
+  readonly lockedFrames: Set<string>;
+  readonly updatedFrames: Set<string>;
+  readonly detachedFrames: Map<string, DetachedFrame>;
+  readonly cameraIntrinsics: Map<string, CameraIntrinsics>;
+  readonly calibrations: Map<string, Calibration>;
+  readonly geoAnchors: Map<string, GeoAnchor>;
+  readonly watches: Array<WatchedPair | undefined>;
+  readonly namespaces: Map<string, Namespace>;
+  readonly joints: Map<string, Joint>;
+  readonly frameOwners: Map<string, string>;
+  readonly lastSources: Map<string, string>;
+  readonly modifiedAt: Map<string, number>;
+  readonly pendingNotifications: Set<string>;
+  readonly pendingStructureEvents: StructureEvent[];
+  readonly pendingJointLimitEvents: Array<[joint: Joint, requested: number]>;
+  readonly journalLength: number;
+  readonly journalSeq: number;
+  readonly forkEditCount: number;
+}
 
 /**
  * TFTree – a directed acyclic graph (tree) of named reference frames.
@@ -32,6 +582,59 @@ export class TFTree implements ITransformTree {
   private readonly worldTransformCache = new Map<string, Transform>();
   private readonly childrenMap = new Map<string, Set<string>>();
   private readonly changeListeners = new Map<string, Set<ChangeCallback>>();
+  private readonly batchChangeListeners = new Set<BatchChangeCallback>();
+  private readonly structureListeners = new Set<StructureChangeCallback>();
+  /** World transform last reported to each frame's change listeners. */
+  private readonly notifiedWorldTransforms = new Map<string, Transform>();
+  private readonly subtreeSizes = new Map<string, number>();
+  private readonly frameVersions = new Map<string, number>();
+  private versionClock = 0;
+  private clock: () => number = Date.now;
+  private readonly modifiedAt = new Map<string, number>();
+  private revisionCounter = 0;
+  private lastSnapshot: TFTreeSnapshot | undefined;
+  private frontSnapshot = new TFTreeSnapshot(0, new Map(), new Map());
+  private readonly aliases = new Map<string, string>();
+  private readonly lockedFrames = new Set<string>();
+  /** Frames whose transform was updated at least once after registration. */
+  private readonly updatedFrames = new Set<string>();
+  private readonly detachedFrames = new Map<string, DetachedFrame>();
+  private readonly cameraIntrinsics = new Map<string, CameraIntrinsics>();
+  private readonly calibrations = new Map<string, Calibration>();
+  private readonly geoAnchors = new Map<string, GeoAnchor>();
+  private readonly joints = new Map<string, Joint>();
+  private readonly jointLimitListeners = new Set<JointLimitCallback>();
+  private readonly frameOwners = new Map<string, string>();
+  private readonly lastSources = new Map<string, string>();
+  private authorityPolicy: AuthorityPolicy = "record";
+  private readonly namespaces = new Map<string, Namespace>();
+  private readonly watches: Array<WatchedPair | undefined> = [];
+  private readonly watchDirty = new Set<string>();
+  private openTransaction: TransactionState | undefined;
+  /** Changes held back while notifications are deferred; `undefined` otherwise. */
+  private deferredNotifications: Set<string> | undefined;
+  private journal: JournalEntry[] | undefined;
+  private journalSeq = 0;
+  /** The tree this one was {@link fork}ed from, if any. */
+  private forkBase: TFTree | undefined;
+  /** Edits made since forking (or the last promote/discard), in order. */
+  private readonly forkEdits: JournalOperation[] = [];
+  private eventSeq = 0;
+  private eventLog: ChangeBatch[] | undefined;
+  private eventLogCapacity = 0;
+  private validateInputs = true;
+  private rotationNormMode: RotationNormMode = "off";
+  private rotationNormEpsilon = 1e-6;
+  private frameIdPolicy: FrameIdPolicy | undefined;
+  private rosNaming: RosNamingOptions | undefined;
+  private readonly remaps = new Map<string, string>();
+  private frameResolver: FrameResolver | undefined;
+  private exportFilter: ExportFilter | undefined;
+  /** Ids being loaded from the frame resolver, to stop on cyclic answers. */
+  private readonly resolving = new Set<string>();
+  private quotas: TreeQuotas = {};
+  private debugChecks = false;
+  private debugCheckEpsilon = 1e-4;
 
   // ── frame registration ─────────────────────────────────────────────────────
 
@@ -39,19 +642,45 @@ export class TFTree implements ITransformTree {
    * Register a new frame.
    *
    * @param id       Unique identifier for this frame.
-   * @param parentId Id of the parent frame.  Omit (or pass `undefined`) for a
-   *                 root frame.  There may be multiple root frames.
+   * @param parentId Id (or alias) of the parent frame.  Omit (or pass
+   *                 `undefined`) for a root frame.  There may be multiple root
+   *                 frames.
    * @param transform Transform expressing this frame relative to its parent.
    *                  Defaults to the identity transform.
-   * @throws {Error} if `id` is already registered or `parentId` is not found.
+   * @throws {DuplicateFrameError} if `id` is already registered (as a frame or
+   *                              an alias).
+   * @throws {InvalidFrameIdError} if `id` violates the frame id policy.
+   * @throws {FrameNotFoundError} if `parentId` is not found, neither in the
+   *                              tree nor by the {@link setFrameResolver}
+   *                              resolver.
+   * @throws {QuotaExceededError} if the frame would exceed a quota.
    * @throws {CycleDetectedError} if adding this frame would introduce a cycle.
+   * @throws {InvalidTransformError} if `transform` has a non-finite component
+   *                                 or violates the rotation norm policy.
    */
   addFrame(id: string, parentId?: string, transform: Transform = Transform.identity()): void {
-    if (this.frames.has(id)) {
-      throw new Error(`Frame "${id}" is already registered.`);
+    this.insertFrame(
+      this.normalizeId(id),
+      parentId === undefined ? undefined : this.resolveAlias(parentId),
+      transform,
+    );
+  }
+
+  /**
+   * {@link addFrame} with `id` already normalized and `parentId` already
+   * resolved, e.g. from the journal: neither is normalized again.
+   */
+  private insertFrame(id: string, parentId: string | undefined, transform: Transform): void {
+    if (this.frames.has(id) || this.aliases.has(id)) {
+      throw new DuplicateFrameError(id);
     }
-    if (parentId !== undefined && !this.frames.has(parentId)) {
-      throw new Error(`Parent frame "${parentId}" not found. Register parents before children.`);
+    this.assertValidId(id);
+    transform = this.checkTransform(id, transform);
+    if (parentId !== undefined && !this.frames.has(parentId) && !this.loadFrame(parentId)) {
+      throw new FrameNotFoundError(
+        parentId,
+        `Parent frame "${parentId}" not found. Register parents before children.`,
+      );
     }
 
     // Check that the parent's chain to root does not already contain `id`,
@@ -65,6 +694,12 @@ export class TFTree implements ITransformTree {
         current = this.frames.get(current)?.parentId;
       }
     }
+    this.assertQuota("maxFrames", this.frames.size + 1, [id]);
+    this.assertNamespaceAccepts(id);
+    if (parentId !== undefined) {
+      this.assertQuota("maxChildren", this.childrenMap.get(parentId)!.size + 1, [parentId]);
+      this.assertQuota("maxDepth", this.chainToRoot(parentId).length, [id]);
+    }
 
     const node: FrameNode =
       parentId !== undefined ? { id, parentId, transform } : { id, transform };
@@ -77,19 +712,47 @@ export class TFTree implements ITransformTree {
     if (parentId !== undefined) {
       this.childrenMap.get(parentId)!.add(id);
     }
+    this.subtreeSizes.set(id, 1);
+    this.frameVersions.set(id, ++this.versionClock);
+    this.adjustSubtreeSizes(parentId, 1);
+    this.record({
+      op: "add",
+      id,
+      parentId: parentId ?? null,
+      transform: transformToJSON(transform),
+    });
+    this.emitStructureEvent({ type: "added", id, parentId: parentId ?? null });
   }
 
   /**
    * Update the transform of an existing frame.
    *
+   * @param options Pass `{ source }` to record which producer issued the
+   *                update (see {@link setAuthorityPolicy}).
    * @throws {Error} if `id` is not registered.
+   * @throws {FrameLockedError} if `id` is locked.
+   * @throws {UnauthorizedSourceError} if `source` does not own the frame
+   *                                   under the `"owner-only"` policy.
+   * @throws {InvalidTransformError} if `transform` has a non-finite component
+   *                                 or violates the rotation norm policy.
    */
-  updateTransform(id: string, transform: Transform): void {
+  updateTransform(id: string, transform: Transform, options: UpdateOptions = {}): void {
+    this.writeTransform(this.resolveAlias(id), transform, options);
+  }
+
+  /** {@link updateTransform} of the frame registered as `id`, which is not an alias. */
+  private writeTransform(id: string, transform: Transform, options: UpdateOptions): void {
     const frame = this.frames.get(id);
     if (frame === undefined) {
-      throw new Error(`Frame "${id}" not found.`);
+      throw new FrameNotFoundError(id);
     }
+    this.assertUnlocked(id);
+    this.assertAuthorized(id, options.source);
+    transform = this.checkTransform(id, transform);
     this.frames.set(id, { ...frame, transform });
+    this.updatedFrames.add(id);
+    this.recordSource(id, options.source);
+    this.record({ op: "update", id, transform: transformToJSON(transform) });
     this.markSubtreeDirty(id);
   }
 
@@ -97,11 +760,34 @@ export class TFTree implements ITransformTree {
    * Alias for {@link updateTransform} – satisfies the {@link ITransformTree} interface.
    *
    * @throws {Error} if `id` is not registered.
+   * @throws {FrameLockedError} if `id` is locked.
    */
   updateFrame(id: string, transform: Transform): void {
     this.updateTransform(id, transform);
   }
 
+  /**
+   * Replace only the rotation of an existing frame, keeping its translation —
+   * the update of an orientation frame driven by an IMU, whose samples carry
+   * no position.  The components are checked before anything is built, so
+   * a bad sample is rejected without touching the frame.
+   *
+   * @param options As for {@link updateTransform}.
+   * @throws {Error} as {@link updateTransform}.
+   * @throws {InvalidTransformError} if a component is not finite or the
+   *                                 quaternion has zero norm.
+   */
+  updateFrameRotation(
+    id: string,
+    qx: number,
+    qy: number,
+    qz: number,
+    qw: number,
+    options: UpdateOptions = {},
+  ): void {
+    this.updateTransform(id, this.withRotation(id, qx, qy, qz, qw), options);
+  }
+
   /**
    * Batch-update the transforms of multiple existing frames in a single call.
    *
@@ -110,245 +796,4610 @@ export class TFTree implements ITransformTree {
    * whose ancestor is also included in the same batch, preventing redundant
    * subtree traversals.
    *
+   * The batch is atomic: every id and transform is checked before any is
+   * applied, and the prior transforms are restored if applying fails, so
+   * the tree is never left half-updated.  Pass `{ validateOnly: true }` to
+   * run every check without applying anything, and `{ source }` as for
+   * {@link updateTransform}.
+   *
    * @throws {Error} if any id in `updates` is not registered.
+   * @throws {FrameLockedError} if any id in `updates` is locked.
+   * @throws {UnauthorizedSourceError} if `source` does not own a frame under
+   *                                   the `"owner-only"` policy.
+   * @throws {InvalidTransformError} if any transform has a non-finite
+   *                                 component or violates the rotation norm
+   *                                 policy.
+   * @throws {TFError} (`INVALID_STATE`) with `validateOnly` inside a
+   *                   transaction.
    */
-  updateTransforms(updates: Record<string, Transform>): void {
-    // First pass: apply all transform changes (validates every id up-front).
-    for (const [id, transform] of Object.entries(updates)) {
-      const frame = this.frames.get(id);
-      if (frame === undefined) {
-        throw new Error(`Frame "${id}" not found.`);
-      }
-      this.frames.set(id, { ...frame, transform });
-    }
-
-    // Second pass: mark subtrees dirty, but skip frames whose ancestor is
-    // also being updated in this batch – the ancestor's markSubtreeDirty
-    // call will already cover those descendants.
-    const ids = new Set(Object.keys(updates));
-    for (const id of ids) {
-      let parentId = this.frames.get(id)?.parentId;
-      let ancestorUpdated = false;
-      while (parentId !== undefined) {
-        if (ids.has(parentId)) {
-          ancestorUpdated = true;
-          break;
-        }
-        parentId = this.frames.get(parentId)?.parentId;
-      }
-      if (!ancestorUpdated) {
-        this.markSubtreeDirty(id);
-      }
+  updateTransforms(
+    updates: Record<string, Transform>,
+    options: BatchOptions & UpdateOptions = {},
+  ): void {
+    const { validateOnly, ...updateOptions } = options;
+    if (validateOnly) {
+      this.dryRun("updateTransforms", () => this.updateTransforms(updates, updateOptions));
+      return;
     }
+    this.assertQuota("maxBatchSize", Object.keys(updates).length, []);
+    this.dispatchChanges(this.applyTransformBatch(updates, updateOptions));
   }
 
   /**
-   * Remove a registered frame from the tree.
+   * Dead-reckon frame `id` (e.g. `base_link` under `odom`) from a velocity
+   * command or wheel odometry: move it for `dt` seconds at the constant
+   * twist (`linear`, `angular`), both expressed in the frame itself as in a
+   * ROS `cmd_vel`, then store the result as with {@link updateTransform}.
    *
-   * @param id Identifier of the frame to remove.
-   * @throws {Error} if `id` is not registered.
-   * @throws {Error} if the frame still has child frames registered.
+   * The motion is integrated exactly along its screw (a constant turn rate
+   * traces an arc, not a polygon) and in double precision, with the
+   * rotation renormalized, so that many small steps don't drift.
+   *
+   * @example
+   * ```ts
+   * odometry.on("twist", ({ linear, angular, dt }) => {
+   *   tf.integrateTwist("base_link", linear, angular, dt);
+   * });
+   * ```
+   *
+   * @param linear  Linear velocity, in tree units per second.
+   * @param angular Angular velocity, in radians per second.
+   * @param dt      Duration, in seconds.
+   * @returns The new transform of `id` relative to its parent.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if a velocity component is not
+   *                   finite or `dt` is negative or not finite.
+   * @throws {Error} as {@link updateTransform}.
    */
-  removeFrame(id: string): void {
-    if (!this.frames.has(id)) {
-      throw new Error(`Frame "${id}" not found.`);
-    }
-    for (const frame of this.frames.values()) {
-      if (frame.parentId === id) {
-        throw new Error(
-          `Cannot remove frame "${id}": it still has child frames. Remove children first.`,
-        );
-      }
-    }
-    const { parentId } = this.frames.get(id)!;
-    this.frames.delete(id);
-    this.worldTransformCache.delete(id);
-    this.dirtySet.delete(id);
-    // Clean up children map.
-    this.childrenMap.delete(id);
-    if (parentId !== undefined) {
-      this.childrenMap.get(parentId)?.delete(id);
+  integrateTwist(
+    id: string,
+    linear: Vec3,
+    angular: Vec3,
+    dt: number,
+    options: UpdateOptions = {},
+  ): Transform {
+    const frame = this.getFrameNode(id);
+    const components = [...linear.toArray(), ...angular.toArray()];
+    if (!components.every(Number.isFinite) || !(dt >= 0 && Number.isFinite(dt))) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Invalid twist (${components.join(", ")}) or duration ${dt} for frame "${frame.id}".`,
+        { frameIds: [frame.id], operation: "integrateTwist" },
+      );
     }
-    // Clean up change listeners.
-    this.changeListeners.delete(id);
-  }
-
-  // ── query ──────────────────────────────────────────────────────────────────
-
-  /** Returns true if the given frame id is registered. */
-  hasFrame(id: string): boolean {
-    return this.frames.has(id);
-  }
-
-  /** Returns all registered frame ids. */
-  frameIds(): string[] {
-    return Array.from(this.frames.keys());
+    this.writeTransform(frame.id, twistStep(frame.transform, linear, angular, dt), options);
+    return this.frames.get(frame.id)!.transform;
   }
 
   /**
-   * Compute the transform that maps points expressed in `from` to the
-   * coordinate system of `to`.
+   * Register `id` if it is missing, otherwise update its transform — the
+   * usual pattern for consumers of a streaming transform feed.
    *
-   * In other words the returned transform `T` satisfies:
-   *   `p_to = T.transformPoint(p_from)`
+   * For an existing frame, a `parentId` that differs from its current parent
+   * also moves the frame (and its subtree) under `parentId`; pass `undefined`
+   * to keep the current parent.
    *
-   * @throws {Error} if either frame is not registered or if the frames are
-   *                 not connected in the same tree.
+   * @returns `true` if the frame was newly registered, `false` if it existed.
+   * @throws {Error} if `parentId` is not registered.
+   * @throws {CycleDetectedError} if `parentId` is `id` or one of its
+   *                              descendants.
+   * @throws {FrameLockedError} if `id` exists and is locked.
    */
-  getTransform(from: string, to: string): Transform {
-    if (!this.frames.has(from)) {
-      throw new Error(`Frame "${from}" not found.`);
+  addOrUpdateFrame(id: string, parentId: string | undefined, transform: Transform): boolean {
+    if (!this.hasFrame(id)) {
+      this.addFrame(id, parentId, transform);
+      return true;
     }
-    if (!this.frames.has(to)) {
-      throw new Error(`Frame "${to}" not found.`);
+    id = this.resolveAlias(id);
+    if (parentId !== undefined) {
+      parentId = this.resolveAlias(parentId);
     }
-    if (from === to) {
-      return Transform.identity();
+    if (parentId === undefined || parentId === this.frames.get(id)!.parentId) {
+      this.writeTransform(id, transform, {});
+    } else {
+      this.assertValidParent(id, parentId);
+      this.assertUnlocked(id);
+      transform = this.checkTransform(id, transform);
+      this.relinkFrame(id, parentId, transform);
+      this.updatedFrames.add(id);
     }
+    return false;
+  }
 
-    // Walk each frame up to the root and collect the chain as (id → index).
-    const fromChain = this.chainToRoot(from);
-    const toChain = this.chainToRoot(to);
-
-    // Find the lowest common ancestor (LCA).
-    const toChainMap = new Map<string, number>(toChain.map((id, idx) => [id, idx]));
-
-    let lcaId: string | undefined;
-    for (let i = 0; i < fromChain.length; i++) {
-      if (toChainMap.has(fromChain[i])) {
-        lcaId = fromChain[i];
-        break;
-      }
+  /**
+   * Move `id` (together with its whole subtree) under `newParentId`, keeping
+   * its stored local transform.  The frame's world pose therefore changes
+   * unless the old and new parents coincide in space.
+   *
+   * @param id          Frame to reparent.
+   * @param newParentId New parent frame; omit to turn `id` into a root frame.
+   * @throws {Error} if `id` or `newParentId` is not registered.
+   * @throws {CycleDetectedError} if `newParentId` is `id` or one of its
+   *                              descendants.
+   * @throws {ReadOnlyNamespaceError} if `id` is in a read-only namespace.
+   */
+  setParent(id: string, newParentId?: string): void {
+    id = this.resolveAlias(id);
+    if (newParentId !== undefined) {
+      newParentId = this.resolveAlias(newParentId);
     }
-
-    if (lcaId === undefined) {
-      throw new Error(`Frames "${from}" and "${to}" are not connected in the same tree.`);
+    const frame = this.getFrameNode(id);
+    this.assertValidParent(id, newParentId);
+    if (frame.parentId === newParentId) {
+      return;
     }
-
-    // Use cached world transforms to compute the relative transform.
-    return this.getWorldTransform(from).invert().compose(this.getWorldTransform(to));
+    this.assertWritable(id);
+    this.relinkFrame(id, newParentId, frame.transform);
   }
 
-  // ── event subscription ─────────────────────────────────────────────────────
-
   /**
-   * Subscribe to world-transform changes for `frameId`.
+   * Move `id` (together with its whole subtree) under `newParentId`,
+   * recomputing its local transform so that neither it nor any descendant
+   * visibly moves — the usual scene-graph "attach" semantics.
    *
-   * The `callback` is fired whenever the world transform of `frameId` changes —
-   * either because `frameId` itself was updated via {@link updateTransform} /
-   * {@link updateFrame}, or because any of its ancestor frames was updated.
+   * World poses are measured relative to each tree's root; when moving a
+   * frame into a different tree the two roots are treated as coincident.
    *
-   * @returns An unsubscribe function that removes the listener when called.
-   * @throws {Error} if `frameId` is not registered.
+   * @param id          Frame to reparent.
+   * @param newParentId New parent frame; omit to turn `id` into a root frame.
+   * @throws {Error} if `id` or `newParentId` is not registered.
+   * @throws {CycleDetectedError} if `newParentId` is `id` or one of its
+   *                              descendants.
+   * @throws {FrameLockedError} if `id` is locked.
    */
-  onChange(frameId: string, callback: ChangeCallback): () => void {
-    if (!this.frames.has(frameId)) {
-      throw new Error(`Frame "${frameId}" not found.`);
+  setParentKeepWorld(id: string, newParentId?: string): void {
+    id = this.resolveAlias(id);
+    if (newParentId !== undefined) {
+      newParentId = this.resolveAlias(newParentId);
     }
-    let listeners = this.changeListeners.get(frameId);
-    if (listeners === undefined) {
-      listeners = new Set();
-      this.changeListeners.set(frameId, listeners);
-    }
-    listeners.add(callback);
-    return () => {
-      this.changeListeners.get(frameId)?.delete(callback);
-    };
+    this.getFrameNode(id);
+    this.assertUnlocked(id);
+    this.assertValidParent(id, newParentId);
+    const world = this.getWorldTransform(id);
+    const local =
+      newParentId === undefined
+        ? world
+        : this.getWorldTransform(newParentId).invert().compose(world);
+    this.relinkFrame(id, newParentId, local);
   }
 
-  // ── serialization ──────────────────────────────────────────────────────────
-
   /**
-   * Serialize the entire tree to a plain JSON-compatible object.
+   * Swap the roles of `childId` and its parent: the parent becomes a child of
+   * `childId` with the inverted edge transform, and `childId` takes the
+   * parent's former place in the tree.  The parent's other children stay
+   * attached to it.  No frame changes its world pose.
    *
-   * Frames are emitted in insertion order, which guarantees that parents
-   * always appear before their children — safe to replay with sequential
-   * {@link addFrame} calls.
-   *
-   * @example
-   * ```ts
-   * const json = tf.toJSON();
-   * const copy = TFTree.fromJSON(json);
-   * ```
+   * @throws {Error} if `childId` is not registered or is a root frame.
+   * @throws {FrameLockedError} if `childId` or its parent is locked.
    */
-  toJSON(): TFTreeJSON {
-    const frames = Array.from(this.frames.values()).map((frame) => ({
-      id: frame.id,
-      parentId: frame.parentId ?? null,
-      transform: {
-        translation: frame.transform.translation.toArray(),
-        rotation: frame.transform.rotation.toArray(),
-      },
-    }));
-    return { frames };
+  invertEdge(childId: string): void {
+    this.invertParentEdge(this.getFrameNode(childId));
+  }
+
+  /** {@link invertEdge} of the registered frame `child`. */
+  private invertParentEdge(child: FrameNode): void {
+    if (child.parentId === undefined) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Frame "${child.id}" is a root frame; it has no parent edge to invert.`,
+        { frameIds: [child.id], operation: "invertEdge" },
+      );
+    }
+    const parent = this.frames.get(child.parentId)!;
+    this.assertUnlocked(child.id);
+    this.assertUnlocked(parent.id);
+    this.relinkFrame(child.id, parent.parentId, parent.transform.compose(child.transform));
+    this.relinkFrame(parent.id, child.id, child.transform.invert());
   }
 
   /**
-   * Reconstruct a {@link TFTree} from a plain JSON object produced by
-   * {@link toJSON}.
-   *
-   * Frames must be listed in parent-before-child order (which {@link toJSON}
-   * guarantees automatically).
+   * Make `newRootId` the root of its tree by inverting every edge on the path
+   * to the current root (e.g. to make `odom` the parent of `map`).  Relative
+   * transforms between any two frames are unchanged.
    *
-   * @example
-   * ```ts
-   * const tf = TFTree.fromJSON(config);
-   * ```
+   * @throws {Error} if `newRootId` is not registered.
+   * @throws {FrameLockedError} if any frame on the path to the root is locked;
+   *                            in that case nothing is changed.
+   */
+  reroot(newRootId: string): void {
+    newRootId = this.getFrameNode(newRootId).id;
+    const path = this.chainToRoot(newRootId);
+    path.forEach((id) => this.assertUnlocked(id));
+    for (let i = 1; i < path.length; i++) {
+      this.invertParentEdge(this.frames.get(newRootId)!);
+    }
+  }
+
+  /**
+   * Temporarily pull `id` (with its subtree) out of the hierarchy, turning it
+   * into a floating root frame that keeps its world pose.  The former parent
+   * and world pose are remembered for {@link reattach}.
    *
-   * @throws {Error} if the data contains an unknown parent reference or a
-   *                 duplicate frame id.
+   * @throws {Error} if `id` is not registered or is already a root frame.
+   * @throws {FrameLockedError} if `id` is locked.
    */
-  static fromJSON(data: TFTreeJSON): TFTree {
-    const tree = new TFTree();
-    for (const frame of data.frames) {
-      const transform = new Transform(
-        Vec3.fromArray(frame.transform.translation),
-        Quaternion.fromArray(frame.transform.rotation),
+  detach(id: string): void {
+    const { id: frameId, parentId } = this.getFrameNode(id);
+    if (parentId === undefined) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Frame "${frameId}" is a root frame and cannot be detached.`,
+        { frameIds: [frameId], operation: "detach" },
       );
-      tree.addFrame(frame.id, frame.parentId ?? undefined, transform);
     }
-    return tree;
+    this.assertUnlocked(frameId);
+    const worldTransform = this.getWorldTransform(frameId);
+    this.relinkFrame(frameId, undefined, worldTransform);
+    this.detachedFrames.set(frameId, { parentId, worldTransform });
   }
 
-  // ── protected helpers (available to subclasses) ───────────────────────────
-
   /**
-   * Returns the {@link FrameNode} for the given id.
-   * Subclasses may use this to walk the frame hierarchy.
+   * Attach a frame previously detached with {@link detach} back into the
+   * hierarchy.  The local transform is recomputed so that the frame returns
+   * to the world pose it had when detached, or — with
+   * `{ keepCurrentPose: true }` — stays where it currently is.
    *
-   * @throws {Error} if `id` is not registered.
+   * @param id       Detached frame.
+   * @param parentId New parent; defaults to the parent it was detached from.
+   * @param options  See {@link ReattachOptions}.
+   * @throws {Error} if `id` is not detached or the parent is not registered.
+   * @throws {CycleDetectedError} if the parent is `id` or one of its
+   *                              descendants.
+   * @throws {FrameLockedError} if `id` is locked.
    */
-  protected getFrameNode(id: string): FrameNode {
-    const frame = this.frames.get(id);
-    if (frame === undefined) {
-      throw new Error(`Frame "${id}" not found.`);
+  reattach(id: string, parentId?: string, options: ReattachOptions = {}): void {
+    id = this.resolveAlias(id);
+    const detached = this.detachedFrames.get(id);
+    if (detached === undefined) {
+      throw new TFError("INVALID_ARGUMENT", `Frame "${id}" is not detached.`, {
+        frameIds: [id],
+        operation: "reattach",
+      });
     }
-    return frame;
+    const newParentId = parentId !== undefined ? this.resolveAlias(parentId) : detached.parentId;
+    this.assertValidParent(id, newParentId);
+    this.assertUnlocked(id);
+    const world = options.keepCurrentPose ? this.getWorldTransform(id) : detached.worldTransform;
+    this.relinkFrame(id, newParentId, this.getWorldTransform(newParentId).invert().compose(world));
+    this.detachedFrames.delete(id);
   }
 
-  // ── private helpers ────────────────────────────────────────────────────────
+  /** Returns `true` if `id` was detached with {@link detach} and not yet reattached. */
+  isDetached(id: string): boolean {
+    return this.detachedFrames.has(this.resolveAlias(id));
+  }
 
   /**
-   * Mark a frame and all of its descendants as dirty, invalidating their
-   * cached world transforms so they are recomputed on next access.
+   * Atomically rename a frame.  The frame keeps its position in registration
+   * order, its parent, children, transform, cached state, change listeners
+   * and aliases; listeners are invoked with `newId` from then on.
+   *
+   * @throws {Error} if `oldId` is not registered or `newId` is already in use.
+   * @throws {InvalidFrameIdError} if `newId` violates the frame id policy.
+   * @throws {ReadOnlyNamespaceError} if `oldId` or `newId` is in a read-only
+   *                                  namespace.
    */
-  private markSubtreeDirty(id: string): void {
-    this.dirtySet.add(id);
-    this.worldTransformCache.delete(id);
-    // Notify subscribers watching this frame.
-    const listeners = this.changeListeners.get(id);
-    if (listeners !== undefined) {
-      for (const cb of listeners) {
-        cb(id);
-      }
-    }
-    for (const childId of this.childrenMap.get(id) ?? []) {
-      this.markSubtreeDirty(childId);
-    }
+  renameFrame(oldId: string, newId: string): void {
+    this.changeFrameId(this.getFrameNode(oldId).id, this.normalizeId(newId));
   }
 
   /**
-   * Returns the cached world transform for `id`, recomputing and caching it
+   * {@link renameFrame} of the frame registered as `oldId` to the already
+   * normalized `newId`.  Subclasses keeping per-frame state override this
+   * rather than {@link renameFrame}, as the journal and patches rename
+   * through it.
+   */
+  protected changeFrameId(oldId: string, newId: string): void {
+    const frame = this.frameNodeById(oldId);
+    if (oldId === newId) {
+      return;
+    }
+    if (this.frames.has(newId) || this.aliases.has(newId)) {
+      throw new DuplicateFrameError(newId);
+    }
+    this.assertValidId(newId);
+    this.assertWritable(oldId);
+    if (this.namespaceOf(newId) !== this.namespaceOf(oldId)) {
+      this.assertNamespaceAccepts(newId);
+    }
+
+    // Rebuild the frame map so the renamed frame keeps its insertion slot.
+    const entries = Array.from(this.frames.values());
+    this.frames.clear();
+    for (const node of entries) {
+      if (node.id === oldId) {
+        this.frames.set(newId, { ...frame, id: newId });
+      } else {
+        this.frames.set(node.id, node);
+      }
+    }
+
+    // Point children at the new id.
+    const children = this.childrenMap.get(oldId)!;
+    for (const childId of children) {
+      const child = this.frames.get(childId)!;
+      this.frames.set(childId, { ...child, parentId: newId });
+    }
+    this.childrenMap.delete(oldId);
+    this.childrenMap.set(newId, children);
+
+    // Swap the entry in the parent's child set, preserving sibling order.
+    if (frame.parentId !== undefined) {
+      const siblings = this.childrenMap.get(frame.parentId)!;
+      const ordered = Array.from(siblings, (id) => (id === oldId ? newId : id));
+      siblings.clear();
+      for (const id of ordered) siblings.add(id);
+    }
+
+    moveKey(this.worldTransformCache, oldId, newId);
+    moveKey(this.subtreeSizes, oldId, newId);
+    moveKey(this.frameVersions, oldId, newId);
+    moveKey(this.changeListeners, oldId, newId);
+    moveKey(this.notifiedWorldTransforms, oldId, newId);
+    if (this.lockedFrames.delete(oldId)) {
+      this.lockedFrames.add(newId);
+    }
+    if (this.updatedFrames.delete(oldId)) {
+      this.updatedFrames.add(newId);
+    }
+    moveKey(this.detachedFrames, oldId, newId);
+    moveKey(this.cameraIntrinsics, oldId, newId);
+    const calibration = this.calibrations.get(oldId);
+    if (calibration !== undefined) {
+      this.calibrations.delete(oldId);
+      this.calibrations.set(newId, { ...calibration, frameId: newId });
+    }
+    moveKey(this.geoAnchors, oldId, newId);
+    for (const joint of this.joints.values()) {
+      if (joint.frameId === oldId) this.joints.set(joint.name, { ...joint, frameId: newId });
+    }
+    moveKey(this.frameOwners, oldId, newId);
+    moveKey(this.lastSources, oldId, newId);
+    for (const watch of this.watches) {
+      if (watch?.from === oldId) watch.from = newId;
+      if (watch?.to === oldId) watch.to = newId;
+    }
+    if (this.watchDirty.delete(oldId)) {
+      this.watchDirty.add(newId);
+    }
+    for (const [id, detached] of this.detachedFrames) {
+      if (detached.parentId === oldId) {
+        this.detachedFrames.set(id, { ...detached, parentId: newId });
+      }
+    }
+    if (this.dirtySet.delete(oldId)) {
+      this.dirtySet.add(newId);
+    }
+    for (const [alias, target] of this.aliases) {
+      if (target === oldId) this.aliases.set(alias, newId);
+    }
+    this.record({ op: "rename", id: oldId, newId });
+    this.emitStructureEvent({ type: "renamed", oldId, id: newId });
+  }
+
+  /**
+   * Remove a registered frame from the tree.
+   *
+   * By default a frame with children cannot be removed.  Pass
+   * `{ promoteChildren: true }` to reparent the children to the removed
+   * frame's parent instead, and additionally `preserveWorld: true` to
+   * precompose the removed frame's transform into each child so that the
+   * children keep their world poses.  Aliases of the frame are removed too.
+   *
+   * @param id      Identifier (or alias) of the frame to remove.
+   * @param options See {@link RemoveFrameOptions}.
+   * @throws {Error} if `id` is not registered.
+   * @throws {Error} if the frame still has child frames registered and
+   *                 `promoteChildren` is not set.
+   * @throws {FrameLockedError} if `preserveWorld` would change the transform
+   *                            of a locked child.
+   * @throws {ReadOnlyNamespaceError} if the frame, or a child that would be
+   *                                  promoted, is in a read-only namespace.
+   */
+  removeFrame(id: string, options: RemoveFrameOptions = {}): void {
+    this.deleteFrame(this.resolveAlias(id), options);
+  }
+
+  /**
+   * {@link removeFrame} of the frame registered as `id`, which is not an
+   * alias.  Subclasses keeping per-frame state override this rather than
+   * {@link removeFrame}, as subtree removal and the journal remove through it.
+   */
+  protected deleteFrame(id: string, options: RemoveFrameOptions): void {
+    if (!this.frames.has(id)) {
+      throw new FrameNotFoundError(id);
+    }
+    const { parentId, transform } = this.frames.get(id)!;
+    const children = this.childrenMap.get(id)!;
+    this.assertWritable(id);
+    if (options.promoteChildren) {
+      children.forEach((childId) =>
+        options.preserveWorld ? this.assertUnlocked(childId) : this.assertWritable(childId),
+      );
+      for (const childId of Array.from(children)) {
+        const child = this.frames.get(childId)!;
+        const local = options.preserveWorld ? transform.compose(child.transform) : child.transform;
+        this.relinkFrame(childId, parentId, local);
+      }
+    } else if (children.size > 0) {
+      throw new TFError(
+        "INVALID_STATE",
+        `Cannot remove frame "${id}": it still has child frames. Remove children first.`,
+        { frameIds: [id], operation: "removeFrame" },
+      );
+    }
+    this.frames.delete(id);
+    this.worldTransformCache.delete(id);
+    this.dirtySet.delete(id);
+    // Clean up children map.
+    this.childrenMap.delete(id);
+    if (parentId !== undefined) {
+      this.childrenMap.get(parentId)?.delete(id);
+    }
+    this.subtreeSizes.delete(id);
+    this.frameVersions.delete(id);
+    this.adjustSubtreeSizes(parentId, -1);
+    // Clean up change listeners, locks and aliases.
+    this.changeListeners.delete(id);
+    this.notifiedWorldTransforms.delete(id);
+    this.lockedFrames.delete(id);
+    this.updatedFrames.delete(id);
+    this.detachedFrames.delete(id);
+    this.cameraIntrinsics.delete(id);
+    this.calibrations.delete(id);
+    this.geoAnchors.delete(id);
+    for (const joint of this.joints.values()) {
+      if (joint.frameId === id) this.deleteJoint(joint.name);
+    }
+    this.frameOwners.delete(id);
+    this.lastSources.delete(id);
+    this.watches.forEach((watch, slot) => {
+      if (watch?.from === id || watch?.to === id) this.watches[slot] = undefined;
+    });
+    this.watchDirty.delete(id);
+    for (const [alias, target] of this.aliases) {
+      if (target === id) this.aliases.delete(alias);
+    }
+    this.record({ op: "remove", id });
+    this.emitStructureEvent({ type: "removed", id, parentId: parentId ?? null });
+  }
+
+  /**
+   * Remove a frame together with all of its descendants.
+   *
+   * @param id Root of the subtree to remove.
+   * @returns The removed frame ids, children before parents (the order in
+   *          which they were removed).
+   * @throws {Error} if `id` is not registered.
+   * @throws {ReadOnlyNamespaceError} if a frame of the subtree is in a
+   *                                  read-only namespace; nothing is removed.
+   */
+  removeSubtree(id: string): string[] {
+    const removed = this.traverse(id, "dfs-post");
+    removed.forEach((frameId) => this.assertWritable(frameId));
+    for (const frameId of removed) {
+      this.deleteFrame(frameId, {});
+    }
+    return removed;
+  }
+
+  /**
+   * Remove every frame whose id starts with `prefix` — e.g. `"robot3/"` to
+   * despawn an entire robot — children before parents.
+   *
+   * The removal is all-or-nothing: if a matching frame has a child that does
+   * not match, nothing is removed.
+   *
+   * @returns The removed frame ids, children before parents.
+   * @throws {Error} if a matching frame has a non-matching child.
+   * @throws {ReadOnlyNamespaceError} if a matching frame is in a read-only
+   *                                  namespace.
+   */
+  removeFramesWithPrefix(prefix: string): string[] {
+    const removed: string[] = [];
+    for (const frame of this.frames.values()) {
+      if (frame.parentId === undefined) {
+        for (const id of this.subtreeIds(frame.id, "dfs-post")) {
+          if (id.startsWith(prefix)) removed.push(id);
+        }
+      }
+    }
+    for (const id of removed) {
+      this.assertWritable(id);
+      for (const childId of this.childrenMap.get(id)!) {
+        if (!childId.startsWith(prefix)) {
+          throw new TFError(
+            "INVALID_STATE",
+            `Cannot remove frame "${id}": its child "${childId}" does not match prefix "${prefix}".`,
+            { frameIds: [id, childId], operation: "removeFramesWithPrefix" },
+          );
+        }
+      }
+    }
+    for (const id of removed) {
+      this.deleteFrame(id, {});
+    }
+    return removed;
+  }
+
+  /**
+   * Remove every frame, alias, cached transform, change listener and watched
+   * pair, returning the tree to its freshly constructed state so the instance
+   * can be reused (e.g. on scene reload).  Listeners registered with
+   * {@link onStructureChange} are kept and receive a `"cleared"` event.
+   *
+   * @throws {ReadOnlyNamespaceError} if a frame is in a read-only namespace;
+   *                                  nothing is removed.
+   */
+  clear(): void {
+    for (const id of this.frames.keys()) {
+      this.assertWritable(id);
+    }
+    this.frames.clear();
+    this.dirtySet.clear();
+    this.worldTransformCache.clear();
+    this.childrenMap.clear();
+    this.changeListeners.clear();
+    this.batchChangeListeners.forEach((cb) => this.openTransaction?.clearedBatchListeners.add(cb));
+    this.batchChangeListeners.clear();
+    this.notifiedWorldTransforms.clear();
+    this.subtreeSizes.clear();
+    this.frameVersions.clear();
+    this.aliases.clear();
+    this.lockedFrames.clear();
+    this.updatedFrames.clear();
+    this.detachedFrames.clear();
+    this.cameraIntrinsics.clear();
+    this.calibrations.clear();
+    this.geoAnchors.clear();
+    this.joints.clear();
+    this.frameOwners.clear();
+    this.lastSources.clear();
+    this.watches.length = 0;
+    this.watchDirty.clear();
+    this.record({ op: "clear" });
+    this.emitStructureEvent({ type: "cleared" });
+  }
+
+  /**
+   * Import every frame of `other` into this tree, attaching the roots of
+   * `other` as children of `mountFrame` — e.g. to spawn a robot model into a
+   * world.  Each imported id is prefixed with `prefix`; `other` itself is not
+   * modified.
+   *
+   * The import is all-or-nothing: ids are checked for collisions before any
+   * frame is added.
+   *
+   * @param other      Tree to import from.
+   * @param mountFrame Existing frame that becomes the parent of `other`'s roots.
+   * @param prefix     String prepended to every imported frame id.
+   * @returns The ids of the imported frames, parents before children.
+   * @throws {Error} if `mountFrame` is not registered or any prefixed id is
+   *                 already registered.
+   */
+  attachTree(other: TFTree, mountFrame: string, prefix = ""): string[] {
+    mountFrame = this.resolveAlias(mountFrame);
+    this.getFrameNode(mountFrame);
+    const frames = other.frameRecords();
+    for (const frame of frames) {
+      if (this.frames.has(prefix + frame.id) || this.aliases.has(prefix + frame.id)) {
+        throw new DuplicateFrameError(prefix + frame.id);
+      }
+    }
+
+    const imported: string[] = [];
+    for (const frame of frames) {
+      const id = prefix + frame.id;
+      const parentId = frame.parentId === null ? mountFrame : prefix + frame.parentId;
+      this.addFrame(id, parentId, other.getLocalTransform(frame.id));
+      imported.push(id);
+    }
+    return imported;
+  }
+
+  /**
+   * Copy the subtree rooted at `rootId` into a new, independent
+   * {@link TFTree} in which `rootId` is a root frame.  Every frame keeps its
+   * local transform, so attaching the result back under the original parent
+   * with {@link attachTree} restores the original poses.
+   *
+   * @param rootId  Root of the subtree to extract.
+   * @param options Pass `{ remove: true }` to also remove the subtree from
+   *                this tree (a "move" rather than a "copy").
+   * @throws {Error} if `rootId` is not registered.
+   */
+  extractSubtree(rootId: string, options: ExtractSubtreeOptions = {}): TFTree {
+    rootId = this.resolveAlias(rootId);
+    const extracted = new TFTree();
+    for (const id of this.subtreeIds(rootId)) {
+      const { parentId, transform } = this.frames.get(id)!;
+      extracted.addFrame(id, id === rootId ? undefined : parentId, transform);
+    }
+    if (options.remove) {
+      this.removeSubtree(rootId);
+    }
+    return extracted;
+  }
+
+  /**
+   * Partition the tree by cutting the edge between each frame in `frameIds`
+   * and its parent, and return every resulting tree as a new, independent
+   * {@link TFTree} — e.g. to hand scene regions to different workers.  This
+   * tree is not modified.
+   *
+   * As with {@link extractSubtree}, every frame keeps its local transform,
+   * so attaching a part back under the cut frame's original parent with
+   * {@link attachTree} restores its poses.  Parts are listed component by
+   * component (see {@link getComponents}), in depth-first pre-order of their
+   * roots.  Listing a root frame has no effect.
+   *
+   * @throws {Error} if any id in `frameIds` is not registered.
+   */
+  splitAt(frameIds: readonly string[]): TFTree[] {
+    const cuts = new Set(frameIds.map((id) => this.getFrameNode(id).id));
+    const parts: TFTree[] = [];
+    const partOf = new Map<string, TFTree>();
+    for (const component of this.getComponents()) {
+      for (const id of component.frameIds) {
+        const { parentId, transform } = this.frames.get(id)!;
+        let part: TFTree;
+        if (parentId === undefined || cuts.has(id)) {
+          part = new TFTree();
+          part.addFrame(id, undefined, transform);
+          parts.push(part);
+        } else {
+          part = partOf.get(parentId)!;
+          part.addFrame(id, parentId, transform);
+        }
+        partOf.set(id, part);
+      }
+    }
+    return parts;
+  }
+
+  // ── transactions ───────────────────────────────────────────────────────────
+
+  /**
+   * Start a transaction.  Subsequent structural and transform edits are
+   * applied immediately but can be undone as a whole with {@link rollback};
+   * change notifications are held back until {@link commit} so listeners
+   * never observe a half-applied sequence.
+   *
+   * @throws {Error} if a transaction is already in progress.
+   */
+  begin(): void {
+    if (this.openTransaction !== undefined) {
+      throw new TFError("INVALID_STATE", "A transaction is already in progress.", {
+        frameIds: [],
+        operation: "begin",
+      });
+    }
+    const children = new Map<string, string[]>();
+    for (const [id, set] of this.childrenMap) {
+      children.set(id, Array.from(set));
+    }
+    this.openTransaction = {
+      frames: Array.from(this.frames.values()),
+      children,
+      subtreeSizes: new Map(this.subtreeSizes),
+      listenerSets: new Map(this.changeListeners),
+      clearedBatchListeners: new Set(),
+      aliases: new Map(this.aliases),
+      lockedFrames: new Set(this.lockedFrames),
+      updatedFrames: new Set(this.updatedFrames),
+      detachedFrames: new Map(this.detachedFrames),
+      cameraIntrinsics: new Map(this.cameraIntrinsics),
+      calibrations: new Map(this.calibrations),
+      geoAnchors: new Map(this.geoAnchors),
+      watches: this.watches.map((watch) => (watch === undefined ? undefined : { ...watch })),
+      namespaces: new Map(Array.from(this.namespaces, ([prefix, ns]) => [prefix, { ...ns }])),
+      joints: new Map(this.joints),
+      frameOwners: new Map(this.frameOwners),
+      lastSources: new Map(this.lastSources),
+      modifiedAt: new Map(this.modifiedAt),
+      pendingNotifications: new Set(),
+      pendingStructureEvents: [],
+      pendingJointLimitEvents: [],
+      journalLength: this.journal?.length ?? 0,
+      journalSeq: this.journalSeq,
+      forkEditCount: this.forkEdits.length,
+    };
+  }
+
+  /**
+   * Finish the current transaction, keeping its edits and delivering the
+   * change notifications collected since {@link begin}.
+   *
+   * @throws {Error} if no transaction is in progress.
+   */
+  commit(): void {
+    const transaction = this.endTransaction();
+    for (const event of transaction.pendingStructureEvents) {
+      this.emitStructureEvent(event);
+    }
+    // Frames removed later in the transaction have nothing left to report.
+    this.dispatchChanges(
+      Array.from(transaction.pendingNotifications).filter((id) => this.frames.has(id)),
+    );
+    for (const [joint, requested] of transaction.pendingJointLimitEvents) {
+      this.emitJointLimitEvent(joint, requested);
+    }
+  }
+
+  /**
+   * Abandon the current transaction, restoring the tree to the exact state it
+   * had when {@link begin} was called.  No change notifications are delivered.
+   *
+   * @throws {Error} if no transaction is in progress.
+   */
+  rollback(): void {
+    const transaction = this.endTransaction();
+    this.frames.clear();
+    for (const frame of transaction.frames) {
+      this.frames.set(frame.id, frame);
+    }
+    this.childrenMap.clear();
+    for (const [id, children] of transaction.children) {
+      this.childrenMap.set(id, new Set(children));
+    }
+    this.subtreeSizes.clear();
+    for (const [id, size] of transaction.subtreeSizes) {
+      this.subtreeSizes.set(id, size);
+    }
+    for (const [id, listeners] of transaction.listenerSets) {
+      this.changeListeners.set(id, listeners);
+    }
+    for (const id of Array.from(this.changeListeners.keys())) {
+      if (!this.frames.has(id)) this.changeListeners.delete(id);
+    }
+    transaction.clearedBatchListeners.forEach((cb) => this.batchChangeListeners.add(cb));
+    this.aliases.clear();
+    for (const [alias, target] of transaction.aliases) {
+      this.aliases.set(alias, target);
+    }
+    this.lockedFrames.clear();
+    transaction.lockedFrames.forEach((id) => this.lockedFrames.add(id));
+    this.updatedFrames.clear();
+    transaction.updatedFrames.forEach((id) => this.updatedFrames.add(id));
+    this.detachedFrames.clear();
+    for (const [id, detached] of transaction.detachedFrames) {
+      this.detachedFrames.set(id, detached);
+    }
+    this.cameraIntrinsics.clear();
+    for (const [id, intrinsics] of transaction.cameraIntrinsics) {
+      this.cameraIntrinsics.set(id, intrinsics);
+    }
+    this.calibrations.clear();
+    for (const [id, calibration] of transaction.calibrations) {
+      this.calibrations.set(id, calibration);
+    }
+    this.geoAnchors.clear();
+    for (const [id, anchor] of transaction.geoAnchors) {
+      this.geoAnchors.set(id, anchor);
+    }
+    this.joints.clear();
+    for (const [name, joint] of transaction.joints) {
+      this.joints.set(name, joint);
+    }
+    this.frameOwners.clear();
+    for (const [id, source] of transaction.frameOwners) {
+      this.frameOwners.set(id, source);
+    }
+    this.lastSources.clear();
+    for (const [id, source] of transaction.lastSources) {
+      this.lastSources.set(id, source);
+    }
+    this.modifiedAt.clear();
+    for (const [id, time] of transaction.modifiedAt) {
+      this.modifiedAt.set(id, time);
+    }
+    // Rolled-back mutations are not counted, but rejected attempts still are.
+    const rejected = new Map(Array.from(this.namespaces, ([prefix, ns]) => [prefix, ns.rejected]));
+    this.namespaces.clear();
+    for (const [prefix, namespace] of transaction.namespaces) {
+      this.namespaces.set(prefix, {
+        ...namespace,
+        rejected: rejected.get(prefix) ?? namespace.rejected,
+      });
+    }
+    // Slots registered since begin() are emptied rather than reused.
+    this.watches.forEach((_, slot) => {
+      this.watches[slot] = transaction.watches[slot];
+    });
+    // Cached world transforms may describe the abandoned state.
+    this.worldTransformCache.clear();
+    this.dirtySet.clear();
+    // Versions and revisions only ever increase, so the restored state gets
+    // fresh ones.
+    this.revisionCounter++;
+    this.frameVersions.clear();
+    for (const id of this.frames.keys()) {
+      this.frameVersions.set(id, ++this.versionClock);
+    }
+    for (const watch of this.watches) {
+      if (watch !== undefined) {
+        this.watchDirty.add(watch.from);
+        this.watchDirty.add(watch.to);
+        if (watch.priority === "high") this.refreshWatch(watch);
+      }
+    }
+    // Journal entries of the abandoned edits never happened.
+    this.journal?.splice(transaction.journalLength);
+    this.journalSeq = transaction.journalSeq;
+    this.forkEdits.splice(transaction.forkEditCount);
+  }
+
+  /**
+   * Run `fn` inside a transaction: commit if it returns normally, roll back
+   * and rethrow if it throws.
+   *
+   * @example
+   * ```ts
+   * tf.transaction(() => {
+   *   tf.removeSubtree("robot1");
+   *   tf.attachTree(robotModel, "world", "robot1/");
+   * });
+   * ```
+   */
+  transaction<T>(fn: () => T): T {
+    this.begin();
+    let result: T;
+    try {
+      result = fn();
+    } catch (err) {
+      this.rollback();
+      throw err;
+    }
+    this.commit();
+    return result;
+  }
+
+  /** Returns `true` while a transaction started with {@link begin} is open. */
+  inTransaction(): boolean {
+    return this.openTransaction !== undefined;
+  }
+
+  // ── mutation journal ───────────────────────────────────────────────────────
+
+  /**
+   * Start recording every mutation in a journal.  Each primitive mutation is
+   * assigned a monotonically increasing sequence number; the journal can be
+   * exported with {@link exportJournal} and replayed on another instance with
+   * {@link applyJournal}, e.g. for replication or crash recovery.
+   *
+   * Calling this while the journal is already enabled has no effect.
+   */
+  enableJournal(): void {
+    if (this.journal === undefined) {
+      this.journal = [];
+    }
+  }
+
+  /**
+   * Stop recording mutations and discard the recorded entries.  Sequence
+   * numbers keep increasing if the journal is enabled again.
+   */
+  disableJournal(): void {
+    this.journal = undefined;
+  }
+
+  /**
+   * Returns the recorded journal entries with a sequence number greater than
+   * `sinceSeq`, oldest first.  Entries are plain JSON-compatible objects.
+   *
+   * @param sinceSeq Only entries after this sequence number are returned.
+   *                 Defaults to `0` (the whole journal).
+   */
+  exportJournal(sinceSeq = 0): JournalEntry[] {
+    return (this.journal ?? []).filter((entry) => entry.seq > sinceSeq);
+  }
+
+  /**
+   * Replay journal entries produced by {@link exportJournal} on this tree.
+   *
+   * The entries are applied atomically: if any of them fails, the tree is
+   * rolled back to its previous state and the error is rethrown.  When a
+   * transaction is already open the entries join it instead.  Pass
+   * `{ validateOnly: true }` to check that the entries would apply without
+   * applying them.
+   *
+   * @throws {Error} if the sequence numbers are not strictly increasing, or
+   *                 if any entry cannot be applied to the current tree.
+   * @throws {TFError} (`INVALID_STATE`) with `validateOnly` inside a
+   *                   transaction.
+   */
+  applyJournal(entries: readonly JournalEntry[], options: BatchOptions = {}): void {
+    if (options.validateOnly) {
+      this.dryRun("applyJournal", () => this.applyJournal(entries));
+      return;
+    }
+    this.assertQuota("maxBatchSize", entries.length, []);
+    let lastSeq = -Infinity;
+    for (const entry of entries) {
+      if (entry.seq <= lastSeq) {
+        throw new TFError(
+          "INVALID_ARGUMENT",
+          `Journal sequence numbers must increase (got ${entry.seq} after ${lastSeq}).`,
+          { frameIds: [], operation: "applyJournal" },
+        );
+      }
+      lastSeq = entry.seq;
+    }
+
+    this.applyOperations(entries);
+  }
+
+  /**
+   * Encode the journal entries after `sinceSeq` into a compact binary delta
+   * for replicating this tree to other clients, e.g. over a WebSocket or
+   * WebRTC data channel.  Apply it on a replica with {@link applySyncPacket}.
+   *
+   * Successive updates of a frame are collapsed into the latest one, ids are
+   * sent once per packet and transforms are quantized (see
+   * {@link SyncOptions}), so a replica matches this tree to within the
+   * quantization step.  With an {@link setExportFilter} export filter, only
+   * changes of exported frames are sent, and `clear` operations are dropped.
+   *
+   * @param sinceSeq Only changes after this journal sequence number are
+   *                 included.  Defaults to `0` (the whole journal); pass the
+   *                 sequence number returned by the replica's last
+   *                 {@link applySyncPacket} to send only what it lacks.
+   * @throws {TFError} (`INVALID_STATE`) if journaling is disabled.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if an id is longer than 65535
+   *                   UTF-16 code units.
+   * @throws {OutOfRangeError} if a translation is too large to quantize.
+   */
+  encodeSyncPacket(sinceSeq = 0, options: SyncOptions = {}): ArrayBuffer {
+    if (this.journal === undefined) {
+      throw new TFError("INVALID_STATE", "Sync packets require enableJournal().", {
+        frameIds: [],
+        operation: "encodeSyncPacket",
+      });
+    }
+    let operations = compactOperations(this.exportJournal(sinceSeq));
+    if (this.exportFilter !== undefined) {
+      operations = operations.filter((op) =>
+        op.op === "clear"
+          ? false
+          : op.op === "rename"
+            ? this.isExported(op.id) || this.isExported(op.newId)
+            : this.isExported(op.id),
+      );
+    }
+    return encodeSyncPacket(this.journalSeq, operations, options.translationStep);
+  }
+
+  /**
+   * Apply a packet produced by {@link encodeSyncPacket} on another tree.  The
+   * packet is applied atomically, like {@link applyJournal}, and accepts the
+   * same `{ validateOnly }` option.
+   *
+   * @returns The sender's journal sequence number the packet brings this
+   *          replica up to.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if the buffer is not a valid sync
+   *                   packet.
+   * @throws {Error} if any operation cannot be applied to the current tree.
+   * @throws {TFError} (`INVALID_STATE`) with `validateOnly` inside a
+   *                   transaction.
+   */
+  applySyncPacket(buffer: ArrayBuffer, options: BatchOptions = {}): number {
+    const { seq, operations } = decodeSyncPacket(buffer);
+    if (options.validateOnly) {
+      this.dryRun("applySyncPacket", () => this.applySyncPacket(buffer));
+      return seq;
+    }
+    this.assertQuota("maxBatchSize", operations.length, []);
+    this.applyOperations(operations);
+    return seq;
+  }
+
+  // ── diff / patch ───────────────────────────────────────────────────────────
+
+  /**
+   * Compute the changes that turn this tree into `other`, e.g. to reconcile a
+   * saved scene with a live one without a full reload.
+   *
+   * Changes are listed in an order that is safe to apply sequentially with
+   * {@link applyPatch}: additions (parents first), reparentings, transform
+   * moves, then removals (children first).  Transforms are compared exactly.
+   */
+  diff(other: TFTree): TreeChange[] {
+    const theirs = new Map(other.frameRecords().map((frame) => [frame.id, frame]));
+    const added: TreeChange[] = [];
+    const reparented: TreeChange[] = [];
+    const moved: TreeChange[] = [];
+
+    for (const frame of theirs.values()) {
+      const mine = this.frames.get(frame.id);
+      if (mine === undefined) {
+        added.push({ type: "added", ...frame });
+      } else if ((mine.parentId ?? null) !== frame.parentId) {
+        reparented.push({ type: "reparented", ...frame });
+      } else if (!sameTransformJSON(transformToJSON(mine.transform), frame.transform)) {
+        moved.push({ type: "moved", id: frame.id, transform: frame.transform });
+      }
+    }
+
+    const removed: TreeChange[] = [];
+    for (const frame of this.frames.values()) {
+      if (frame.parentId === undefined) {
+        for (const id of this.subtreeIds(frame.id, "dfs-post")) {
+          if (!theirs.has(id)) removed.push({ type: "removed", id });
+        }
+      }
+    }
+
+    return [...added, ...reparented, ...moved, ...removed];
+  }
+
+  /**
+   * Compare this tree with a {@link snapshot} taken earlier — e.g. a
+   * calibration baseline — and report what changed since, ignoring motion
+   * below the thresholds.
+   *
+   * Differences are listed as added frames, reparented frames, moved frames
+   * (with how far they moved), then removed frames.  A reparented frame is
+   * not also reported as moved.
+   */
+  diffAgainst(baseline: TFTreeSnapshot, options: SnapshotDiffOptions = {}): SnapshotDifference[] {
+    const { minTranslation = 0, minRotation = 0, space = "local" } = options;
+    const added: SnapshotDifference[] = [];
+    const reparented: SnapshotDifference[] = [];
+    const moved: SnapshotDifference[] = [];
+    for (const frame of this.frames.values()) {
+      const { id } = frame;
+      const parentId = frame.parentId ?? null;
+      if (!baseline.hasFrame(id)) {
+        added.push({ type: "added", id, parentId });
+        continue;
+      }
+      const previousParentId = baseline.getParentId(id) ?? null;
+      if (previousParentId !== parentId) {
+        reparented.push({ type: "reparented", id, parentId, previousParentId });
+        continue;
+      }
+      const [before, after] =
+        space === "world"
+          ? [baseline.getWorldTransform(id), this.getWorldTransform(id)]
+          : [baseline.getLocalTransform(id), frame.transform];
+      if (sameTransformJSON(transformToJSON(before), transformToJSON(after))) {
+        continue;
+      }
+      const translation = after.translation.subtract(before.translation).length();
+      const rotation = after.rotation.angleTo(before.rotation);
+      if (translation > minTranslation || rotation > minRotation) {
+        moved.push({ type: "moved", id, translation, rotation });
+      }
+    }
+
+    const removed: SnapshotDifference[] = [];
+    for (const id of baseline.frameIds()) {
+      if (!this.frames.has(id)) {
+        removed.push({ type: "removed", id, parentId: baseline.getParentId(id) ?? null });
+      }
+    }
+    return [...added, ...reparented, ...moved, ...removed];
+  }
+
+  /**
+   * Apply changes produced by {@link diff} to this tree.
+   *
+   * The patch is applied atomically: if any change cannot be applied (for
+   * example because this tree has diverged from the diff's source), the tree
+   * is rolled back and the error is rethrown.  Pass `{ validateOnly: true }`
+   * to check that the patch would apply without applying it.
+   *
+   * @throws {Error} if any change references an unknown or conflicting frame.
+   * @throws {FrameLockedError} if a change would move or reparent a locked
+   *                            frame.
+   * @throws {ReadOnlyNamespaceError} if a change touches a read-only namespace.
+   * @throws {TFError} (`INVALID_STATE`) with `validateOnly` inside a
+   *                   transaction.
+   */
+  applyPatch(changes: readonly TreeChange[], options: BatchOptions = {}): void {
+    if (options.validateOnly) {
+      this.dryRun("applyPatch", () => this.applyPatch(changes));
+      return;
+    }
+    this.assertQuota("maxBatchSize", changes.length, []);
+    const apply = () => {
+      for (const change of changes) {
+        switch (change.type) {
+          case "added":
+            this.applyJournalOperation({ ...change, op: "add" });
+            break;
+          case "reparented":
+            this.applyJournalOperation({ ...change, op: "relink" });
+            break;
+          case "moved":
+            this.applyJournalOperation({ ...change, op: "update" });
+            break;
+          case "removed":
+            this.applyJournalOperation({ ...change, op: "remove" });
+            break;
+        }
+      }
+    };
+    if (this.inTransaction()) {
+      apply();
+    } else {
+      this.transaction(apply);
+    }
+  }
+
+  /**
+   * Merge `other`, a replica that diverged from this tree (e.g. edited
+   * offline), into this tree and report the conflicts found.
+   *
+   * Frames only present in `other` are added.  Frames present in both with a
+   * different parent or transform are conflicts, resolved deterministically:
+   * frames in a namespace listed in `options.namespaces` go to the side owning
+   * it, otherwise the side with the later {@link getModifiedAt} stamp wins, and
+   * exact ties go to the greater of the two serialized frames.  Merging either
+   * replica into the other thus gives both the same frames, except where
+   * applying `other`'s side would hit a locked frame or one in a read-only
+   * namespace, or create a cycle — those conflicts keep this tree's side.
+   * Frames only present in this tree are kept: without a common ancestor, a
+   * removal cannot be told apart from an addition.
+   *
+   * The merge is atomic, like {@link applyPatch}.
+   *
+   * @param other   The replica to merge in ("theirs"); it is not modified.
+   * @param options Namespace ownership, as frame id prefixes mapped to the
+   *                owning side.  The longest matching prefix applies.
+   */
+  merge(other: TFTree, options: MergeOptions = {}): MergeReport {
+    const namespaces = Object.entries(options.namespaces ?? {}).sort(
+      ([a], [b]) => b.length - a.length,
+    );
+    const added: string[] = [];
+    const conflicts: MergeConflict[] = [];
+    const apply = () => {
+      // Parent-first, so that every frame's parent exists when it is applied.
+      for (const theirs of other.frameRecords()) {
+        const mine = this.frames.get(theirs.id);
+        const theirTime = other.getModifiedAt(theirs.id);
+        if (mine === undefined) {
+          this.applyJournalOperation({ op: "add", ...theirs });
+          this.modifiedAt.set(theirs.id, theirTime);
+          added.push(theirs.id);
+          continue;
+        }
+        const ours: FrameNodeJSON = {
+          id: mine.id,
+          parentId: mine.parentId ?? null,
+          transform: transformToJSON(mine.transform),
+        };
+        if (
+          ours.parentId === theirs.parentId &&
+          sameTransformJSON(ours.transform, theirs.transform)
+        ) {
+          continue;
+        }
+
+        let winner: MergeSide;
+        let reason: MergeResolution;
+        const owner = namespaces.find(([prefix]) => theirs.id.startsWith(prefix));
+        const ourTime = this.modifiedAt.get(theirs.id)!;
+        if (owner !== undefined) {
+          [winner, reason] = [owner[1], "namespace"];
+        } else if (ourTime !== theirTime) {
+          [winner, reason] = [theirTime > ourTime ? "theirs" : "ours", "timestamp"];
+        } else {
+          const theirsGreater = JSON.stringify(theirs) > JSON.stringify(ours);
+          [winner, reason] = [theirsGreater ? "theirs" : "ours", "tie"];
+        }
+        if (winner === "theirs") {
+          try {
+            this.applyJournalOperation({ op: "relink", ...theirs });
+            this.modifiedAt.set(theirs.id, theirTime);
+          } catch (err) {
+            if (err instanceof FrameLockedError || err instanceof ReadOnlyNamespaceError) {
+              [winner, reason] = ["ours", "locked"];
+            } else if (err instanceof CycleDetectedError) {
+              [winner, reason] = ["ours", "cycle"];
+            } else {
+              throw err;
+            }
+          }
+        }
+        conflicts.push({ frameId: theirs.id, ours, theirs, winner, reason });
+      }
+    };
+    if (this.inTransaction()) {
+      apply();
+    } else {
+      this.transaction(apply);
+    }
+    return { added, conflicts };
+  }
+
+  // ── speculative edits ──────────────────────────────────────────────────────
+
+  /**
+   * Create a shadow tree for speculative edits — e.g. for a planner trying
+   * out motions or a UI previewing one — without risking this tree.
+   *
+   * The fork starts with this tree's frames, aliases, locks and settings,
+   * sharing the immutable frame records so that forking only copies the
+   * indices.  Every edit made to the fork is recorded: {@link promote}
+   * replays them on this tree and {@link discard} drops them.  Listeners,
+   * watched pairs and the journal are not carried over.
+   */
+  fork(): TFTree {
+    const fork = new TFTree();
+    fork.forkBase = this;
+    fork.clock = this.clock;
+    fork.validateInputs = this.validateInputs;
+    fork.rotationNormMode = this.rotationNormMode;
+    fork.rotationNormEpsilon = this.rotationNormEpsilon;
+    fork.frameIdPolicy = this.frameIdPolicy;
+    fork.rosNaming = this.rosNaming;
+    fork.frameResolver = this.frameResolver;
+    fork.exportFilter = this.exportFilter;
+    assignMap(fork.remaps, this.remaps);
+    fork.quotas = { ...this.quotas };
+    fork.authorityPolicy = this.authorityPolicy;
+    for (const [prefix, { policy }] of this.namespaces) {
+      fork.setNamespacePolicy(prefix, policy);
+    }
+    fork.revisionCounter = this.revisionCounter;
+    fork.copyState(this);
+    return fork;
+  }
+
+  /** Returns `true` if this tree was created with {@link fork}. */
+  isFork(): boolean {
+    return this.forkBase !== undefined;
+  }
+
+  /**
+   * Returns the edits made to this fork that {@link promote} would apply,
+   * in order.  Always empty for a tree that is not a fork.
+   */
+  pendingEdits(): JournalOperation[] {
+    return this.forkEdits.slice();
+  }
+
+  /**
+   * Apply the edits made to this fork to the tree it was forked from, then
+   * reset the fork to the resulting state so it can be reused.
+   *
+   * The edits are applied atomically, like {@link applyJournal}: if the base
+   * tree has diverged so that one no longer applies, the base is left
+   * unchanged, the fork keeps its edits and the error is rethrown.
+   *
+   * @throws {TFError} (`INVALID_STATE`) if this tree is not a fork or has a
+   *                   transaction in progress.
+   */
+  promote(): void {
+    const base = this.assertIdleFork("promote");
+    base.applyOperations(this.forkEdits);
+    this.resetToBase(base);
+  }
+
+  /**
+   * Drop the edits made to this fork, resetting it to the current state of
+   * the tree it was forked from.  Change listeners of frames whose world
+   * transform differs are notified; those of frames that no longer exist
+   * are removed.
+   *
+   * @throws {TFError} (`INVALID_STATE`) if this tree is not a fork or has a
+   *                   transaction in progress.
+   */
+  discard(): void {
+    this.resetToBase(this.assertIdleFork("discard"));
+  }
+
+  // ── aliases ────────────────────────────────────────────────────────────────
+
+  /**
+   * Register `alias` as an alternative id for `targetId` (e.g. `base_footprint`
+   * for `base_link`).  Every method that looks up an existing frame accepts the
+   * alias in place of the frame id; results always report canonical ids.
+   *
+   * An alias of an alias resolves to the same canonical frame.  Aliases follow
+   * their frame through {@link renameFrame} and disappear when it is removed.
+   *
+   * @throws {Error} if `alias` is already registered as a frame or alias, or
+   *                 `targetId` is not registered.
+   */
+  addAlias(alias: string, targetId: string): void {
+    alias = this.normalizeId(alias);
+    if (this.frames.has(alias) || this.aliases.has(alias)) {
+      throw new DuplicateFrameError(alias);
+    }
+    const target = this.getFrameNode(targetId);
+    this.aliases.set(alias, target.id);
+    this.lastSnapshot = undefined;
+  }
+
+  /**
+   * Remove a previously registered alias.  The target frame is unaffected.
+   *
+   * @throws {Error} if `alias` is not a registered alias.
+   */
+  removeAlias(alias: string): void {
+    if (!this.aliases.delete(alias)) {
+      throw new TFError("ALIAS_NOT_FOUND", `Alias "${alias}" not found.`, {
+        frameIds: [],
+        operation: "removeAlias",
+      });
+    }
+    this.lastSnapshot = undefined;
+  }
+
+  /** Returns every registered alias mapped to the canonical id it resolves to. */
+  getAliases(): Record<string, string> {
+    return Object.fromEntries(this.aliases);
+  }
+
+  /**
+   * Returns the canonical frame id for `id`: the target frame if `id` is an
+   * alias, otherwise `id` itself (whether or not it is registered).  With
+   * {@link setRosNaming} enabled, `id` is normalized first, falling back to
+   * the unprefixed id when only that one is registered.
+   */
+  resolveAlias(id: string): string {
+    const known = (candidate: string) =>
+      this.frames.has(candidate) || this.aliases.has(candidate);
+    id = resolveFrameId(id, this.rosNaming, this.remaps, known);
+    return this.aliases.get(id) ?? id;
+  }
+
+  // ── locking ────────────────────────────────────────────────────────────────
+
+  /**
+   * Lock a frame so that its stored transform cannot be replaced — e.g. to
+   * protect a calibrated sensor mount from a misbehaving publisher.  While
+   * locked, operations that would rewrite its transform (such as
+   * {@link updateTransform} or {@link setParentKeepWorld}) throw a
+   * {@link FrameLockedError}.
+   *
+   * Locking an already locked frame has no effect.
+   *
+   * @throws {Error} if `id` is not registered.
+   */
+  lockFrame(id: string): void {
+    this.lockedFrames.add(this.getFrameNode(id).id);
+  }
+
+  /**
+   * Unlock a frame previously locked with {@link lockFrame}.  Unlocking a
+   * frame that is not locked has no effect.
+   *
+   * @throws {Error} if `id` is not registered.
+   */
+  unlockFrame(id: string): void {
+    this.lockedFrames.delete(this.getFrameNode(id).id);
+  }
+
+  /** Returns `true` if `id` is registered and currently locked. */
+  isFrameLocked(id: string): boolean {
+    return this.lockedFrames.has(this.resolveAlias(id));
+  }
+
+  // ── authority ──────────────────────────────────────────────────────────────
+
+  /**
+   * Choose how update sources passed as `{ source }` to
+   * {@link updateTransform} / {@link updateTransforms} are treated:
+   * `"record"` (the default) only records them, while `"owner-only"` also
+   * rejects updates from a source other than the frame's owner with an
+   * {@link UnauthorizedSourceError}.  Under `"owner-only"`, the first source
+   * to update an unowned frame becomes its owner.  Updates without a source
+   * are never rejected.
+   */
+  setAuthorityPolicy(policy: AuthorityPolicy): void {
+    this.authorityPolicy = policy;
+  }
+
+  /**
+   * Make `source` the owner of frame `id`, or release it with `undefined`.
+   *
+   * @throws {Error} if `id` is not registered.
+   */
+  setFrameOwner(id: string, source: string | undefined): void {
+    id = this.getFrameNode(id).id;
+    if (source === undefined) {
+      this.frameOwners.delete(id);
+    } else {
+      this.frameOwners.set(id, source);
+    }
+  }
+
+  /** Returns the owner of frame `id`, if any. */
+  getFrameOwner(id: string): string | undefined {
+    return this.frameOwners.get(this.resolveAlias(id));
+  }
+
+  /**
+   * Returns the source of the latest update of frame `id`, or `undefined`
+   * if it was made without one (or the frame was never updated).
+   */
+  getLastSource(id: string): string | undefined {
+    return this.lastSources.get(this.resolveAlias(id));
+  }
+
+  // ── namespaces ─────────────────────────────────────────────────────────────
+
+  /**
+   * Set the policy of the namespace of frames whose id starts with `prefix`
+   * — e.g. to isolate the plugins sharing one tree from each other — or
+   * remove it with `undefined`.  A frame belongs to the namespace with the
+   * longest matching prefix.
+   *
+   * In a `readOnly` namespace, adding, removing, renaming and reparenting
+   * frames, and operations that would replace a frame's transform (as for
+   * {@link lockFrame}), throw a {@link ReadOnlyNamespaceError}; adding a
+   * frame beyond `maxFrames` throws a {@link QuotaExceededError}.  Existing
+   * frames are not re-checked.
+   * Changing the policy of a namespace keeps its statistics.
+   */
+  setNamespacePolicy(prefix: string, policy: NamespacePolicy | undefined): void {
+    const namespace = this.namespaces.get(prefix);
+    if (policy === undefined) {
+      this.namespaces.delete(prefix);
+    } else if (namespace !== undefined) {
+      namespace.policy = { ...policy };
+    } else {
+      this.namespaces.set(prefix, { policy: { ...policy }, mutations: 0, rejected: 0 });
+    }
+  }
+
+  /** Returns the policy of every namespace, keyed by prefix. */
+  getNamespacePolicies(): Record<string, NamespacePolicy> {
+    const policies: Record<string, NamespacePolicy> = {};
+    for (const [prefix, { policy }] of this.namespaces) {
+      policies[prefix] = { ...policy };
+    }
+    return policies;
+  }
+
+  /**
+   * Returns the statistics of every namespace, keyed by prefix: its frame
+   * count and how many mutations of its frames were made and rejected.
+   * Mutations undone by {@link rollback} or made by a `validateOnly` dry run
+   * are not counted.
+   */
+  getNamespaceStats(): Record<string, NamespaceStats> {
+    const frames = new Map<string, number>();
+    for (const id of this.frames.keys()) {
+      const prefix = this.namespaceOf(id);
+      if (prefix !== undefined) frames.set(prefix, (frames.get(prefix) ?? 0) + 1);
+    }
+    const stats: Record<string, NamespaceStats> = {};
+    for (const [prefix, { mutations, rejected }] of this.namespaces) {
+      stats[prefix] = { frames: frames.get(prefix) ?? 0, mutations, rejected };
+    }
+    return stats;
+  }
+
+  // ── camera intrinsics ──────────────────────────────────────────────────────
+
+  /**
+   * Attach pinhole intrinsics to a camera frame, for {@link projectPoints}.
+   * Pass `undefined` to remove them.  Intrinsics follow the frame through
+   * renames and are dropped when it is removed.
+   *
+   * @throws {Error} if `id` is not registered.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if a focal length is not a
+   *                   positive finite number.
+   */
+  setCameraIntrinsics(id: string, intrinsics: CameraIntrinsics | undefined): void {
+    id = this.getFrameNode(id).id;
+    if (intrinsics === undefined) {
+      this.cameraIntrinsics.delete(id);
+      return;
+    }
+    const { fx, fy } = intrinsics;
+    if (!(fx > 0 && fy > 0 && Number.isFinite(fx) && Number.isFinite(fy))) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Focal lengths of frame "${id}" must be positive, got fx=${fx} and fy=${fy}.`,
+        { frameIds: [id], operation: "setCameraIntrinsics" },
+      );
+    }
+    this.cameraIntrinsics.set(id, { ...intrinsics });
+  }
+
+  /** Returns the intrinsics attached to `id`, or `undefined` if there are none. */
+  getCameraIntrinsics(id: string): CameraIntrinsics | undefined {
+    return this.cameraIntrinsics.get(this.resolveAlias(id));
+  }
+
+  /**
+   * Project points given in frame `from` into the image of `cameraFrame` in
+   * one pass — resolving the chain once, then applying the pinhole model of
+   * its {@link setCameraIntrinsics intrinsics} — e.g. for AR overlays.
+   * Points behind the camera, or outside the image when its size is known,
+   * are flagged invalid and get `NaN` pixels.
+   *
+   * @param points Packed `x, y, z` triples given in `from`.
+   * @param out    Destination for the packed `u, v` pairs; a new
+   *               `Float64Array` is allocated when omitted.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `cameraFrame` has no
+   *                   intrinsics, the length of `points` is not a multiple
+   *                   of 3, or `out` is too short.
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  projectPoints<T extends FloatArray = Float64Array>(
+    from: string,
+    cameraFrame: string,
+    points: Float64Array,
+    out?: T,
+  ): ProjectedPoints<T> {
+    const intrinsics = this.getCameraIntrinsics(cameraFrame);
+    const count = points.length / 3;
+    if (
+      intrinsics === undefined ||
+      !Number.isInteger(count) ||
+      (out !== undefined && out.length < count * 2)
+    ) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        intrinsics === undefined
+          ? `Frame "${cameraFrame}" has no camera intrinsics.`
+          : `Expected packed xyz points and an output buffer of ${Math.floor(count) * 2}, ` +
+              `got lengths ${points.length} and ${out?.length ?? "(none)"}.`,
+        { frameIds: [from, cameraFrame], operation: "projectPoints" },
+      );
+    }
+    out ??= new Float64Array(count * 2) as T;
+    const { fx, fy, cx, cy, width = Infinity, height = Infinity } = intrinsics;
+    const m = this.getTransform(cameraFrame, from).toMat4();
+    const valid = new Uint8Array(count);
+    for (let i = 0; i < count; i++) {
+      const x = points[3 * i];
+      const y = points[3 * i + 1];
+      const z = points[3 * i + 2];
+      const cz = m[2] * x + m[6] * y + m[10] * z + m[14];
+      const u = (fx * (m[0] * x + m[4] * y + m[8] * z + m[12])) / cz + cx;
+      const v = (fy * (m[1] * x + m[5] * y + m[9] * z + m[13])) / cz + cy;
+      const inImage = cz > 0 && u >= 0 && u < width && v >= 0 && v < height;
+      valid[i] = inImage ? 1 : 0;
+      out[2 * i] = inImage ? u : NaN;
+      out[2 * i + 1] = inImage ? v : NaN;
+    }
+    return { pixels: out, valid };
+  }
+
+  // ── joints ─────────────────────────────────────────────────────────────────
+
+  /**
+   * Drive frame `frameId` with a joint named `name`, e.g. for each joint of
+   * a robot arm: the frame's local transform becomes `origin` composed with
+   * the joint's motion at its position — a rotation about `axis` for a
+   * revolute joint, a translation along it for a prismatic one, and planar
+   * or free motion for a mobile base or a free body (see {@link JointType}).
+   * Move the joint with {@link setJointPosition} instead of rebuilding the
+   * transform.  Joints follow their frame through renames and are removed
+   * with it.
+   *
+   * With `limits`, positions outside `[lower, upper]` are clamped or
+   * rejected according to the limit policy, so that sliders and controllers
+   * can't drive the joint past its stops.
+   *
+   * @throws {Error} if `frameId` is not registered.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `name` is already used, the
+   *                   frame already has a joint, or the axis, limits, mimic
+   *                   coupling or position are invalid.
+   * @throws {OutOfRangeError} if the initial position is outside the limits
+   *                           and their policy is `"throw"`.
+   */
+  addJoint(name: string, frameId: string, options: JointOptions): void {
+    const frame = this.getFrameNode(frameId);
+    const invalid = (message: string) =>
+      new TFError("INVALID_ARGUMENT", message, { frameIds: [frame.id], operation: "addJoint" });
+    if (this.joints.has(name)) {
+      throw invalid(`Joint "${name}" is already registered.`);
+    }
+    for (const joint of this.joints.values()) {
+      if (joint.frameId === frame.id) {
+        throw invalid(`Frame "${frame.id}" is already driven by joint "${joint.name}".`);
+      }
+    }
+    const axis = options.axis ?? new Vec3(1, 0, 0);
+    const length = axis.length();
+    if (!(length > 0 && Number.isFinite(length))) {
+      throw invalid(`Joint "${name}" has an invalid axis ${axis.toString()}.`);
+    }
+    const singleDof = JOINT_DOF[options.type] === 1;
+    if (!singleDof && (options.limits !== undefined || options.mimic !== undefined)) {
+      throw invalid(`Joint "${name}" is a ${options.type} joint and takes no limits or mimic.`);
+    }
+    let joint: Joint = {
+      name,
+      frameId: frame.id,
+      type: options.type,
+      axis: axis.scale(1 / length),
+      origin: options.origin ?? frame.transform,
+      position: 0,
+      positions: [],
+      limits: this.jointLimits(name, frame.id, options.limits ?? {}, "addJoint"),
+    };
+    let position = options.position ?? zeroJointPosition(options.type);
+    if (options.mimic !== undefined) {
+      const { multiplier = 1, offset = 0 } = options.mimic;
+      const source = this.getJointState(options.mimic.joint, "addJoint");
+      if (!Number.isFinite(multiplier) || !Number.isFinite(offset)) {
+        throw invalid(`Joint "${name}" has an invalid mimic coupling.`);
+      }
+      if (JOINT_DOF[source.type] !== 1) {
+        throw invalid(`Joint "${name}" can't mimic ${source.type} joint "${source.name}".`);
+      }
+      if (options.position !== undefined) {
+        throw invalid(`Joint "${name}" mimics "${source.name}" and takes no position.`);
+      }
+      joint = { ...joint, mimic: { joint: source.name, multiplier, offset } };
+      position = multiplier * source.position + offset;
+    }
+    this.driveJoint(joint, position, "addJoint");
+  }
+
+  /**
+   * Remove joint `name`.  Its frame keeps its current local transform, and
+   * joints mimicking it become independent.
+   *
+   * @throws {TFError} (`INVALID_ARGUMENT`) if the joint is not registered.
+   */
+  removeJoint(name: string): void {
+    this.getJointState(name, "removeJoint");
+    this.deleteJoint(name);
+  }
+
+  /**
+   * Move joint `name` to `position` (radians or tree units, or an array of
+   * coordinates for a planar or floating joint, see {@link JointType}),
+   * recomputing the local transform of its frame.  A position outside the
+   * joint's limits is clamped or rejected according to their policy.
+   * Joints mimicking this one follow, in the same batch.
+   *
+   * @throws {TFError} (`INVALID_ARGUMENT`) if the joint is not registered or
+   *                   is a mimic joint, or `position` has the wrong number
+   *                   of coordinates or a non-finite one.
+   * @throws {OutOfRangeError} if `position` is outside the joint's limits
+   *                           and their policy is `"throw"`.
+   * @throws {FrameLockedError} if the joint's frame is locked.
+   */
+  setJointPosition(name: string, position: number | readonly number[]): void {
+    const joint = this.getJointState(name, "setJointPosition");
+    if (joint.mimic !== undefined) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Joint "${name}" mimics "${joint.mimic.joint}" and can't be moved directly.`,
+        { frameIds: [joint.frameId], operation: "setJointPosition" },
+      );
+    }
+    this.driveJoint(joint, position, "setJointPosition");
+  }
+
+  /**
+   * Move every joint in one call — e.g. once per control cycle from a
+   * robot's joint state.  `positions` holds the coordinates of each joint of
+   * {@link getJoints} in turn: one for a revolute or prismatic joint, three
+   * for a planar one and seven for a floating one.  The frames are updated
+   * as one batch, like
+   * {@link updateTransforms}: atomically, and with a single change
+   * notification.  Joints whose position doesn't change are skipped, and
+   * the positions given for mimic joints are ignored.
+   *
+   * @returns The frames whose world transforms changed.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `positions` doesn't hold the
+   *                   coordinates of every joint or one is not finite.
+   * @throws {OutOfRangeError} if a position is outside its joint's limits
+   *                           and their policy is `"throw"`.
+   * @throws {FrameLockedError} if a moved joint's frame is locked.
+   */
+  setJointPositions(positions: ArrayLike<number>): string[] {
+    const joints = Array.from(this.joints.values());
+    const expected = joints.reduce((sum, joint) => sum + JOINT_DOF[joint.type], 0);
+    if (positions.length !== expected) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Expected ${expected} joint coordinates, got ${positions.length}.`,
+        { frameIds: [], operation: "setJointPositions" },
+      );
+    }
+    const moves: Array<[Joint, number[]]> = [];
+    let offset = 0;
+    for (const joint of joints) {
+      const end = offset + JOINT_DOF[joint.type];
+      if (joint.mimic === undefined) {
+        moves.push([joint, Array.prototype.slice.call(positions, offset, end) as number[]]);
+      }
+      offset = end;
+    }
+    const changed = this.moveJoints(moves, "setJointPositions");
+    // Listeners receive their own copy, so they can't alter the result.
+    this.dispatchChanges(changed.slice());
+    return changed;
+  }
+
+  /**
+   * Replace the limits of joint `name`; omitted bounds are unlimited.  The
+   * current position is then brought within the new limits according to
+   * their policy.
+   *
+   * @throws {TFError} (`INVALID_ARGUMENT`) if the joint is not registered or
+   *                   not single-DoF, or `lower` is greater than `upper`.
+   * @throws {OutOfRangeError} if the current position is outside the new
+   *                           limits and their policy is `"throw"`.
+   */
+  setJointLimits(name: string, limits: JointLimits): void {
+    const joint = this.getJointState(name, "setJointLimits");
+    if (JOINT_DOF[joint.type] !== 1) {
+      throw new TFError("INVALID_ARGUMENT", `Joint "${name}" is a ${joint.type} joint.`, {
+        frameIds: [joint.frameId],
+        operation: "setJointLimits",
+      });
+    }
+    this.driveJoint(
+      { ...joint, limits: this.jointLimits(name, joint.frameId, limits, "setJointLimits") },
+      joint.position,
+      "setJointLimits",
+    );
+  }
+
+  /**
+   * Subscribe to positions clamped by joints whose limit policy is
+   * `"warn"`, e.g. to flag a controller that commands infeasible positions.
+   * Inside a transaction, the clamps are reported on {@link commit}, after
+   * the change listeners, and dropped on {@link rollback}.
+   *
+   * @returns An unsubscribe function that removes the listener when called.
+   */
+  onJointLimit(callback: JointLimitCallback): () => void {
+    this.jointLimitListeners.add(callback);
+    return () => {
+      this.jointLimitListeners.delete(callback);
+    };
+  }
+
+  /**
+   * Returns the position of single-DoF joint `name`.  Read the coordinates
+   * of a planar or floating joint from `getJoint(name).positions`.
+   *
+   * @throws {TFError} (`INVALID_ARGUMENT`) if the joint is not registered or
+   *                   not single-DoF.
+   */
+  getJointPosition(name: string): number {
+    const joint = this.getJointState(name, "getJointPosition");
+    if (JOINT_DOF[joint.type] !== 1) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Joint "${name}" is a ${joint.type} joint with ${joint.positions.length} coordinates.`,
+        { frameIds: [joint.frameId], operation: "getJointPosition" },
+      );
+    }
+    return joint.position;
+  }
+
+  /** Returns joint `name`, or `undefined` if it is not registered. */
+  getJoint(name: string): Joint | undefined {
+    return this.joints.get(name);
+  }
+
+  /** Returns every joint, in registration order. */
+  getJoints(): Joint[] {
+    return Array.from(this.joints.values());
+  }
+
+  /**
+   * Returns the serial chain from `baseFrame` down to `tipFrame`: the frames
+   * in between, their current transforms, and the joints driving them with
+   * their axes and positions — e.g. the input of a controller or an IK
+   * solver.
+   *
+   * @throws {Error} if either frame is not registered.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `baseFrame` is not
+   *                   `tipFrame` or one of its ancestors.
+   */
+  getChain(baseFrame: string, tipFrame: string): KinematicChain {
+    const base = this.getFrameNode(baseFrame).id;
+    const tip = this.getFrameNode(tipFrame).id;
+    const chain = this.chainToRoot(tip);
+    const baseIndex = chain.indexOf(base);
+    if (baseIndex < 0) {
+      throw new TFError("INVALID_ARGUMENT", `Frame "${base}" is not an ancestor of "${tip}".`, {
+        frameIds: [base, tip],
+        operation: "getChain",
+      });
+    }
+    const frameIds = chain.slice(0, baseIndex + 1).reverse();
+    const jointsByFrame = new Map<string, Joint>();
+    for (const joint of this.joints.values()) {
+      jointsByFrame.set(joint.frameId, joint);
+    }
+    const links = frameIds.slice(1).map((frameId): ChainLink => {
+      const transform = this.frames.get(frameId)!.transform;
+      const joint = jointsByFrame.get(frameId);
+      return joint === undefined ? { frameId, transform } : { frameId, transform, joint };
+    });
+    const joints = links.flatMap((link) => (link.joint === undefined ? [] : [link.joint]));
+    return { frameIds, links, joints };
+  }
+
+  /**
+   * Find joint positions that bring `tipFrame` to `target`, a pose relative
+   * to `baseFrame` — e.g. to drag an end effector in a browser UI.  The
+   * solver iterates damped least-squares steps from the current positions,
+   * using the chain's Jacobian and forward kinematics, halves any step that
+   * doesn't reduce the error, and keeps every joint within its limits.
+   *
+   * The revolute and prismatic joints of {@link getChain} are solved for;
+   * mimic joints follow them, and other joints keep their positions.  The
+   * tree is only modified with `{ apply: true }`.
+   *
+   * @returns The positions found, or the closest ones with the reason the
+   *          solver failed.
+   * @throws {Error} if either frame is not registered.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `baseFrame` is not an ancestor
+   *                   of `tipFrame` or no joint between them can be solved
+   *                   for.
+   * @throws {InvalidTransformError} if `target` has a non-finite component.
+   * @throws {FrameLockedError} with `{ apply: true }`, if a solved joint's
+   *                            frame is locked.
+   */
+  solveIk(
+    baseFrame: string,
+    tipFrame: string,
+    target: Transform,
+    options: IkOptions = {},
+  ): IkResult {
+    const {
+      maxIterations = 100,
+      positionTolerance = 1e-4,
+      orientationTolerance = 1e-3,
+      damping = 0.05,
+      positionOnly = false,
+      apply = false,
+    } = options;
+    const { frameIds, links } = this.getChain(baseFrame, tipFrame);
+    const tip = frameIds[frameIds.length - 1];
+    const component = nonFiniteComponent(target);
+    if (component !== undefined) {
+      throw new InvalidTransformError(tip, component, `IK target has a non-finite ${component}.`);
+    }
+    const variables = links.flatMap(({ joint }) =>
+      joint !== undefined && JOINT_DOF[joint.type] === 1 && joint.mimic === undefined
+        ? [joint]
+        : [],
+    );
+    if (variables.length === 0) {
+      throw new TFError("INVALID_ARGUMENT", `No joint to solve for below "${frameIds[0]}".`, {
+        frameIds: [frameIds[0], tip],
+        operation: "solveIk",
+      });
+    }
+    const index = new Map(variables.map((joint, i) => [joint.name, i]));
+    const goal = target.rotation.normalize();
+    const rows = positionOnly ? 3 : 6;
+
+    // Forward kinematics at `q`: the tip's error from the target and the
+    // Jacobian columns of the variables, all relative to the base.
+    const evaluate = (q: readonly number[]) => {
+      let pose = Transform.identity();
+      const axes: Array<[number, number, Joint, Vec3, Vec3]> = [];
+      for (const { transform, joint } of links) {
+        const source = joint === undefined ? undefined : (joint.mimic?.joint ?? joint.name);
+        const i = source === undefined ? undefined : index.get(source);
+        if (joint === undefined || i === undefined) {
+          pose = pose.compose(transform);
+          continue;
+        }
+        const { multiplier, offset } = joint.mimic ?? { multiplier: 1, offset: 0 };
+        const frame = pose.compose(joint.origin);
+        axes.push([i, multiplier, joint, frame.rotation.rotateVec3(joint.axis), frame.translation]);
+        pose = frame.compose(jointMotion(joint, [multiplier * q[i] + offset]));
+      }
+      const columns = variables.map(() => new Array<number>(6).fill(0));
+      for (const [i, multiplier, joint, axis, point] of axes) {
+        const [linear, angular] =
+          joint.type === "revolute"
+            ? [axis.cross(pose.translation.subtract(point)), axis]
+            : [axis, Vec3.zero()];
+        const column = [linear.x, linear.y, linear.z, angular.x, angular.y, angular.z];
+        for (let k = 0; k < 6; k++) columns[i][k] += multiplier * column[k];
+      }
+      const t = target.translation.subtract(pose.translation);
+      const r = rotationVector(goal.multiply(pose.rotation.invert()));
+      const error = [t.x, t.y, t.z, r.x, r.y, r.z].slice(0, rows);
+      return {
+        columns,
+        error,
+        cost: error.reduce((sum, v) => sum + v * v, 0),
+        positionError: t.length(),
+        orientationError: r.length(),
+      };
+    };
+
+    let q = variables.map((joint) => joint.position);
+    let state = evaluate(q);
+    let iterations = 0;
+    let reason: IkFailureReason | undefined;
+    while (
+      state.positionError > positionTolerance ||
+      (!positionOnly && state.orientationError > orientationTolerance)
+    ) {
+      if (iterations === maxIterations) {
+        reason = "max-iterations";
+        break;
+      }
+      iterations++;
+      // Δq = Jᵀ (J Jᵀ + λ² I)⁻¹ e
+      const { columns, error } = state;
+      const a = Array.from({ length: rows }, (_, r) =>
+        Array.from({ length: rows }, (_, c) =>
+          columns.reduce((sum, column) => sum + column[r] * column[c], r === c ? damping ** 2 : 0),
+        ),
+      );
+      const y = solveLinearSystem(a, error.slice());
+      const step = columns.map((column) => y.reduce((sum, v, r) => sum + column[r] * v, 0));
+      // Halve the step until it reduces the error, so that the solver
+      // neither overshoots near singularities nor oscillates at a limit.
+      let accepted = false;
+      for (let scale = 1; scale > 1e-3 && !accepted; scale /= 2) {
+        const candidate = variables.map(({ limits }, i) =>
+          Math.min(Math.max(q[i] + scale * step[i], limits.lower), limits.upper),
+        );
+        const next = evaluate(candidate);
+        if (next.cost < state.cost) {
+          q = candidate;
+          state = next;
+          accepted = true;
+        }
+      }
+      if (!accepted) {
+        reason = "stalled";
+        break;
+      }
+    }
+
+    const positions: Record<string, number> = {};
+    variables.forEach((joint, i) => {
+      positions[joint.name] = q[i];
+    });
+    const { positionError, orientationError } = state;
+    if (reason !== undefined) {
+      return { success: false, reason, positions, iterations, positionError, orientationError };
+    }
+    if (apply) {
+      const moves = variables.map((joint, i): [Joint, number[]] => [joint, [q[i]]]);
+      this.dispatchChanges(this.moveJoints(moves, "solveIk"));
+    }
+    return { success: true, positions, iterations, positionError, orientationError };
+  }
+
+  /**
+   * Build a serial chain of frames from Denavit–Hartenberg parameters, as
+   * manipulators are specified in textbooks and datasheets: the first link
+   * becomes a child of `baseFrame`, and every further link a child of the
+   * previous one.  With the `"modified"` convention, links can also register
+   * joints, so that the chain can then be moved with
+   * {@link setJointPosition}.
+   *
+   * The chain is added atomically: if any link fails, no frame or joint is
+   * added.
+   *
+   * @example
+   * ```ts
+   * const revolute = (name: string) => ({ name, type: "revolute" }) as const;
+   * tf.addDhChain(
+   *   "base",
+   *   [
+   *     { frameId: "link1", a: 0, alpha: 0, d: 0.3, theta: 0, joint: revolute("j1") },
+   *     { frameId: "link2", a: 0, alpha: Math.PI / 2, d: 0, theta: 0, joint: revolute("j2") },
+   *     { frameId: "link3", a: 0.4, alpha: 0, d: 0, theta: 0, joint: revolute("j3") },
+   *   ],
+   *   { convention: "modified" },
+   * );
+   * ```
+   *
+   * @returns The ids of the created frames, from the base outwards.
+   * @throws {Error} if `baseFrame` is not registered or a link frame
+   *                 already is.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if a parameter is not finite, a
+   *                   joint is requested with the `"standard"` convention, or
+   *                   a joint is invalid (see {@link addJoint}).
+   */
+  addDhChain(baseFrame: string, links: readonly DhLink[], options: DhChainOptions = {}): string[] {
+    const convention = options.convention ?? "standard";
+    let parentId = this.getFrameNode(baseFrame).id;
+    for (const link of links) {
+      const { frameId, a, alpha, d, theta, joint } = link;
+      const invalid = (message: string) =>
+        new TFError("INVALID_ARGUMENT", message, { frameIds: [frameId], operation: "addDhChain" });
+      if (![a, alpha, d, theta].every(Number.isFinite)) {
+        throw invalid(`Invalid DH parameters of link "${frameId}".`);
+      }
+      if (joint !== undefined && convention === "standard") {
+        throw invalid(`Joint of link "${frameId}" requires the "modified" DH convention.`);
+      }
+    }
+
+    const ids: string[] = [];
+    const apply = () => {
+      for (const link of links) {
+        const id = this.normalizeId(link.frameId);
+        this.insertFrame(id, parentId, dhTransform(link, convention));
+        ids.push(id);
+        parentId = id;
+        if (link.joint !== undefined) {
+          const { name, type, limits } = link.joint;
+          this.addJoint(name, link.frameId, {
+            type,
+            axis: new Vec3(0, 0, 1),
+            ...(limits === undefined ? {} : { limits }),
+          });
+        }
+      }
+    };
+    if (this.inTransaction()) {
+      apply();
+    } else {
+      this.transaction(apply);
+    }
+    return ids;
+  }
+
+  // ── calibration ────────────────────────────────────────────────────────────
+
+  /**
+   * Register frame `id` as a calibrated frame, e.g. a sensor mount measured
+   * once: add it under `parentId` with `transform` (or update it if it
+   * exists), record how it was calibrated, and lock it (see
+   * {@link lockFrame}) so that no publisher overwrites it.  The frame
+   * counts as static from then on, as if it had never been updated.
+   * Calling this again recalibrates the frame.
+   *
+   * Calibrations follow their frame through renames and are dropped when it
+   * is removed.
+   *
+   * @example
+   * ```ts
+   * tf.setCalibration("lidar", "base_link", mount, {
+   *   method: "target-based",
+   *   residualRms: 0.004,
+   * });
+   * ```
+   *
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `date` is not finite or
+   *                   `residualRms` is negative or not a number.
+   * @throws {Error} if the frame can't be added or updated (see
+   *                 {@link addOrUpdateFrame}).
+   */
+  setCalibration(
+    id: string,
+    parentId: string | undefined,
+    transform: Transform,
+    metadata: CalibrationMetadata,
+  ): void {
+    const { method, date = this.clock(), residualRms } = metadata;
+    if (!Number.isFinite(date) || (residualRms !== undefined && !(residualRms >= 0))) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Invalid calibration date ${date} or residual ${residualRms} of frame "${id}".`,
+        { frameIds: [id], operation: "setCalibration" },
+      );
+    }
+    const apply = () => {
+      this.lockedFrames.delete(this.resolveAlias(id));
+      this.addOrUpdateFrame(id, parentId, transform);
+      const frame = this.getFrameNode(id);
+      this.lockedFrames.add(frame.id);
+      this.updatedFrames.delete(frame.id);
+      this.calibrations.set(frame.id, {
+        frameId: frame.id,
+        transform: frame.transform,
+        method,
+        date,
+        ...(residualRms === undefined ? {} : { residualRms }),
+      });
+    };
+    if (this.inTransaction()) {
+      apply();
+    } else {
+      this.transaction(apply);
+    }
+  }
+
+  /**
+   * Forget the calibration of `id` and unlock the frame, which is kept.
+   *
+   * @returns `false` if `id` had no calibration.
+   */
+  clearCalibration(id: string): boolean {
+    id = this.resolveAlias(id);
+    if (!this.calibrations.delete(id)) {
+      return false;
+    }
+    this.lockedFrames.delete(id);
+    return true;
+  }
+
+  /** Returns the calibration of `id`, or `undefined` if it has none. */
+  getCalibration(id: string): Calibration | undefined {
+    return this.calibrations.get(this.resolveAlias(id));
+  }
+
+  /** Returns every calibrated frame, in calibration order. */
+  getCalibrations(): Calibration[] {
+    return Array.from(this.calibrations.values());
+  }
+
+  /**
+   * Check the calibrated frames, e.g. before a run: report those that were
+   * unlocked or no longer hold their calibrated transform, and those whose
+   * calibration is older or less accurate than allowed.
+   *
+   * @returns The findings, grouped by frame in calibration order.
+   */
+  auditCalibrations(options: CalibrationAuditOptions = {}): CalibrationIssue[] {
+    const { maxAge = Infinity, maxResidual = Infinity } = options;
+    const now = this.clock();
+    const issues: CalibrationIssue[] = [];
+    for (const { frameId, transform, date, residualRms } of this.calibrations.values()) {
+      const report = (kind: CalibrationIssue["kind"], message: string) => {
+        issues.push({ kind, frameId, message });
+      };
+      if (!this.lockedFrames.has(frameId)) {
+        report("unlocked", `Calibrated frame "${frameId}" is not locked.`);
+      }
+      if (!this.frames.get(frameId)!.transform.equals(transform)) {
+        report("modified", `Frame "${frameId}" no longer has its calibrated transform.`);
+      }
+      if (now - date > maxAge) {
+        report("stale", `Calibration of frame "${frameId}" is ${now - date} ms old.`);
+      }
+      if (residualRms !== undefined && residualRms > maxResidual) {
+        report("high-residual", `Calibration of frame "${frameId}" has residual ${residualRms}.`);
+      }
+    }
+    return issues;
+  }
+
+  /**
+   * Solve the hand-eye calibration `AX = XB` for the transform `X` between
+   * a robot's hand and a camera, from captures in several robot
+   * configurations that each pair the hand's pose with the pose of a
+   * calibration target as observed by the camera:
+   *
+   * - `"eye-in-hand"` – the camera rides on the hand and `X` is the camera
+   *   relative to the hand; the target stays fixed in the workspace.
+   * - `"eye-to-hand"` – the camera is fixed and `X` is the camera relative
+   *   to the robot base; the target is held by the hand.
+   *
+   * Every pair of captures gives a motion `A` of the hand and the matching
+   * motion `B` of the camera.  The rotation of `X` is the least-squares
+   * solution of `qA · qX = qX · qB` over all motions, and its translation
+   * then follows by linear least squares.  The captures must rotate the
+   * hand about at least two non-parallel axes.
+   *
+   * @example
+   * ```ts
+   * const hand: Transform[] = [];
+   * const eye: Transform[] = [];
+   * for (const pose of calibrationPoses) {
+   *   await robot.moveTo(pose);
+   *   hand.push(tf.getTransform("base", "gripper"));
+   *   eye.push(detectBoard(await camera.capture()));
+   * }
+   * tf.solveHandEye(hand, eye, { install: { frameId: "camera", parentId: "gripper" } });
+   * ```
+   *
+   * @param handPoses Pose of the hand relative to the robot base, per capture.
+   * @param eyePoses  Pose of the calibration target relative to the camera,
+   *                  per capture.
+   * @returns `X` with the RMS residuals of `AX = XB` over all motions.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if the sequences differ in
+   *                   length, hold fewer than three poses or a non-finite
+   *                   one, or don't rotate about two non-parallel axes.
+   * @throws {Error} if `install` is set and the frame can't be added or
+   *                 updated (see {@link setCalibration}).
+   */
+  solveHandEye(
+    handPoses: readonly Transform[],
+    eyePoses: readonly Transform[],
+    options: HandEyeOptions = {},
+  ): HandEyeResult {
+    const { setup = "eye-in-hand", install } = options;
+    const invalid = (message: string) =>
+      new TFError("INVALID_ARGUMENT", message, { frameIds: [], operation: "solveHandEye" });
+    if (eyePoses.length !== handPoses.length) {
+      throw invalid(`Got ${handPoses.length} hand poses but ${eyePoses.length} eye poses.`);
+    }
+    if (handPoses.length < 3) {
+      throw invalid(`Hand-eye calibration needs at least 3 poses, got ${handPoses.length}.`);
+    }
+    for (const pose of [...handPoses, ...eyePoses]) {
+      const component = nonFiniteComponent(pose);
+      if (component !== undefined) {
+        throw invalid(`Calibration pose has a non-finite ${component}.`);
+      }
+    }
+
+    // With the camera fixed, the inverse hand poses play the part of the
+    // hand poses of the eye-in-hand setup.
+    const hand = setup === "eye-in-hand" ? handPoses : handPoses.map((pose) => pose.invert());
+    const motions: Array<[Transform, Transform]> = [];
+    for (let i = 0; i < hand.length; i++) {
+      for (let j = i + 1; j < hand.length; j++) {
+        const a = hand[j].invert().compose(hand[i]);
+        motions.push([a, eyePoses[j].compose(eyePoses[i].invert())]);
+      }
+    }
+    const axes = motions.map(([a]) => rotationVector(a.rotation)).filter((v) => v.length() > 1e-6);
+    const spread = axes.some((u, k) =>
+      axes.slice(k + 1).some((v) => u.cross(v).length() > 1e-2 * u.length() * v.length()),
+    );
+    if (!spread) {
+      throw invalid("Hand-eye calibration needs rotations about two non-parallel axes.");
+    }
+
+    // Each motion constrains qX to the null space of L(qA) − R(qB), the
+    // matrices of left multiplication by qA and right multiplication by qB.
+    const normal = Array.from({ length: 4 }, () => new Array<number>(4).fill(0));
+    // A and B rotate by the same angle, so take both quaternions with w ≥ 0.
+    const positive = (r: Quaternion) => (r.w < 0 ? new Quaternion(-r.x, -r.y, -r.z, -r.w) : r);
+    for (const [a, b] of motions) {
+      const p = positive(a.rotation);
+      const q = positive(b.rotation);
+      const [sx, sy, sz] = [p.x + q.x, p.y + q.y, p.z + q.z];
+      const [dx, dy, dz, dw] = [p.x - q.x, p.y - q.y, p.z - q.z, p.w - q.w];
+      const m = [
+        [dw, -sz, sy, dx],
+        [sz, dw, -sx, dy],
+        [-sy, sx, dw, dz],
+        [-dx, -dy, -dz, dw],
+      ];
+      for (let r = 0; r < 4; r++) {
+        for (let c = 0; c < 4; c++) {
+          for (let k = 0; k < 4; k++) normal[r][c] += m[k][r] * m[k][c];
+        }
+      }
+    }
+    const { values, vectors } = symmetricEigen(normal);
+    const smallest = values.indexOf(Math.min(...values));
+    const [x, y, z, w] = vectors[smallest];
+    const rotation = new Quaternion(x, y, z, w).normalize();
+
+    // (R_A − I) · t_X = R_X · t_B − t_A
+    const lhs = Array.from({ length: 3 }, () => new Array<number>(3).fill(0));
+    const rhs = [0, 0, 0];
+    const basis = [new Vec3(1, 0, 0), new Vec3(0, 1, 0), new Vec3(0, 0, 1)];
+    for (const [a, b] of motions) {
+      const columns = basis.map((e) => a.rotation.rotateVec3(e).subtract(e));
+      const c = rotation.rotateVec3(b.translation).subtract(a.translation);
+      for (let r = 0; r < 3; r++) {
+        for (let k = 0; k < 3; k++) lhs[r][k] += columns[r].dot(columns[k]);
+        rhs[r] += columns[r].dot(c);
+      }
+    }
+    const [tx, ty, tz] = solveLinearSystem(lhs, rhs);
+    const transform = new Transform(new Vec3(tx, ty, tz), rotation);
+
+    let rotationSquares = 0;
+    let translationSquares = 0;
+    for (const [a, b] of motions) {
+      const left = a.compose(transform);
+      const right = transform.compose(b);
+      const r = rotationVector(left.rotation.multiply(right.rotation.invert()));
+      rotationSquares += r.length() ** 2;
+      translationSquares += left.translation.subtract(right.translation).length() ** 2;
+    }
+    const rotationResidual = Math.sqrt(rotationSquares / motions.length);
+    const translationResidual = Math.sqrt(translationSquares / motions.length);
+    if (install !== undefined) {
+      this.setCalibration(install.frameId, install.parentId, transform, {
+        method: "hand-eye",
+        residualRms: translationResidual,
+      });
+    }
+    return { transform, rotationResidual, translationResidual };
+  }
+
+  // ── geodetic anchors ───────────────────────────────────────────────────────
+
+  /**
+   * Add frame `id` at geodetic coordinates on the WGS84 ellipsoid, so that
+   * GPS-tagged data can be placed in the tree without a geodesy library.
+   * The frame is the local east-north-up frame at that point, in meters,
+   * turned `heading` degrees counterclockwise about up (`x` points east by
+   * default, north with a heading of 90).
+   *
+   * If `parentId` is itself a geo anchor, the frame is placed relative to
+   * that reference anchor; otherwise `parentId` is taken to be an ECEF
+   * (earth-centred, earth-fixed) frame.  The relative placement is computed
+   * in double precision, so prefer a reference anchor to ECEF for
+   * centimeter-level work: tree operations on ECEF-sized translations lose
+   * precision.
+   *
+   * @example
+   * ```ts
+   * tf.addFrame("earth");
+   * tf.addGeoAnchor("site", "earth", 48.8584, 2.2945, 35);
+   * tf.addGeoAnchor("rover", "site", 48.8586, 2.295, 36, 30);
+   * const rover = tf.getTransform("site", "rover"); // ~37 m east, ~22 m north
+   * ```
+   *
+   * @param latitude  Degrees north of the equator.
+   * @param longitude Degrees east of Greenwich.
+   * @param altitude  Meters above the ellipsoid.
+   * @throws {Error} if `parentId` is not registered or `id` already is (see
+   *                 {@link addFrame}).
+   * @throws {TFError} (`INVALID_ARGUMENT`) if a coordinate is not finite or
+   *                   the latitude is outside [-90, 90].
+   */
+  addGeoAnchor(
+    id: string,
+    parentId: string,
+    latitude: number,
+    longitude: number,
+    altitude: number,
+    heading = 0,
+  ): void {
+    const anchor: GeoAnchor = { latitude, longitude, altitude, heading };
+    if (!Object.values(anchor).every(Number.isFinite) || Math.abs(latitude) > 90) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Invalid geodetic coordinates (${latitude}, ${longitude}, ${altitude}) or heading ` +
+          `${heading} of frame "${id}".`,
+        { frameIds: [id], operation: "addGeoAnchor" },
+      );
+    }
+    let transform = geoAnchorPose(anchor);
+    const reference = this.geoAnchors.get(this.resolveAlias(parentId));
+    if (reference !== undefined) {
+      const { translation: origin, rotation } = geoAnchorPose(reference);
+      const { translation: t } = transform;
+      const inverse = new Quaternion(-rotation.x, -rotation.y, -rotation.z, rotation.w);
+      transform = new Transform(
+        rotateExact(inverse, new Vec3(t.x - origin.x, t.y - origin.y, t.z - origin.z)),
+        multiplyExact(inverse, transform.rotation),
+      );
+    }
+    this.addFrame(id, parentId, transform);
+    this.geoAnchors.set(this.getFrameNode(id).id, anchor);
+  }
+
+  /**
+   * Returns the coordinates `id` was added with by {@link addGeoAnchor}, or
+   * `undefined` if it is not a geo anchor.
+   */
+  getGeoAnchor(id: string): GeoAnchor | undefined {
+    return this.geoAnchors.get(this.resolveAlias(id));
+  }
+
+  // ── input validation ───────────────────────────────────────────────────────
+
+  /**
+   * Switch validation of incoming transforms on or off.  Validation is on by
+   * default: {@link addFrame}, {@link updateTransform} and friends throw an
+   * {@link InvalidTransformError} for `NaN` or infinite components instead
+   * of letting them poison the world transforms of a whole subtree.  Trusted
+   * pipelines can turn it off to skip the check.
+   */
+  setInputValidation(enabled: boolean): void {
+    this.validateInputs = enabled;
+  }
+
+  /**
+   * Choose how incoming rotations that are not unit quaternions are handled:
+   *
+   * - `"off"` (the default) – stored as given.
+   * - `"strict"` – rejected with an {@link InvalidTransformError} unless the
+   *   norm is within `epsilon` of 1, e.g. for CI-grade validation.
+   * - `"normalize"` – normalized before being stored, for tolerant
+   *   ingestion of noisy sources.
+   *
+   * @param epsilon Allowed deviation of the norm from 1 in `"strict"` mode.
+   */
+  setRotationNormMode(mode: RotationNormMode, epsilon = 1e-6): void {
+    this.rotationNormMode = mode;
+    this.rotationNormEpsilon = epsilon;
+  }
+
+  /**
+   * Set the rules new frame ids must follow, enforced by {@link addFrame}
+   * and {@link renameFrame} with an {@link InvalidFrameIdError} — so that
+   * garbage ids from malformed messages are rejected at the boundary.
+   * Frames already registered are not re-checked.  Pass `undefined` to
+   * accept any id again.
+   */
+  setFrameIdPolicy(policy: FrameIdPolicy | undefined): void {
+    this.frameIdPolicy = policy;
+  }
+
+  /**
+   * Set resource limits — frame count, children per frame, depth and batch
+   * size — so that a buggy publisher cannot grow the tree without bound.
+   * Operations that would exceed a limit throw a {@link QuotaExceededError}
+   * and change nothing.  Depth and children limits are checked when frames
+   * are added or reparented; existing frames are not re-checked.  Pass `{}`
+   * to lift all limits.
+   */
+  setQuotas(quotas: TreeQuotas): void {
+    this.quotas = { ...quotas };
+  }
+
+  /**
+   * Enable (or, with `undefined`, disable) ROS-compatible frame id
+   * normalization of every id passed in, following ROS 1's `tf::resolve`:
+   *
+   * - an id with leading slashes (`"/base_link"`) is absolute — the slashes
+   *   are stripped and no prefix is applied;
+   * - any other id is prefixed with `tfPrefix`, if set and not already
+   *   present (`"base_link"` → `"robot1/base_link"`).
+   *
+   * New ids are registered under the prefixed id, while lookups of a
+   * relative id fall back to the unprefixed one when only that is
+   * registered — so `"map"` and `"/map"` both find a shared `"map"` frame.
+   * Registered ids are not rewritten, so enable this before adding frames.
+   * Snapshots taken from now on apply the same rules.
+   */
+  setRosNaming(options: RosNamingOptions | undefined): void {
+    if (options?.tfPrefix !== undefined) {
+      options = { tfPrefix: options.tfPrefix.replace(/^\/+|\/+$/g, "") };
+    }
+    this.rosNaming = options;
+    this.lastSnapshot = undefined;
+  }
+
+  /**
+   * Rewrite ids starting with `fromPrefix` to start with `toPrefix` instead,
+   * on ingestion and lookup alike — e.g. to load the same recording for
+   * several simulated robots (`"robot/"` → `"sim3/robot/"`) without touching
+   * its frame ids.  When several prefixes match, the longest wins; remaps
+   * apply after {@link setRosNaming} normalization and are not chained.
+   * Pass `undefined` as `toPrefix` to remove a remap.
+   *
+   * Registered ids are not rewritten, so set remaps before adding frames.
+   */
+  setRemap(fromPrefix: string, toPrefix: string | undefined): void {
+    if (toPrefix === undefined) {
+      this.remaps.delete(fromPrefix);
+    } else {
+      this.remaps.set(fromPrefix, toPrefix);
+    }
+    this.lastSnapshot = undefined;
+  }
+
+  /** Returns every remap set with {@link setRemap}, keyed by source prefix. */
+  getRemaps(): Record<string, string> {
+    return Object.fromEntries(this.remaps);
+  }
+
+  // ── lazy loading ───────────────────────────────────────────────────────────
+
+  /**
+   * Set where frames missing from the tree are looked up: a callback or a
+   * secondary tree, consulted when an added frame names an unknown parent
+   * or a lookup such as {@link getTransform} names an unknown frame — e.g.
+   * to fetch the frames of a large static map on demand.  Frames found are
+   * registered (missing parents first) as if added with {@link addFrame},
+   * so they are only fetched once.  {@link hasFrame} and the other
+   * non-throwing queries never consult it.  Pass `undefined` to remove it.
+   */
+  setFrameResolver(resolver: FrameResolver | TFTree | undefined): void {
+    if (resolver instanceof TFTree) {
+      const tree = resolver;
+      resolver = (id) =>
+        tree.frames.has(id) || tree.loadFrame(id) ? tree.frames.get(id) : undefined;
+    }
+    this.frameResolver = resolver;
+  }
+
+  // ── integrity ──────────────────────────────────────────────────────────────
+
+  /**
+   * Switch expensive self-checks on or off — meant for development builds
+   * and tests, off by default.  While on, every change of world transforms
+   * verifies that `T.compose(T.invert())` is within `epsilon` of identity
+   * for each affected frame, and that every cached world transform matches
+   * one recomputed from scratch, throwing a {@link TFError} with code
+   * `INVALID_STATE` on the first violation — so numerical and invalidation
+   * bugs surface where they happen rather than as a wrong pose later.
+   *
+   * @param epsilon Allowed absolute deviation per component.  Transforms are
+   *                composed in single precision, so keep it well above
+   *                `1e-7` times the largest translation.
+   */
+  setDebugChecks(enabled: boolean, epsilon = 1e-4): void {
+    this.debugChecks = enabled;
+    this.debugCheckEpsilon = epsilon;
+  }
+
+  /**
+   * Replace the clock used to stamp frame modifications (see
+   * {@link getModifiedAt}); defaults to `Date.now`.  Replicas that will be
+   * {@link merge}d should share a time base, e.g. a server-synchronized clock.
+   */
+  setClock(clock: () => number): void {
+    this.clock = clock;
+  }
+
+  /**
+   * Check the invariants the engine relies on — every parent registered, no
+   * cycles, the children index matching the parent links, correct subtree
+   * sizes, no cached state for unregistered frames, and unit-norm rotations —
+   * and report every violation found instead of throwing on the first one.
+   *
+   * A tree only modified through the public API is always valid; this is
+   * meant for tests, debugging and data loaded from untrusted sources.
+   */
+  validate(): ValidationReport {
+    const issues: ValidationIssue[] = [];
+    const report = (kind: ValidationIssue["kind"], frameId: string, message: string) => {
+      issues.push({ kind, frameId, message });
+    };
+
+    for (const [id, frame] of this.frames) {
+      const { parentId } = frame;
+      if (parentId !== undefined && !this.frames.has(parentId)) {
+        report("missing-parent", id, `Parent "${parentId}" of frame "${id}" is not registered.`);
+      }
+      const visited = new Set<string>();
+      for (let current = parentId; current !== undefined; ) {
+        if (current === id || visited.has(current)) {
+          report("cycle", id, `The parent chain of frame "${id}" contains a cycle.`);
+          break;
+        }
+        visited.add(current);
+        current = this.frames.get(current)?.parentId;
+      }
+      if (parentId !== undefined && this.childrenMap.get(parentId)?.has(id) !== true) {
+        report("children-mismatch", id, `Frame "${id}" is missing from its parent's children.`);
+      }
+      for (const childId of this.childrenMap.get(id) ?? []) {
+        if (this.frames.get(childId)?.parentId !== id) {
+          report("children-mismatch", id, `"${childId}" is listed as a child of "${id}".`);
+        }
+      }
+      const rotation = frame.transform.rotation;
+      const norm = Math.hypot(rotation.x, rotation.y, rotation.z, rotation.w);
+      if (!(Math.abs(norm - 1) <= 1e-6)) {
+        report("unnormalized-rotation", id, `Rotation of frame "${id}" has norm ${norm}.`);
+      }
+    }
+
+    // Subtree sizes can only be recomputed once the links are sound.
+    if (issues.every(({ kind }) => kind === "unnormalized-rotation")) {
+      const sizes = new Map<string, number>();
+      for (const [rootId, frame] of this.frames) {
+        if (frame.parentId !== undefined) continue;
+        for (const id of this.subtreeIds(rootId, "dfs-post")) {
+          let expected = 1;
+          for (const childId of this.childrenMap.get(id) ?? []) expected += sizes.get(childId)!;
+          sizes.set(id, expected);
+          if (this.subtreeSizes.get(id) !== expected) {
+            report("subtree-size", id, `Subtree size of "${id}" should be ${expected}.`);
+          }
+        }
+      }
+    }
+
+    const caches: Array<[string, Iterable<string>]> = [
+      ["children index", this.childrenMap.keys()],
+      ["world transform cache", this.worldTransformCache.keys()],
+      ["dirty set", this.dirtySet],
+      ["subtree sizes", this.subtreeSizes.keys()],
+      ["frame versions", this.frameVersions.keys()],
+    ];
+    for (const [name, ids] of caches) {
+      for (const id of ids) {
+        if (!this.frames.has(id)) {
+          report("dangling-entry", id, `The ${name} has an entry for unregistered frame "${id}".`);
+        }
+      }
+    }
+
+    return { valid: issues.length === 0, issues };
+  }
+
+  /**
+   * Flag likely data problems — as opposed to the broken invariants reported
+   * by {@link validate}: (nearly) zero or badly un-normalized rotations,
+   * implausibly large translations, unusually deep chains, and non-root
+   * frames whose transform was never updated after registration.
+   *
+   * @returns The findings, grouped by frame in registration order.
+   */
+  diagnose(options: DiagnoseOptions = {}): Diagnostic[] {
+    const { normTolerance = 1e-3, maxTranslation = 1e6, maxDepth = 64 } = options;
+    const diagnostics: Diagnostic[] = [];
+    const warn = (kind: Diagnostic["kind"], frameId: string, message: string) => {
+      diagnostics.push({ kind, severity: "warning", frameId, message });
+    };
+
+    for (const [id, { parentId, transform }] of this.frames) {
+      const { rotation } = transform;
+      const norm = Math.hypot(rotation.x, rotation.y, rotation.z, rotation.w);
+      if (norm < 1e-6) {
+        warn("degenerate-rotation", id, `Rotation of frame "${id}" is (nearly) zero.`);
+      } else if (Math.abs(norm - 1) > normTolerance) {
+        warn("unnormalized-rotation", id, `Rotation of frame "${id}" has norm ${norm}.`);
+      }
+      const distance = transform.translation.length();
+      if (distance > maxTranslation) {
+        warn("large-translation", id, `Frame "${id}" is ${distance} away from its parent.`);
+      }
+      // Only report the first frame of a chain that crosses the limit.
+      const depth = this.chainToRoot(id).length - 1;
+      if (depth === maxDepth + 1) {
+        warn("deep-chain", id, `Frame "${id}" has ${depth} ancestors.`);
+      }
+      if (parentId !== undefined && !this.updatedFrames.has(id)) {
+        diagnostics.push({
+          kind: "never-updated",
+          severity: "info",
+          frameId: id,
+          message: `Frame "${id}" was never updated after registration.`,
+        });
+      }
+    }
+    return diagnostics;
+  }
+
+  /**
+   * Recompute every cached world transform from scratch and compare it with
+   * the cache, returning the frames whose entry is stale — e.g. when
+   * chasing a pose that stays wrong after a batch update.  Entries already
+   * marked dirty are skipped since they are recomputed on next access.
+   *
+   * @param epsilon Allowed absolute deviation per component.
+   * @returns The mismatches, in cache order; empty if the cache is sound.
+   */
+  verifyCache(epsilon = 1e-6): CacheMismatch[] {
+    const mismatches: CacheMismatch[] = [];
+    for (const [frameId, cached] of this.worldTransformCache) {
+      if (!this.frames.has(frameId) || this.dirtySet.has(frameId)) continue;
+      const expected = this.freshWorldTransform(frameId);
+      if (!cached.equals(expected, epsilon)) {
+        mismatches.push({ frameId, cached, expected });
+      }
+    }
+    return mismatches;
+  }
+
+  /**
+   * Render the hierarchy as an indented text tree, one frame per line with
+   * its local translation and rotation (`x, y, z, w`) and any of the flags
+   * `dirty` (world transform not cached), `locked`, `calibrated` and
+   * `detached`.  Meant
+   * for logs and bug reports; the format is not stable.
+   *
+   * @example
+   * ```
+   * world t=(0.000, 0.000, 0.000) r=(0.000, 0.000, 0.000, 1.000)
+   * └─ robot t=(1.000, 0.000, 0.000) r=(0.000, 0.000, 0.000, 1.000) [dirty]
+   *    └─ camera t=(0.000, 0.000, 0.500) r=(0.000, 0.000, 0.000, 1.000) [dirty]
+   * ```
+   */
+  debugDump(options: DebugDumpOptions = {}): string {
+    const { precision = 3 } = options;
+    const format = (values: number[]) => values.map((v) => v.toFixed(precision)).join(", ");
+    const lines: string[] = [];
+    const dump = (id: string, head: string, indent: string) => {
+      const { translation, rotation } = this.frames.get(id)!.transform;
+      const flags: string[] = [];
+      if (this.dirtySet.has(id) || !this.worldTransformCache.has(id)) flags.push("dirty");
+      if (this.lockedFrames.has(id)) flags.push("locked");
+      if (this.calibrations.has(id)) flags.push("calibrated");
+      if (this.detachedFrames.has(id)) flags.push("detached");
+      lines.push(
+        `${head}${id} t=(${format(translation.toArray())}) r=(${format(rotation.toArray())})` +
+          (flags.length > 0 ? ` [${flags.join(", ")}]` : ""),
+      );
+      const children = Array.from(this.childrenMap.get(id) ?? []);
+      children.forEach((childId, i) => {
+        const last = i === children.length - 1;
+        dump(childId, indent + (last ? "└─ " : "├─ "), indent + (last ? "   " : "│  "));
+      });
+    };
+    for (const [id, frame] of this.frames) {
+      if (frame.parentId === undefined) dump(id, "", "");
+    }
+    return lines.join("\n");
+  }
+
+  // ── query ──────────────────────────────────────────────────────────────────
+
+  /** Returns true if the given frame id (or alias) is registered. */
+  hasFrame(id: string): boolean {
+    return this.frames.has(this.resolveAlias(id));
+  }
+
+  /** Returns all registered frame ids. */
+  frameIds(): string[] {
+    return Array.from(this.frames.keys());
+  }
+
+  /**
+   * Returns the ids of all frames matching a glob-style `pattern`, in
+   * registration order.
+   *
+   * `*` matches within a single `/`-separated segment, `**` matches across
+   * segments and `?` matches one non-`/` character, so `"camera/*"` selects
+   * the direct members of the `camera/` namespace while `"robot1/**"` selects
+   * everything beneath `robot1/`.
+   */
+  findFrames(pattern: string): string[] {
+    const re = compileFramePattern(pattern);
+    return this.frameIds().filter((id) => re.test(id));
+  }
+
+  /**
+   * Returns the stored transform of `id` relative to its parent — the value
+   * last supplied to {@link addFrame} or {@link updateTransform}.
+   *
+   * @throws {Error} if `id` is not registered.
+   */
+  getLocalTransform(id: string): Transform {
+    return this.getFrameNode(id).transform;
+  }
+
+  /**
+   * Returns the number of frames in the subtree rooted at `id`, including `id`
+   * itself.  Counts are maintained incrementally, so this is O(1).
+   *
+   * @throws {Error} if `id` is not registered.
+   */
+  subtreeSize(id: string): number {
+    id = this.getFrameNode(id).id;
+    return this.subtreeSizes.get(id)!;
+  }
+
+  /**
+   * Returns the tree revision: a counter bumped by every mutation, structural
+   * or transform (and by {@link rollback}).  Comparing it with a previously
+   * stored value is a cheap "has anything changed since I last rendered?"
+   * check.
+   */
+  revision(): number {
+    return this.revisionCounter;
+  }
+
+  /**
+   * Returns the version of `id`: a counter that increases whenever the world
+   * transform of `id` may have changed — i.e. when it or any ancestor is
+   * updated or reparented.  Caches of derived data can store the version
+   * they were computed at and compare instead of subscribing to events.
+   *
+   * Versions are drawn from a tree-wide clock, so a frame that is removed
+   * and registered again never repeats an earlier version.
+   *
+   * @throws {Error} if `id` is not registered.
+   */
+  getVersion(id: string): number {
+    return this.frameVersions.get(this.getFrameNode(id).id)!;
+  }
+
+  /**
+   * Returns when frame `id` was last added, updated, reparented or renamed,
+   * as read from the clock set with {@link setClock}.  Used by {@link merge}
+   * to resolve conflicts.
+   *
+   * @throws {Error} if `id` is not registered.
+   */
+  getModifiedAt(id: string): number {
+    return this.modifiedAt.get(this.getFrameNode(id).id)!;
+  }
+
+  /**
+   * Returns the ids of `rootId` and all of its descendants in the requested
+   * `order`.  Siblings are visited in registration order, so the result is
+   * deterministic for a given sequence of mutations.
+   *
+   * @param rootId Frame at which the traversal starts (included in the result).
+   * @param order  Visiting order; defaults to depth-first pre-order.
+   * @throws {Error} if `rootId` is not registered.
+   */
+  traverse(rootId: string, order: TraversalOrder = "dfs-pre"): string[] {
+    return this.subtreeIds(this.getFrameNode(rootId).id, order);
+  }
+
+  /** {@link traverse} from the frame registered as `rootId`, which is not an alias. */
+  private subtreeIds(rootId: string, order: TraversalOrder = "dfs-pre"): string[] {
+    if (order === "bfs") {
+      const queue = [rootId];
+      for (let i = 0; i < queue.length; i++) {
+        for (const childId of this.childrenMap.get(queue[i]) ?? []) {
+          queue.push(childId);
+        }
+      }
+      return queue;
+    }
+
+    const result: string[] = [];
+    const stack = [rootId];
+    while (stack.length > 0) {
+      const id = stack.pop()!;
+      result.push(id);
+      const children = Array.from(this.childrenMap.get(id) ?? []);
+      if (order === "dfs-pre") {
+        // Push in reverse so the first-registered child is popped first.
+        for (let i = children.length - 1; i >= 0; i--) stack.push(children[i]);
+      } else {
+        // Visiting children last-to-first and reversing the whole sequence
+        // yields a left-to-right post-order.
+        for (const childId of children) stack.push(childId);
+      }
+    }
+    return order === "dfs-pre" ? result : result.reverse();
+  }
+
+  /**
+   * Compute the transform that maps points expressed in `from` to the
+   * coordinate system of `to`.
+   *
+   * In other words the returned transform `T` satisfies:
+   *   `p_to = T.transformPoint(p_from)`
+   *
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected in the
+   *                                   same tree.
+   */
+  getTransform(from: string, to: string): Transform {
+    return this.relativeTransform(this.resolveAlias(from), this.resolveAlias(to));
+  }
+
+  /** {@link getTransform} between frames registered as `from` and `to`, which are not aliases. */
+  private relativeTransform(from: string, to: string): Transform {
+    if (!this.frames.has(from) && !this.loadFrame(from)) {
+      throw new FrameNotFoundError(from);
+    }
+    if (!this.frames.has(to) && !this.loadFrame(to)) {
+      throw new FrameNotFoundError(to);
+    }
+    if (from === to) {
+      return Transform.identity();
+    }
+
+    if (this.lowestCommonAncestor(from, to) === undefined) {
+      throw new DisconnectedFramesError(from, to);
+    }
+
+    // Use cached world transforms to compute the relative transform.
+    return this.getWorldTransform(from).invert().compose(this.getWorldTransform(to));
+  }
+
+  /**
+   * Returns `true` if `a` and `b` are both registered and belong to the same
+   * tree, i.e. {@link getTransform} between them would succeed.
+   *
+   * Unlike {@link getTransform} this never throws for unknown frames and does
+   * not compute any transforms.
+   */
+  isConnected(a: string, b: string): boolean {
+    return this.connected(this.resolveAlias(a), this.resolveAlias(b));
+  }
+
+  /** {@link isConnected} for ids that are not aliases. */
+  private connected(a: string, b: string): boolean {
+    if (!this.frames.has(a) || !this.frames.has(b)) {
+      return false;
+    }
+    return this.rootOf(a) === this.rootOf(b);
+  }
+
+  /**
+   * Returns a read-only {@link TFTreeSnapshot} of the current state, whose
+   * queries are unaffected by later mutations — e.g. to render a whole frame
+   * from one consistent state while producers keep updating the tree.
+   *
+   * Taking a snapshot copies the frame index but shares the frame records
+   * and transforms.  Calls without mutations in between return the same
+   * snapshot.
+   */
+  snapshot(): TFTreeSnapshot {
+    if (this.lastSnapshot?.revision !== this.revisionCounter) {
+      const rosNaming = this.rosNaming;
+      const remaps = new Map(this.remaps);
+      const frames = new Map(this.frames);
+      const aliases = new Map(this.aliases);
+      const known = (id: string) => frames.has(id) || aliases.has(id);
+      this.lastSnapshot = new TFTreeSnapshot(this.revisionCounter, frames, aliases, (id) =>
+        resolveFrameId(id, rosNaming, remaps, known),
+      );
+    }
+    return this.lastSnapshot;
+  }
+
+  /**
+   * Publish the current state as the front buffer returned by {@link front}.
+   * In double-buffered use, writers mutate the tree (the back buffer) freely
+   * and call `swap()` once a consistent state is complete; readers only use
+   * {@link front}, so they never see a half-applied set of updates.  To
+   * share the front buffer with workers, see {@link SharedTransformBuffer}.
+   *
+   * @returns The new front buffer.
+   */
+  swap(): TFTreeSnapshot {
+    this.frontSnapshot = this.snapshot();
+    return this.frontSnapshot;
+  }
+
+  /**
+   * Returns the state published by the last {@link swap} — an empty
+   * snapshot before the first one.
+   */
+  front(): TFTreeSnapshot {
+    return this.frontSnapshot;
+  }
+
+  /**
+   * Returns the smallest connected set of frames that contains every id in
+   * `ids` together with the paths linking them, rooted at their lowest common
+   * ancestor.
+   *
+   * Frames are listed in depth-first pre-order starting at `rootId`, so
+   * parents always precede their children.
+   *
+   * @throws {Error} if `ids` is empty, any id is not registered, or the
+   *                 frames are not connected in the same tree.
+   */
+  coveringSubtree(ids: readonly string[]): CoveringSubtree {
+    if (ids.length === 0) {
+      throw new TFError("INVALID_ARGUMENT", "coveringSubtree() requires at least one frame id.", {
+        frameIds: [],
+        operation: "coveringSubtree",
+      });
+    }
+    ids = ids.map((id) => this.getFrameNode(id).id);
+
+    let rootId = ids[0];
+    for (const id of ids) {
+      const lca = this.lowestCommonAncestor(rootId, id);
+      if (lca === undefined) {
+        throw new DisconnectedFramesError(rootId, id);
+      }
+      rootId = lca;
+    }
+
+    // Each frame contributes its path up to (and including) the common root.
+    const members = new Set<string>();
+    for (const id of ids) {
+      let current: string | undefined = id;
+      while (current !== undefined && !members.has(current)) {
+        members.add(current);
+        if (current === rootId) break;
+        current = this.frames.get(current)!.parentId;
+      }
+    }
+
+    const frameIds = this.subtreeIds(rootId).filter((id) => members.has(id));
+    return { rootId, frameIds };
+  }
+
+  /**
+   * Groups all registered frames by the root of the tree they belong to.
+   *
+   * A healthy scene usually has exactly one component; more than one often
+   * means a frame was published before its intended parent (the classic
+   * "two trees" problem).  Components are listed in root registration order
+   * and each component's frames in depth-first pre-order.
+   */
+  getComponents(): FrameComponent[] {
+    const components: FrameComponent[] = [];
+    for (const frame of this.frames.values()) {
+      if (frame.parentId === undefined) {
+        components.push({ rootId: frame.id, frameIds: this.subtreeIds(frame.id) });
+      }
+    }
+    return components;
+  }
+
+  // ── spatial queries ────────────────────────────────────────────────────────
+
+  /**
+   * Returns the ids of all frames whose origin lies within `radius` of
+   * `point`, where `point` is expressed in `referenceFrame`.
+   *
+   * Only frames in the same tree as `referenceFrame` are considered; results
+   * are listed in depth-first pre-order from that tree's root.
+   *
+   * @throws {Error} if `referenceFrame` is not registered.
+   */
+  framesWithinRadius(referenceFrame: string, point: Vec3, radius: number): string[] {
+    const result: string[] = [];
+    for (const [id, origin] of this.frameOriginsIn(referenceFrame)) {
+      if (origin.subtract(point).length() <= radius) {
+        result.push(id);
+      }
+    }
+    return result;
+  }
+
+  /**
+   * Returns the ids of the `k` frames whose origins are closest to `point`
+   * (expressed in `referenceFrame`), nearest first.
+   *
+   * Only frames in the same tree as `referenceFrame` are considered.  Ties
+   * keep depth-first pre-order from the tree's root.
+   *
+   * @throws {Error} if `referenceFrame` is not registered.
+   */
+  nearestFrames(referenceFrame: string, point: Vec3, k: number): string[] {
+    return this.sortedByDistance(referenceFrame, point)
+      .slice(0, Math.max(0, k))
+      .map(({ id }) => id);
+  }
+
+  /**
+   * Returns the frames of the tree containing `referenceFrame`, excluding the
+   * reference frame itself, paired with the distance from their origin to the
+   * reference frame's origin and sorted nearest first.
+   *
+   * @param referenceFrame Frame to measure distances from.
+   * @param limit          Maximum number of entries to return; unlimited when
+   *                       omitted.
+   * @throws {Error} if `referenceFrame` is not registered.
+   */
+  framesByDistance(referenceFrame: string, limit = Infinity): FrameDistance[] {
+    referenceFrame = this.resolveAlias(referenceFrame);
+    return this.sortedByDistance(referenceFrame, Vec3.zero())
+      .filter(({ id }) => id !== referenceFrame)
+      .slice(0, Math.max(0, limit));
+  }
+
+  /**
+   * Returns the straight-line distance between the origins of frames `a`
+   * and `b`.
+   *
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  distanceBetween(a: string, b: string): number {
+    return this.getTransform(a, b).translation.length();
+  }
+
+  /**
+   * Returns the displacement from the origin of frame `a` to the origin of
+   * frame `b`, expressed in `referenceFrame` — e.g. the heading to steer a
+   * follower along, in its own frame.
+   *
+   * @param referenceFrame Frame whose axes the vector is expressed in;
+   *                       defaults to `a`.
+   * @throws {FrameNotFoundError} if any frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  relativePosition(a: string, b: string, referenceFrame = a): Vec3 {
+    const displacement = this.getTransform(a, b).translation;
+    return this.transformVector(a, referenceFrame, displacement);
+  }
+
+  /**
+   * Returns the axis-aligned bounding box, expressed in `referenceFrame`, of
+   * the origins of `rootId` and all of its descendants — e.g. to fit a camera
+   * to a selected subtree.
+   *
+   * @throws {Error} if either frame is not registered or the frames are not
+   *                 connected in the same tree.
+   */
+  subtreeAabb(rootId: string, referenceFrame: string): AABB {
+    rootId = this.resolveAlias(rootId);
+    referenceFrame = this.resolveAlias(referenceFrame);
+    // Validates both ids and their connectivity.
+    this.relativeTransform(referenceFrame, rootId);
+
+    const referenceInv = this.getWorldTransform(referenceFrame).invert();
+    let min = new Vec3(Infinity, Infinity, Infinity);
+    let max = new Vec3(-Infinity, -Infinity, -Infinity);
+    for (const id of this.subtreeIds(rootId)) {
+      const p = referenceInv.transformPoint(this.getWorldTransform(id).translation);
+      min = new Vec3(Math.min(min.x, p.x), Math.min(min.y, p.y), Math.min(min.z, p.z));
+      max = new Vec3(Math.max(max.x, p.x), Math.max(max.y, p.y), Math.max(max.z, p.z));
+    }
+    return { min, max };
+  }
+
+  // ── point transformation ───────────────────────────────────────────────────
+
+  /**
+   * Re-express a point given in frame `from` in frame `to` — e.g. where a
+   * detection in the camera frame lies in the map frame.
+   *
+   * @example
+   * ```ts
+   * const inMap = tf.transformPoint("camera", "map", detection);
+   * ```
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformPoint(from: string, to: string, point: Vec3): Vec3 {
+    return this.getTransform(to, from).transformPoint(point);
+  }
+
+  /**
+   * Re-express many points at once: `points` holds packed `x, y, z`
+   * triples given in frame `from`, and the results, expressed in `to`, are
+   * written to `out` in the same layout.  The transform is resolved once
+   * for the whole buffer, which makes this far cheaper than calling
+   * {@link transformPoint} per point, e.g. for lidar returns.
+   *
+   * @param out Destination buffer — e.g. a `Float32Array` view headed for
+   *            the GPU, or one over a `SharedArrayBuffer` — written without
+   *            intermediate copies; a `Float64Array` is allocated when
+   *            omitted.  May be `points` itself to transform in place.
+   * @returns `out`.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if the length of `points` is not a
+   *                   multiple of 3 or `out` is shorter than `points`.
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformPoints<T extends FloatArray = Float64Array>(
+    from: string,
+    to: string,
+    points: Float64Array,
+    out: T = new Float64Array(points.length) as T,
+  ): T {
+    if (points.length % 3 !== 0 || out.length < points.length) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Expected packed xyz points and an output buffer at least as long, got lengths ` +
+          `${points.length} and ${out.length}.`,
+        { frameIds: [from, to], operation: "transformPoints" },
+      );
+    }
+    transformPacked(this.getTransform(to, from).toMat4(), points, out, 3, 0);
+    return out;
+  }
+
+  /**
+   * Bulk {@link transformPoint} where every point carries its own source
+   * frame — e.g. fusing detections from many cameras into one display
+   * frame.  Point `i` (elements `3i … 3i+2` of `points`) is given in
+   * `frameIds[i]`; each distinct frame is resolved only once.
+   *
+   * @param out Destination buffer, as for {@link transformPoints}.
+   * @returns `out`.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `points` does not hold exactly
+   *                   one triple per frame id or `out` is shorter.
+   * @throws {FrameNotFoundError} if any frame is not registered.
+   * @throws {DisconnectedFramesError} if any frame is not connected to
+   *                                   `targetFrame`.
+   */
+  transformPointsMulti<T extends FloatArray = Float64Array>(
+    targetFrame: string,
+    frameIds: readonly string[],
+    points: Float64Array,
+    out: T = new Float64Array(points.length) as T,
+  ): T {
+    if (points.length !== frameIds.length * 3 || out.length < points.length) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Expected one xyz triple per frame id (${frameIds.length}) and an output buffer at ` +
+          `least as long, got lengths ${points.length} and ${out.length}.`,
+        { frameIds: [targetFrame], operation: "transformPointsMulti" },
+      );
+    }
+    const matrices = new Map<string, Float32Array>();
+    frameIds.forEach((frameId, i) => {
+      let m = matrices.get(frameId);
+      if (m === undefined) {
+        m = this.getTransform(targetFrame, frameId).toMat4();
+        matrices.set(frameId, m);
+      }
+      const x = points[3 * i];
+      const y = points[3 * i + 1];
+      const z = points[3 * i + 2];
+      out[3 * i] = m[0] * x + m[4] * y + m[8] * z + m[12];
+      out[3 * i + 1] = m[1] * x + m[5] * y + m[9] * z + m[13];
+      out[3 * i + 2] = m[2] * x + m[6] * y + m[10] * z + m[14];
+    });
+    return out;
+  }
+
+  /**
+   * Re-express a free vector — a direction, surface normal or velocity —
+   * given in frame `from` in frame `to`.  Unlike {@link transformPoint}
+   * only the rotation is applied: vectors have no position, so the
+   * translation between the frames must not be added.
+   *
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformVector(from: string, to: string, vector: Vec3): Vec3 {
+    return this.getTransform(to, from).rotation.rotateVec3(vector);
+  }
+
+  /**
+   * Bulk {@link transformVector} over packed `x, y, z` triples, with the same
+   * buffer rules as {@link transformPoints}.
+   *
+   * @returns `out`.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if the length of `vectors` is not
+   *                   a multiple of 3 or `out` is shorter than `vectors`.
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformVectors<T extends FloatArray = Float64Array>(
+    from: string,
+    to: string,
+    vectors: Float64Array,
+    out: T = new Float64Array(vectors.length) as T,
+  ): T {
+    if (vectors.length % 3 !== 0 || out.length < vectors.length) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Expected packed xyz vectors and an output buffer at least as long, got lengths ` +
+          `${vectors.length} and ${out.length}.`,
+        { frameIds: [from, to], operation: "transformVectors" },
+      );
+    }
+    const rotation = new Transform(Vec3.zero(), this.getTransform(to, from).rotation);
+    transformPacked(rotation.toMat4(), vectors, out, 3, 0);
+    return out;
+  }
+
+  /**
+   * Re-express an orientation — e.g. an IMU attitude — given relative to
+   * frame `from` relative to frame `to`, without going through a dummy
+   * {@link Transform}.
+   *
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformQuaternion(from: string, to: string, orientation: Quaternion): Quaternion {
+    return this.getTransform(to, from).rotation.multiply(orientation);
+  }
+
+  /**
+   * Bulk {@link transformQuaternion} over packed `x, y, z, w` quaternions,
+   * with the same buffer rules as {@link transformPoints}.
+   *
+   * @returns `out`.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if the length of `orientations` is
+   *                   not a multiple of 4 or `out` is shorter.
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformQuaternions<T extends FloatArray = Float64Array>(
+    from: string,
+    to: string,
+    orientations: Float64Array,
+    out: T = new Float64Array(orientations.length) as T,
+  ): T {
+    if (orientations.length % 4 !== 0 || out.length < orientations.length) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Expected packed xyzw quaternions and an output buffer at least as long, got ` +
+          `lengths ${orientations.length} and ${out.length}.`,
+        { frameIds: [from, to], operation: "transformQuaternions" },
+      );
+    }
+    const { x: ax, y: ay, z: az, w: aw } = this.getTransform(to, from).rotation;
+    for (let i = 0; i < orientations.length; i += 4) {
+      const bx = orientations[i];
+      const by = orientations[i + 1];
+      const bz = orientations[i + 2];
+      const bw = orientations[i + 3];
+      out[i] = aw * bx + ax * bw + ay * bz - az * by;
+      out[i + 1] = aw * by - ax * bz + ay * bw + az * bx;
+      out[i + 2] = aw * bz + ax * by - ay * bx + az * bw;
+      out[i + 3] = aw * bw - ax * bx - ay * by - az * bz;
+    }
+    return out;
+  }
+
+  /**
+   * Re-express an axis-aligned box given in frame `from` in frame `to`,
+   * returning the smallest box aligned with `to`'s axes that encloses it.
+   * All eight corners are transformed, so rotations between the frames grow
+   * the box as needed — e.g. for culling or region-of-interest checks.
+   *
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformAabb(from: string, to: string, box: AABB): AABB {
+    const transform = this.getTransform(to, from);
+    let min = new Vec3(Infinity, Infinity, Infinity);
+    let max = new Vec3(-Infinity, -Infinity, -Infinity);
+    for (const x of [box.min.x, box.max.x]) {
+      for (const y of [box.min.y, box.max.y]) {
+        for (const z of [box.min.z, box.max.z]) {
+          const p = transform.transformPoint(new Vec3(x, y, z));
+          min = new Vec3(Math.min(min.x, p.x), Math.min(min.y, p.y), Math.min(min.z, p.z));
+          max = new Vec3(Math.max(max.x, p.x), Math.max(max.y, p.y), Math.max(max.z, p.z));
+        }
+      }
+    }
+    return { min, max };
+  }
+
+  /**
+   * Re-express an oriented box given in frame `from` in frame `to`.  Unlike
+   * {@link transformAabb} this is exact: the centre is moved, the rotation
+   * composed and the extents kept.
+   *
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformObb(from: string, to: string, box: OBB): OBB {
+    const transform = this.getTransform(to, from);
+    return {
+      center: transform.transformPoint(box.center),
+      halfExtents: box.halfExtents,
+      rotation: transform.rotation.multiply(box.rotation).normalize(),
+    };
+  }
+
+  /**
+   * Re-express a ray given in frame `from` in frame `to`, for picking and
+   * ray casting: the origin is transformed as a point, the direction only
+   * rotated (see {@link transformVector}).
+   *
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformRay(from: string, to: string, ray: Ray): Ray {
+    const transform = this.getTransform(to, from);
+    return {
+      origin: transform.transformPoint(ray.origin),
+      direction: transform.rotation.rotateVec3(ray.direction),
+    };
+  }
+
+  /**
+   * Bulk {@link transformRay}: `rays` holds packed `ox, oy, oz, dx, dy, dz`
+   * sextuples given in frame `from`, and the results, expressed in `to`,
+   * are written to `out` in the same layout.
+   *
+   * @param out Destination buffer, as for {@link transformPoints}.  May be
+   *            `rays` itself to transform in place.
+   * @returns `out`.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if the length of `rays` is not a
+   *                   multiple of 6 or `out` is shorter than `rays`.
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformRays<T extends FloatArray = Float64Array>(
+    from: string,
+    to: string,
+    rays: Float64Array,
+    out: T = new Float64Array(rays.length) as T,
+  ): T {
+    if (rays.length % 6 !== 0 || out.length < rays.length) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Expected packed rays of 6 elements and an output buffer at least as long, got ` +
+          `lengths ${rays.length} and ${out.length}.`,
+        { frameIds: [from, to], operation: "transformRays" },
+      );
+    }
+    const transform = this.getTransform(to, from);
+    transformPacked(transform.toMat4(), rays, out, 6, 0);
+    transformPacked(new Transform(Vec3.zero(), transform.rotation).toMat4(), rays, out, 6, 3);
+    return out;
+  }
+
+  /**
+   * Rotate a 6×6 pose covariance given in frame `from` into frame `to`, as
+   * `R₆ · C · R₆ᵀ` with `R₆ = diag(R, R)` — the convention of ROS
+   * `PoseWithCovariance`, with row-major `x, y, z, roll, pitch, yaw` order.
+   * Translation between the frames does not affect the covariance.
+   *
+   * @param covariance 36 row-major entries.
+   * @param out        Destination for the 36 row-major results, as for
+   *                   {@link transformPoints}.  May be `covariance` itself.
+   * @returns `out`.
+   * @throws {TFError} (`INVALID_ARGUMENT`) if `covariance` does not have 36
+   *                   entries.
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformCovariance<T extends FloatArray = Float64Array>(
+    from: string,
+    to: string,
+    covariance: FloatArray | readonly number[],
+    out: T = new Float64Array(36) as T,
+  ): T {
+    if (covariance.length !== 36 || out.length < 36) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Expected a 6x6 covariance of 36 entries, got ${covariance.length} and an output ` +
+          `buffer of ${out.length}.`,
+        { frameIds: [from, to], operation: "transformCovariance" },
+      );
+    }
+    const m = new Transform(Vec3.zero(), this.getTransform(to, from).rotation).toMat4();
+    // R₆[r][c]: the 3×3 rotation (column-major in m) repeated on the diagonal.
+    const rotation = (r: number, c: number) =>
+      Math.floor(r / 3) === Math.floor(c / 3) ? m[(c % 3) * 4 + (r % 3)] : 0;
+    const rc = new Float64Array(36);
+    for (let r = 0; r < 6; r++) {
+      for (let c = 0; c < 6; c++) {
+        let sum = 0;
+        for (let k = 0; k < 6; k++) sum += rotation(r, k) * covariance[k * 6 + c];
+        rc[r * 6 + c] = sum;
+      }
+    }
+    for (let r = 0; r < 6; r++) {
+      for (let c = 0; c < 6; c++) {
+        let sum = 0;
+        for (let k = 0; k < 6; k++) sum += rc[r * 6 + k] * rotation(c, k);
+        out[r * 6 + c] = sum;
+      }
+    }
+    return out;
+  }
+
+  /**
+   * Transform the points of an interleaved sensor cloud (`Float32Array` or
+   * `Float64Array`) from frame `from` to frame `to` without repacking: each
+   * point spans `stride` elements with `x, y, z` starting at `offset`, and
+   * the remaining channels (intensity, colour, …) are left untouched.  The
+   * cloud is transformed in place unless `options.out` is given.
+   *
+   * @example
+   * ```ts
+   * // XYZI cloud, transformed in place.
+   * tf.transformPointCloud("lidar", "map", cloud, { stride: 4 });
+   * ```
+   * @returns The transformed buffer (`options.out` or `cloud`).
+   * @throws {TFError} (`INVALID_ARGUMENT`) if the layout does not fit a whole
+   *                   number of points or `out` differs in length.
+   * @throws {FrameNotFoundError} if either frame is not registered.
+   * @throws {DisconnectedFramesError} if the frames are not connected.
+   */
+  transformPointCloud(
+    from: string,
+    to: string,
+    cloud: FloatArray,
+    options: PointCloudOptions = {},
+  ): FloatArray {
+    const { stride = 3, offset = 0, out = cloud } = options;
+    if (
+      !Number.isInteger(stride) ||
+      !Number.isInteger(offset) ||
+      offset < 0 ||
+      offset + 3 > stride ||
+      cloud.length % stride !== 0 ||
+      out.length !== cloud.length
+    ) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Invalid point cloud layout: stride ${stride}, offset ${offset}, lengths ` +
+          `${cloud.length} and ${out.length}.`,
+        { frameIds: [from, to], operation: "transformPointCloud" },
+      );
+    }
+    const m = this.getTransform(to, from).toMat4();
+    if (out !== cloud) out.set(cloud);
+    transformPacked(m, cloud, out, stride, offset);
+    return out;
+  }
+
+  // ── event subscription ─────────────────────────────────────────────────────
+
+  /**
+   * Subscribe to world-transform changes for `frameId`.
+   *
+   * The `callback` is fired whenever the world transform of `frameId` changes —
+   * either because `frameId` itself was updated via {@link updateTransform} /
+   * {@link updateFrame}, or because any of its ancestor frames was updated.
+   * It receives the world transform before and after the change.
+   *
+   * Pass `minTranslation` / `minRotation` thresholds to skip notifications
+   * for small movements; `previous` is then the world transform at the last
+   * notification this listener actually received.
+   *
+   * @returns An unsubscribe function that removes the listener when called.
+   * @throws {Error} if `frameId` is not registered.
+   */
+  onChange(
+    frameId: string,
+    callback: ChangeCallback,
+    options: ChangeSubscriptionOptions = {},
+  ): () => void {
+    frameId = this.resolveAlias(frameId);
+    if (!this.frames.has(frameId)) {
+      throw new FrameNotFoundError(frameId);
+    }
+    if (options.minTranslation !== undefined || options.minRotation !== undefined) {
+      callback = thresholdFilter(callback, this.getWorldTransform(frameId), options);
+    }
+    const listeners = this.changeListeners.get(frameId) ?? new Set<ChangeCallback>();
+    this.changeListeners.set(frameId, listeners);
+    if (!this.notifiedWorldTransforms.has(frameId)) {
+      this.notifiedWorldTransforms.set(frameId, this.getWorldTransform(frameId));
+    }
+    listeners.add(callback);
+    return () => {
+      // The set follows the frame through renames.
+      listeners.delete(callback);
+    };
+  }
+
+  /**
+   * Subscribe to world-transform changes anywhere in the tree.
+   *
+   * Unlike {@link onChange}, the `callback` is invoked once per mutation with
+   * the ids of all affected frames — e.g. once for an
+   * {@link updateTransforms} batch, or once per committed transaction — so a
+   * consumer can dispatch to its own listeners without one call per frame.
+   *
+   * Pass a `pattern` (e.g. `"robot1/**"`) and/or `subtreeRoot` to only hear
+   * about part of the scene; the callback is then skipped entirely for
+   * mutations that affect no matching frame.
+   *
+   * @returns An unsubscribe function that removes the listener when called.
+   * @throws {Error} if `subtreeRoot` is given but not registered.
+   */
+  onAnyChange(callback: BatchChangeCallback, options: BatchSubscriptionOptions = {}): () => void {
+    const { pattern, subtreeRoot } = options;
+    if (pattern !== undefined || subtreeRoot !== undefined) {
+      const re = pattern !== undefined ? compileFramePattern(pattern) : undefined;
+      const rootId = subtreeRoot !== undefined ? this.getFrameNode(subtreeRoot).id : undefined;
+      const inScope = (id: string) =>
+        (re === undefined || re.test(id)) &&
+        (rootId === undefined || this.chainToRoot(id).includes(rootId));
+      const unfiltered = callback;
+      callback = (frameIds, seq) => {
+        const matching = frameIds.filter(inScope);
+        if (matching.length > 0) unfiltered(matching, seq);
+      };
+    }
+    this.batchChangeListeners.add(callback);
+    return () => {
+      this.batchChangeListeners.delete(callback);
+      this.openTransaction?.clearedBatchListeners.delete(callback);
+    };
+  }
+
+  /**
+   * Subscribe to structural changes — frames added, removed, reparented or
+   * renamed, and {@link clear} — so that e.g. a tree-view UI can update
+   * incrementally instead of re-enumerating the tree.  Transform updates are
+   * not reported here; use {@link onChange} or {@link onAnyChange}.
+   *
+   * Inside a transaction, events are delivered on {@link commit} (and
+   * discarded on {@link rollback}).
+   *
+   * @returns An unsubscribe function that removes the listener when called.
+   */
+  onStructureChange(callback: StructureChangeCallback): () => void {
+    this.structureListeners.add(callback);
+    return () => {
+      this.structureListeners.delete(callback);
+    };
+  }
+
+  /**
+   * Watch whether the origin of `frameId` lies inside `region`, a box
+   * expressed in `regionFrame` — e.g. for geofencing.  Containment is
+   * re-tested whenever either frame's world transform changes, and `callback`
+   * fires only when the frame enters or leaves the box (boundary counts as
+   * inside).  While the two frames are not connected no test is made, so the
+   * last known state is kept.
+   *
+   * @returns An unsubscribe function that removes the watch when called.
+   * @throws {Error} if either frame is not registered.
+   */
+  onRegionChange(
+    frameId: string,
+    regionFrame: string,
+    region: AABB,
+    callback: RegionCallback,
+  ): () => void {
+    frameId = this.getFrameNode(frameId).id;
+    regionFrame = this.getFrameNode(regionFrame).id;
+    const isInside = (): boolean | undefined => {
+      if (!this.connected(frameId, regionFrame)) return undefined;
+      const p = this.getWorldTransform(regionFrame)
+        .invert()
+        .transformPoint(this.getWorldTransform(frameId).translation);
+      const { min, max } = region;
+      return (
+        p.x >= min.x &&
+        p.x <= max.x &&
+        p.y >= min.y &&
+        p.y <= max.y &&
+        p.z >= min.z &&
+        p.z <= max.z
+      );
+    };
+    let inside = isInside() === true;
+    return this.onAnyChange((frameIds) => {
+      if (!frameIds.includes(frameId) && !frameIds.includes(regionFrame)) return;
+      const now = isInside();
+      if (now === undefined || now === inside) return;
+      inside = now;
+      callback(frameId, now);
+    });
+  }
+
+  /**
+   * Watch whether frames `a` and `b` are connected (see {@link isConnected}),
+   * e.g. to replace a "waiting for transform" retry loop with an event.
+   * Neither frame needs to be registered yet.  Connectivity is re-checked
+   * after every structural change, and `callback` fires only when it flips.
+   *
+   * @returns An unsubscribe function that removes the watch when called.
+   */
+  onConnectivityChange(a: string, b: string, callback: ConnectivityCallback): () => void {
+    let connected = this.isConnected(a, b);
+    return this.onStructureChange(() => {
+      const now = this.isConnected(a, b);
+      if (now === connected) return;
+      connected = now;
+      callback(now);
+    });
+  }
+
+  /**
+   * Returns the tree's change batches (as delivered to {@link onAnyChange})
+   * as an async iterator, for consumers that prefer streams over callbacks.
+   * Batches queue up until consumed; see {@link ChangeStream} for how a slow
+   * consumer is handled.  Call `return()` (or `break` out of `for await`) to
+   * unsubscribe.
+   *
+   * @throws {Error} if `subtreeRoot` is given but not registered.
+   */
+  changes(options: ChangeStreamOptions = {}): ChangeStream {
+    const { highWaterMark = 16, ...scope } = options;
+    return new ChangeStream((push) => this.onAnyChange(push, scope), highWaterMark);
+  }
+
+  /**
+   * Switch deferred notification mode on or off.  While deferred, change
+   * notifications accumulate (deduplicated) instead of being delivered after
+   * every mutation, until {@link flushNotifications} — typically once per
+   * animation frame.  Turning the mode off flushes any pending changes.
+   */
+  setDeferredNotifications(enabled: boolean): void {
+    if (enabled) {
+      if (this.deferredNotifications === undefined) {
+        this.deferredNotifications = new Set();
+      }
+    } else {
+      this.flushNotifications();
+      this.deferredNotifications = undefined;
+    }
+  }
+
+  /**
+   * Deliver the changes accumulated in deferred mode as one merged change
+   * set: each affected frame's {@link onChange} listeners fire once and each
+   * {@link onAnyChange} listener receives a single batch.
+   *
+   * @returns The ids of the frames whose world transform changed since the
+   *          previous flush (empty when not in deferred mode).
+   */
+  flushNotifications(): string[] {
+    if (this.deferredNotifications === undefined) {
+      return [];
+    }
+    const ids = Array.from(this.deferredNotifications).filter((id) => this.frames.has(id));
+    this.deferredNotifications.clear();
+    if (ids.length > 0) {
+      this.deliverChanges(ids);
+    }
+    return ids;
+  }
+
+  /**
+   * Start retaining the last `capacity` delivered change batches so that a
+   * late subscriber — or a restarted worker — can catch up with
+   * {@link eventsSince} instead of requesting a full snapshot.  Calling this
+   * again changes the capacity and keeps the retained batches that fit.
+   */
+  enableEventLog(capacity = 256): void {
+    if (this.eventLog === undefined) {
+      this.eventLog = [];
+    }
+    this.eventLogCapacity = Math.max(0, capacity);
+    this.eventLog.splice(0, Math.max(0, this.eventLog.length - this.eventLogCapacity));
+  }
+
+  /**
+   * Stop retaining change batches and discard the retained ones.  Sequence
+   * numbers keep increasing.
+   */
+  disableEventLog(): void {
+    this.eventLog = undefined;
+  }
+
+  /**
+   * Sequence number of the most recently delivered change batch (as passed
+   * to {@link onAnyChange} listeners), or `0` if none was delivered yet.
+   */
+  lastEventSeq(): number {
+    return this.eventSeq;
+  }
+
+  /**
+   * Returns the change batches delivered after batch `seq`, oldest first.
+   *
+   * @throws {RangeError} if some of those batches are no longer retained (or
+   *                      the event log is not enabled); the caller then needs
+   *                      a full snapshot instead.
+   */
+  eventsSince(seq: number): ChangeBatch[] {
+    if (seq >= this.eventSeq) {
+      return [];
+    }
+    const log = this.eventLog ?? [];
+    if (log.length === 0 || log[0].seq > seq + 1) {
+      throw new OutOfRangeError(`Change batches after #${seq} are no longer retained.`, {
+        frameIds: [],
+        operation: "eventsSince",
+      });
+    }
+    return log.filter((batch) => batch.seq > seq);
+  }
+
+  /**
+   * Encode the current world transforms of `frameIds` — typically a batch
+   * received by an {@link onAnyChange} listener — into a transferable
+   * `ArrayBuffer`, so that it can be forwarded to workers without
+   * re-encoding.  Decode it with {@link decodeChangePacket}.
+   *
+   * @param frameIds Frames to include.
+   * @param seq      Sequence number to stamp on the packet; defaults to
+   *                 {@link lastEventSeq}.
+   * @throws {Error} if any frame is not registered.
+   */
+  packChanges(frameIds: readonly string[], seq = this.eventSeq): ArrayBuffer {
+    return encodeChangePacket(
+      seq,
+      frameIds.map((id) => {
+        id = this.getFrameNode(id).id;
+        return [id, this.getWorldTransform(id)] as const;
+      }),
+    );
+  }
+
+  // ── watched pairs ──────────────────────────────────────────────────────────
+
+  /**
+   * Register interest in the transform between `from` and `to` (as returned
+   * by {@link getTransform}).  Changed pairs are collected with
+   * {@link flushWatches}, replacing per-frame polling of many
+   * {@link getTransform} calls.
+   *
+   * `"low"` priority pairs (the default) are only recomputed by
+   * {@link flushWatches}.  `"high"` priority pairs — a camera pose, the
+   * selected object — are recomputed as soon as one of their frames changes,
+   * so {@link getWatchedTransform} returns their up-to-date value between
+   * flushes.
+   *
+   * The watch is dropped automatically when either frame is removed.
+   *
+   * @returns A slot index identifying this pair in {@link flushWatches}
+   *          results.  Slots are never reused, so indices stay stable.
+   * @throws {Error} if either frame is not registered.
+   */
+  watchTransform(from: string, to: string, priority: WatchPriority = "low"): number {
+    from = this.getFrameNode(from).id;
+    to = this.getFrameNode(to).id;
+    const watch: WatchedPair = { from, to, priority, last: undefined, current: undefined };
+    this.watches.push(watch);
+    if (priority === "high") {
+      this.refreshWatch(watch);
+    }
+    return this.watches.length - 1;
+  }
+
+  /**
+   * Returns the current value of a `"high"` priority pair, or the value last
+   * reported by {@link flushWatches} for a `"low"` priority one.
+   *
+   * @returns `undefined` for unknown slots, for pairs whose frames are not
+   *          connected, and for `"low"` pairs not flushed yet.
+   */
+  getWatchedTransform(slot: number): Transform | undefined {
+    const watch = this.watches[slot];
+    return watch?.priority === "high" ? watch.current : watch?.last;
+  }
+
+  /** Stop watching the pair in `slot`.  Unknown slots are ignored. */
+  unwatchTransform(slot: number): void {
+    if (this.watches[slot] !== undefined) {
+      this.watches[slot] = undefined;
+    }
+  }
+
+  /**
+   * Returns the watched pairs whose transform changed since the previous
+   * call, packed into a single `Float64Array`.  Every pair is reported on the
+   * first call after it is registered; pairs whose frames are currently not
+   * connected are skipped.
+   *
+   * Only `"low"` priority pairs involving a frame dirtied since the last
+   * flush are recomputed; `"high"` priority pairs are already up to date.
+   */
+  flushWatches(): WatchedPairChanges {
+    const slots: number[] = [];
+    const values: Transform[] = [];
+    this.watches.forEach((watch, slot) => {
+      if (watch === undefined) return;
+      const dirty = this.watchDirty.has(watch.from) || this.watchDirty.has(watch.to);
+      if (watch.last !== undefined && !dirty) return;
+      if (!this.connected(watch.from, watch.to)) return;
+      const value =
+        watch.priority === "high" && watch.current !== undefined
+          ? watch.current
+          : this.relativeTransform(watch.from, watch.to);
+      if (
+        watch.last !== undefined &&
+        sameTransformJSON(transformToJSON(watch.last), transformToJSON(value))
+      ) {
+        return;
+      }
+      watch.last = value;
+      slots.push(slot);
+      values.push(value);
+    });
+    this.watchDirty.clear();
+
+    const data = new Float64Array(values.length * 7);
+    values.forEach(({ translation: t, rotation: r }, i) => {
+      data.set([t.x, t.y, t.z, r.x, r.y, r.z, r.w], i * 7);
+    });
+    return { slots, data };
+  }
+
+  // ── serialization ──────────────────────────────────────────────────────────
+
+  /**
+   * Restrict the frames {@link toJSON} and {@link encodeSyncPacket} include
+   * — e.g. so that a client publishes only the frames it owns instead of
+   * echoing the whole world back to the server — or export every frame
+   * again with `undefined`.  Exported frames keep their parent ids even
+   * when the parent itself is not exported.
+   */
+  setExportFilter(filter: ExportFilter | undefined): void {
+    this.exportFilter = filter === undefined ? undefined : { ...filter };
+  }
+
+  /**
+   * Serialize the tree to a plain JSON-compatible object — every frame, or
+   * those passing the {@link setExportFilter} export filter.
+   *
+   * Frames are emitted in insertion order, except that a frame reparented
+   * under a later-registered frame is moved after its new parent.  Parents
+   * therefore always appear before their children — safe to replay with
+   * sequential {@link addFrame} calls.
+   *
+   * @example
+   * ```ts
+   * const json = tf.toJSON();
+   * const copy = TFTree.fromJSON(json);
+   * ```
+   */
+  toJSON(): TFTreeJSON {
+    const frames = this.frameRecords();
+    if (this.exportFilter === undefined) {
+      return { frames };
+    }
+    return { frames: frames.filter((frame) => this.isExported(frame.id)) };
+  }
+
+  /**
+   * Reconstruct a {@link TFTree} from a plain JSON object produced by
+   * {@link toJSON}.
+   *
+   * Frames must be listed in parent-before-child order (which {@link toJSON}
+   * guarantees automatically).
+   *
+   * @example
+   * ```ts
+   * const tf = TFTree.fromJSON(config);
+   * ```
+   *
+   * @throws {Error} if the data contains an unknown parent reference or a
+   *                 duplicate frame id.
+   */
+  static fromJSON(data: TFTreeJSON): TFTree {
+    const tree = new TFTree();
+    for (const frame of data.frames) {
+      tree.addFrame(frame.id, frame.parentId ?? undefined, transformFromJSON(frame.transform));
+    }
+    return tree;
+  }
+
+  /**
+   * Repair third-party tree data that {@link fromJSON} would reject, instead
+   * of failing the whole load: later duplicates of an id are dropped, frames
+   * whose parent is missing become roots, and every parent cycle is broken
+   * by turning one of its frames into a root.  Frames are also reordered
+   * parent-first.
+   *
+   * @example
+   * ```ts
+   * const { data, repairs } = TFTree.repairJSON(untrusted);
+   * if (repairs.length > 0) console.warn("Repaired tree data", repairs);
+   * const tf = TFTree.fromJSON(data);
+   * ```
+   */
+  static repairJSON(data: TFTreeJSON): RepairedJSON {
+    const repairs: JSONRepair[] = [];
+    const frames = new Map<string, FrameNodeJSON>();
+    for (const frame of data.frames) {
+      if (frames.has(frame.id)) {
+        repairs.push({ type: "duplicate-dropped", id: frame.id, parentId: frame.parentId });
+      } else {
+        frames.set(frame.id, frame);
+      }
+    }
+    const makeRoot = (frame: FrameNodeJSON, type: JSONRepair["type"]) => {
+      repairs.push({ type, id: frame.id, parentId: frame.parentId });
+      frames.set(frame.id, { ...frame, parentId: null });
+    };
+    for (const frame of frames.values()) {
+      if (frame.parentId !== null && !frames.has(frame.parentId)) {
+        makeRoot(frame, "dangling-parent");
+      }
+    }
+
+    // Walk up from every frame; meeting a frame of the current walk again
+    // means a cycle, which is broken at that frame.
+    const state = new Map<string, "visiting" | "done">();
+    for (const id of frames.keys()) {
+      const path: string[] = [];
+      let current: string | null = id;
+      while (current !== null && !state.has(current)) {
+        state.set(current, "visiting");
+        path.push(current);
+        current = frames.get(current)!.parentId;
+      }
+      if (current !== null && state.get(current) === "visiting") {
+        makeRoot(frames.get(current)!, "cycle-broken");
+      }
+      for (const visited of path) state.set(visited, "done");
+    }
+
+    const children = new Map<string | null, FrameNodeJSON[]>();
+    for (const frame of frames.values()) {
+      let siblings = children.get(frame.parentId);
+      if (siblings === undefined) {
+        siblings = [];
+        children.set(frame.parentId, siblings);
+      }
+      siblings.push(frame);
+    }
+    const ordered: FrameNodeJSON[] = [];
+    const stack = [...(children.get(null) ?? [])].reverse();
+    while (stack.length > 0) {
+      const frame = stack.pop()!;
+      ordered.push(frame);
+      stack.push(...[...(children.get(frame.id) ?? [])].reverse());
+    }
+    return { data: { frames: ordered }, repairs };
+  }
+
+  // ── protected helpers (available to subclasses) ───────────────────────────
+
+  /**
+   * Returns the {@link FrameNode} for the given id or alias.
+   * Subclasses may use this to walk the frame hierarchy.
+   *
+   * @throws {Error} if `id` is not registered.
+   */
+  protected getFrameNode(id: string): FrameNode {
+    return this.frameNodeById(this.resolveAlias(id));
+  }
+
+  /**
+   * Returns the {@link FrameNode} registered as `id`, an id already resolved
+   * (e.g. a {@link FrameNode.id}), without normalizing it again.
+   *
+   * @throws {Error} if `id` is not registered.
+   */
+  protected frameNodeById(id: string): FrameNode {
+    const frame = this.frames.get(id);
+    if (frame === undefined) {
+      if (this.loadFrame(id)) return this.frames.get(id)!;
+      throw new FrameNotFoundError(id);
+    }
+    return frame;
+  }
+
+  /**
+   * Returns the current transform of `id` with its rotation replaced by the
+   * quaternion `(qx, qy, qz, qw)`, for rotation-only updates.
+   *
+   * @throws {Error} if `id` is not registered.
+   * @throws {InvalidTransformError} if a component is not finite or the
+   *                                 quaternion has zero norm.
+   */
+  protected withRotation(id: string, qx: number, qy: number, qz: number, qw: number): Transform {
+    const { translation } = this.getFrameNode(id).transform;
+    const components: Array<[string, number]> = [
+      ["rotation.x", qx],
+      ["rotation.y", qy],
+      ["rotation.z", qz],
+      ["rotation.w", qw],
+    ];
+    const component = components.find(([, value]) => !Number.isFinite(value))?.[0];
+    if (component !== undefined) {
+      throw new InvalidTransformError(
+        id,
+        component,
+        `Rotation for frame "${id}" has a non-finite ${component}.`,
+      );
+    }
+    if (!(Math.hypot(qx, qy, qz, qw) > 0)) {
+      throw new InvalidTransformError(id, "rotation", `Rotation for frame "${id}" has zero norm.`);
+    }
+    return new Transform(translation, new Quaternion(qx, qy, qz, qw));
+  }
+
+  /** Returns the current time of the tree's clock (see {@link setClock}). */
+  protected now(): number {
+    return this.clock();
+  }
+
+  // ── private helpers ────────────────────────────────────────────────────────
+
+  /**
+   * Mark a frame and all of its descendants as dirty, invalidating their
+   * cached world transforms so they are recomputed on next access, and
+   * notify subscribers.
+   */
+  private markSubtreeDirty(id: string): void {
+    this.dispatchChanges(this.invalidateSubtree(id, []));
+  }
+
+  /**
+   * Invalidate the cached world transforms of `id` and all of its
+   * descendants, appending their ids (in pre-order) to `changed`.
+   */
+  private invalidateSubtree(id: string, changed: string[]): string[] {
+    this.dirtySet.add(id);
+    this.worldTransformCache.delete(id);
+    this.frameVersions.set(id, ++this.versionClock);
+    if (this.watches.length > 0) {
+      this.watchDirty.add(id);
+    }
+    changed.push(id);
+    for (const childId of this.childrenMap.get(id) ?? []) {
+      this.invalidateSubtree(childId, changed);
+    }
+    return changed;
+  }
+
+  /**
+   * Validate and apply `updates` as one atomic batch (see
+   * {@link updateTransforms}), without notifying subscribers.
+   *
+   * @returns The frames whose world transforms changed.
+   */
+  private applyTransformBatch(
+    updates: Record<string, Transform>,
+    options: UpdateOptions,
+  ): string[] {
+    // First pass: validate every id and transform before touching the tree.
+    const entries: Array<[FrameNode, Transform]> = [];
+    const ids = new Set<string>();
+    for (const [key, input] of Object.entries(updates)) {
+      const id = this.resolveAlias(key);
+      const frame = this.frames.get(id);
+      if (frame === undefined) {
+        throw new FrameNotFoundError(id);
+      }
+      this.assertUnlocked(id);
+      this.assertAuthorized(id, options.source);
+      entries.push([frame, this.checkTransform(id, input)]);
+      ids.add(id);
+    }
+
+    const journalLength = this.journal?.length ?? 0;
+    const journalSeq = this.journalSeq;
+    const forkEditCount = this.forkEdits.length;
+    const applied: FrameNode[] = [];
+    const changed: string[] = [];
+    try {
+      // Second pass: apply all transform changes.
+      for (const [frame, transform] of entries) {
+        applied.push(frame);
+        this.frames.set(frame.id, { ...frame, transform });
+        this.record({ op: "update", id: frame.id, transform: transformToJSON(transform) });
+      }
+
+      // Third pass: mark subtrees dirty, but skip frames whose ancestor is
+      // also being updated in this batch – the ancestor's invalidateSubtree
+      // call will already cover those descendants.
+      for (const id of ids) {
+        let parentId = this.frames.get(id)?.parentId;
+        let ancestorUpdated = false;
+        while (parentId !== undefined) {
+          if (ids.has(parentId)) {
+            ancestorUpdated = true;
+            break;
+          }
+          parentId = this.frames.get(parentId)?.parentId;
+        }
+        if (!ancestorUpdated) {
+          this.invalidateSubtree(id, changed);
+        }
+      }
+    } catch (err) {
+      // Restore the prior transforms (last-applied first, so the original
+      // wins when aliases name a frame twice).  Frames already invalidated
+      // simply recompute their unchanged world transforms.
+      for (const frame of applied.reverse()) {
+        this.frames.set(frame.id, frame);
+      }
+      this.journal?.splice(journalLength);
+      this.journalSeq = journalSeq;
+      this.forkEdits.splice(forkEditCount);
+      throw err;
+    }
+    for (const id of ids) {
+      this.updatedFrames.add(id);
+      this.recordSource(id, options.source);
+    }
+    return changed;
+  }
+
+  /**
+   * Notify per-frame and tree-wide subscribers that the world transforms of
+   * `ids` changed.  Inside a transaction the notifications are deferred
+   * until {@link commit}; in deferred mode, until {@link flushNotifications}.
+   */
+  private dispatchChanges(ids: string[]): void {
+    if (ids.length === 0) {
+      return;
+    }
+    if (this.debugChecks) {
+      this.runDebugChecks(ids);
+    }
+    this.refreshWatches(ids);
+    const pending = this.openTransaction?.pendingNotifications ?? this.deferredNotifications;
+    if (pending !== undefined) {
+      for (const id of ids) {
+        pending.add(id);
+      }
+      return;
+    }
+    this.deliverChanges(ids);
+  }
+
+  /** Recompute the `"high"` priority watched pairs involving any of `ids`. */
+  private refreshWatches(ids: readonly string[]): void {
+    const changed = new Set(ids);
+    for (const watch of this.watches) {
+      if (watch?.priority === "high" && (changed.has(watch.from) || changed.has(watch.to))) {
+        this.refreshWatch(watch);
+      }
+    }
+  }
+
+  /** Recompute the current value of a `"high"` priority watched pair. */
+  private refreshWatch(watch: WatchedPair): void {
+    watch.current = this.connected(watch.from, watch.to)
+      ? this.relativeTransform(watch.from, watch.to)
+      : undefined;
+  }
+
+  /** Invoke the per-frame and tree-wide change listeners for `ids` right away. */
+  private deliverChanges(ids: string[]): void {
+    for (const id of ids) {
+      this.notifyChange(id);
+    }
+    const seq = ++this.eventSeq;
+    if (this.eventLog !== undefined) {
+      this.eventLog.push({ seq, frameIds: [...ids] });
+      if (this.eventLog.length > this.eventLogCapacity) {
+        this.eventLog.shift();
+      }
+    }
+    for (const cb of this.batchChangeListeners) {
+      cb(ids, seq);
+    }
+  }
+
+  /**
+   * Deliver `event` to the structure listeners, or queue it until
+   * {@link commit} inside a transaction.
+   */
+  private emitStructureEvent(event: StructureEvent): void {
+    if (this.openTransaction !== undefined) {
+      this.openTransaction.pendingStructureEvents.push(event);
+      return;
+    }
+    for (const cb of this.structureListeners) {
+      cb(event);
+    }
+  }
+
+  /**
+   * Register a primitive mutation: bumps the {@link revision}, stamps the
+   * frame's modification time, appends `op` to the journal when journaling
+   * is enabled and to the pending edits of a fork.
+   */
+  private record(op: JournalOperation): void {
+    this.revisionCounter++;
+    if (op.op === "clear") {
+      this.modifiedAt.clear();
+    } else if (op.op === "remove") {
+      this.modifiedAt.delete(op.id);
+    } else if (op.op === "rename") {
+      this.modifiedAt.delete(op.id);
+      this.modifiedAt.set(op.newId, this.clock());
+    } else {
+      this.modifiedAt.set(op.id, this.clock());
+    }
+    if (this.journal !== undefined) {
+      this.journal.push({ ...op, seq: ++this.journalSeq });
+    }
+    if (this.forkBase !== undefined) {
+      this.forkEdits.push(op);
+    }
+    if (op.op !== "clear" && this.namespaces.size > 0) {
+      const prefix = this.namespaceOf(op.id);
+      if (prefix !== undefined) this.namespaces.get(prefix)!.mutations++;
+    }
+  }
+
+  /**
+   * Perform journaled mutations in order, atomically: inside the open
+   * transaction if there is one, otherwise in a transaction of their own.
+   */
+  private applyOperations(operations: readonly JournalOperation[]): void {
+    const apply = () => {
+      for (const op of operations) {
+        this.applyJournalOperation(op);
+      }
+    };
+    if (this.inTransaction()) {
+      apply();
+    } else {
+      this.transaction(apply);
+    }
+  }
+
+  /**
+   * Perform a single journaled mutation.  Its ids are registered ids, as
+   * recorded by {@link record}, so they are not normalized again.
+   */
+  private applyJournalOperation(op: JournalOperation): void {
+    switch (op.op) {
+      case "add":
+        this.insertFrame(op.id, op.parentId ?? undefined, transformFromJSON(op.transform));
+        break;
+      case "update":
+        this.writeTransform(op.id, transformFromJSON(op.transform), {});
+        break;
+      case "relink": {
+        const parentId = op.parentId ?? undefined;
+        this.assertUnlocked(this.frameNodeById(op.id).id);
+        this.assertValidParent(op.id, parentId);
+        this.relinkFrame(op.id, parentId, transformFromJSON(op.transform));
+        break;
+      }
+      case "remove":
+        this.deleteFrame(op.id, {});
+        break;
+      case "rename":
+        this.changeFrameId(op.id, op.newId);
+        break;
+      case "clear":
+        this.clear();
+        break;
+    }
+  }
+
+  /**
+   * Invoke every change listener registered for `id` with its previously
+   * reported and current world transform.
+   */
+  private notifyChange(id: string): void {
+    const listeners = this.changeListeners.get(id);
+    if (listeners === undefined || listeners.size === 0) {
+      return;
+    }
+    const current = this.getWorldTransform(id);
+    const previous = this.notifiedWorldTransforms.get(id) ?? current;
+    this.notifiedWorldTransforms.set(id, current);
+    for (const cb of listeners) {
+      cb(id, { previous, current });
+    }
+  }
+
+  /** Returns the base of this fork, checking that it can be promoted or discarded. */
+  private assertIdleFork(operation: string): TFTree {
+    if (this.forkBase === undefined) {
+      throw new TFError("INVALID_STATE", "This tree is not a fork.", { frameIds: [], operation });
+    }
+    if (this.openTransaction !== undefined) {
+      throw new TFError("INVALID_STATE", "A transaction is in progress on the fork.", {
+        frameIds: [],
+        operation,
+      });
+    }
+    return this.forkBase;
+  }
+
+  /**
+   * Replace the frames and per-frame state of this tree with those of
+   * `source`, sharing its immutable frame records.  Frames get fresh
+   * versions.
+   */
+  private copyState(source: TFTree): void {
+    assignMap(this.frames, source.frames);
+    this.childrenMap.clear();
+    for (const [id, children] of source.childrenMap) {
+      this.childrenMap.set(id, new Set(children));
+    }
+    assignMap(this.subtreeSizes, source.subtreeSizes);
+    assignMap(this.worldTransformCache, source.worldTransformCache);
+    this.dirtySet.clear();
+    source.dirtySet.forEach((id) => this.dirtySet.add(id));
+    assignMap(this.aliases, source.aliases);
+    this.lockedFrames.clear();
+    source.lockedFrames.forEach((id) => this.lockedFrames.add(id));
+    this.updatedFrames.clear();
+    source.updatedFrames.forEach((id) => this.updatedFrames.add(id));
+    assignMap(this.detachedFrames, source.detachedFrames);
+    assignMap(this.cameraIntrinsics, source.cameraIntrinsics);
+    assignMap(this.calibrations, source.calibrations);
+    assignMap(this.geoAnchors, source.geoAnchors);
+    assignMap(this.joints, source.joints);
+    assignMap(this.frameOwners, source.frameOwners);
+    assignMap(this.lastSources, source.lastSources);
+    assignMap(this.modifiedAt, source.modifiedAt);
+    this.frameVersions.clear();
+    for (const id of this.frames.keys()) {
+      this.frameVersions.set(id, ++this.versionClock);
+    }
+    // Watched pairs are not copied, but this tree's own must not outlive
+    // their frames in the copied state.
+    this.watches.forEach((watch, slot) => {
+      if (watch !== undefined && !(this.frames.has(watch.from) && this.frames.has(watch.to))) {
+        this.watches[slot] = undefined;
+      }
+    });
+  }
+
+  /**
+   * Reset this fork to the current state of `base` and drop its pending
+   * edits, notifying the frames whose world transform changed.
+   */
+  private resetToBase(base: TFTree): void {
+    const previous = new Map<string, Transform>();
+    for (const id of this.frames.keys()) {
+      previous.set(id, this.getWorldTransform(id));
+    }
+    this.copyState(base);
+    this.forkEdits.length = 0;
+    // Revisions only ever increase, so the reset state gets a fresh one.
+    this.revisionCounter = Math.max(this.revisionCounter, base.revisionCounter) + 1;
+
+    const changed: string[] = [];
+    for (const id of this.frames.keys()) {
+      const before = previous.get(id);
+      const after = this.getWorldTransform(id);
+      if (
+        before === undefined ||
+        !sameTransformJSON(transformToJSON(before), transformToJSON(after))
+      ) {
+        changed.push(id);
+      }
+    }
+    for (const id of previous.keys()) {
+      if (!this.frames.has(id)) {
+        this.changeListeners.delete(id);
+        this.notifiedWorldTransforms.delete(id);
+      }
+    }
+    for (const watch of this.watches) {
+      if (watch !== undefined) {
+        this.watchDirty.add(watch.from);
+        this.watchDirty.add(watch.to);
+      }
+    }
+    this.dispatchChanges(changed);
+  }
+
+  /**
+   * Register the missing frame `id` from the frame resolver, if there is
+   * one and it knows the frame.  Returns `true` if the frame was added.
+   */
+  private loadFrame(id: string): boolean {
+    if (this.frameResolver === undefined || this.resolving.has(id)) {
+      return false;
+    }
+    this.resolving.add(id);
+    try {
+      const frame = this.frameResolver(id);
+      if (frame === undefined) {
+        return false;
+      }
+      const { parentId } = frame;
+      this.insertFrame(
+        id,
+        parentId === undefined ? undefined : this.resolveAlias(parentId),
+        frame.transform,
+      );
+      return true;
+    } finally {
+      this.resolving.delete(id);
+    }
+  }
+
+  /** Returns joint `name`, throwing if it is not registered. */
+  private getJointState(name: string, operation: string): Joint {
+    const joint = this.joints.get(name);
+    if (joint === undefined) {
+      throw new TFError("INVALID_ARGUMENT", `Joint "${name}" is not registered.`, {
+        frameIds: [],
+        operation,
+      });
+    }
+    return joint;
+  }
+
+  /** Validate `limits` of joint `name` and fill in their defaults. */
+  private jointLimits(
+    name: string,
+    frameId: string,
+    limits: JointLimits,
+    operation: string,
+  ): Required<JointLimits> {
+    const { lower = -Infinity, upper = Infinity, policy = "clamp" } = limits;
+    if (!(lower <= upper)) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Invalid limits [${lower}, ${upper}] of joint "${name}".`,
+        { frameIds: [frameId], operation },
+      );
+    }
+    return { lower, upper, policy };
+  }
+
+  /**
+   * Set the frame of `joint` to the joint's transform at `position`, after
+   * applying the joint's limits, and move the joints mimicking it along.
+   */
+  private driveJoint(joint: Joint, position: number | readonly number[], operation: string): void {
+    const positions = typeof position === "number" ? [position] : position;
+    this.dispatchChanges(this.moveJoints([[joint, positions]], operation));
+  }
+
+  /**
+   * Move each joint of `moves` to its requested position, and the joints
+   * mimicking them along, updating their frames as one atomic batch.
+   * Subscribers are not notified.
+   *
+   * @returns The frames whose world transforms changed.
+   */
+  private moveJoints(
+    moves: ReadonlyArray<[Joint, readonly number[]]>,
+    operation: string,
+  ): string[] {
+    const targets: Array<[Joint, number[], readonly number[]]> = [];
+    const visit = (joint: Joint, requested: readonly number[]) => {
+      const limited = this.limitJointPosition(joint, requested, operation);
+      targets.push([joint, limited, requested]);
+      for (const follower of this.joints.values()) {
+        if (follower.mimic?.joint === joint.name) {
+          const { multiplier, offset } = follower.mimic;
+          visit(follower, [multiplier * limited[0] + offset]);
+        }
+      }
+    };
+    for (const [joint, positions] of moves) {
+      visit(joint, positions);
+    }
+    const updates: Record<string, Transform> = {};
+    for (const [joint, limited] of targets) {
+      // New or reconfigured joints always set their frame.
+      const unchanged = limited.every((v, k) => v === joint.positions[k]);
+      if (unchanged && this.joints.get(joint.name) === joint) continue;
+      updates[joint.frameId] = joint.origin.compose(jointMotion(joint, limited));
+    }
+    const changed = this.applyTransformBatch(updates, {});
+    for (const [joint, limited, requested] of targets) {
+      this.storeJointPosition(joint, limited, requested);
+    }
+    return changed;
+  }
+
+  /** Delete joint `name`, making the joints mimicking it independent. */
+  private deleteJoint(name: string): void {
+    this.joints.delete(name);
+    for (const joint of this.joints.values()) {
+      const { mimic, ...independent } = joint;
+      if (mimic?.joint === name) this.joints.set(joint.name, independent);
+    }
+  }
+
+  /**
+   * Returns the coordinates `positions` within the limits of `joint`,
+   * clamped or rejected according to their policy.
+   */
+  private limitJointPosition(
+    joint: Joint,
+    positions: readonly number[],
+    operation: string,
+  ): number[] {
+    const [position] = positions;
+    const dof = JOINT_DOF[joint.type];
+    if (
+      positions.length !== dof ||
+      !positions.every(Number.isFinite) ||
+      (joint.type === "floating" && positions.slice(3).every((v) => v === 0))
+    ) {
+      throw new TFError(
+        "INVALID_ARGUMENT",
+        `Invalid position ${dof === 1 ? position : `[${positions.join(", ")}]`} of joint ` +
+          `"${joint.name}", which takes ${dof} coordinate(s).`,
+        { frameIds: [joint.frameId], operation },
+      );
+    }
+    if (dof !== 1) {
+      return positions.slice();
+    }
+    const { lower, upper, policy } = joint.limits;
+    const limited = Math.min(Math.max(position, lower), upper);
+    if (limited !== position && policy === "throw") {
+      throw new OutOfRangeError(
+        `Position ${position} of joint "${joint.name}" is outside [${lower}, ${upper}].`,
+        { frameIds: [joint.frameId], operation },
+      );
+    }
+    return [limited];
+  }
+
+  /**
+   * Record that `joint` moved to `positions`, notifying the
+   * {@link onJointLimit} listeners if `requested` was clamped under the
+   * `"warn"` policy.
+   */
+  private storeJointPosition(
+    joint: Joint,
+    positions: number[],
+    requested: readonly number[],
+  ): void {
+    const driven: Joint = { ...joint, position: positions[0], positions };
+    this.joints.set(joint.name, driven);
+    if (positions[0] !== requested[0] && joint.limits.policy === "warn") {
+      this.emitJointLimitEvent(driven, requested[0]);
+    }
+  }
+
+  /**
+   * Deliver a clamped joint position to the {@link onJointLimit} listeners,
+   * or queue it until {@link commit} inside a transaction.
+   */
+  private emitJointLimitEvent(joint: Joint, requested: number): void {
+    if (this.openTransaction !== undefined) {
+      this.openTransaction.pendingJointLimitEvents.push([joint, requested]);
+      return;
+    }
+    for (const cb of this.jointLimitListeners) {
+      cb(joint, requested);
+    }
+  }
+
+  /** Close the open transaction and return its captured state. */
+  private endTransaction(): TransactionState {
+    const transaction = this.openTransaction;
+    if (transaction === undefined) {
+      throw new TFError("INVALID_STATE", "No transaction in progress.");
+    }
+    this.openTransaction = undefined;
+    return transaction;
+  }
+
+  /**
+   * Add `delta` to the stored subtree size of `id` and every one of its
+   * ancestors.  A no-op when `id` is `undefined` (i.e. above a root frame).
+   */
+  private adjustSubtreeSizes(id: string | undefined, delta: number): void {
+    let current = id;
+    while (current !== undefined) {
+      this.subtreeSizes.set(current, this.subtreeSizes.get(current)! + delta);
+      current = this.frames.get(current)?.parentId;
+    }
+  }
+
+  /**
+   * Returns the cached world transform for `id`, recomputing and caching it
    * if the frame is dirty.  The world transform is the accumulated transform
    * from the subtree root down to this frame.
    */
@@ -371,6 +5422,390 @@ export class TFTree implements ITransformTree {
     return worldTransform;
   }
 
+  /**
+   * Recompute the world transform of `id` from the local transforms along
+   * its chain, ignoring the cache.  Composes in the same order as
+   * {@link getWorldTransform}, so a correct cache entry matches exactly.
+   */
+  private freshWorldTransform(id: string): Transform {
+    const [rootId, ...chain] = this.chainToRoot(id).reverse();
+    return chain.reduce(
+      (world, chainId) => world.compose(this.frames.get(chainId)!.transform),
+      this.frames.get(rootId)!.transform,
+    );
+  }
+
+  /** The checks enabled by {@link setDebugChecks}, run after `ids` changed. */
+  private runDebugChecks(ids: readonly string[]): void {
+    const epsilon = this.debugCheckEpsilon;
+    const fail = (id: string, message: string) => {
+      throw new TFError("INVALID_STATE", message, { frameIds: [id], operation: "debugChecks" });
+    };
+    for (const id of ids) {
+      const transform = this.frames.get(id)?.transform;
+      if (transform?.compose(transform.invert()).equals(Transform.identity(), epsilon) === false) {
+        fail(id, `Transform of frame "${id}" composed with its inverse is not identity.`);
+      }
+    }
+    const [stale] = this.verifyCache(epsilon);
+    if (stale !== undefined) {
+      fail(stale.frameId, `Cached world transform of frame "${stale.frameId}" is stale.`);
+    }
+  }
+
+  /**
+   * Returns the origin of every frame in the tree containing `referenceFrame`,
+   * expressed in `referenceFrame`, in depth-first pre-order from the root.
+   */
+  private frameOriginsIn(referenceFrame: string): Array<[string, Vec3]> {
+    referenceFrame = this.getFrameNode(referenceFrame).id;
+    const referenceInv = this.getWorldTransform(referenceFrame).invert();
+    return this.subtreeIds(this.rootOf(referenceFrame)).map((id) => [
+      id,
+      referenceInv.transformPoint(this.getWorldTransform(id).translation),
+    ]);
+  }
+
+  /**
+   * Pairs every frame in the tree containing `referenceFrame` with the
+   * distance from its origin to `point` (expressed in `referenceFrame`),
+   * sorted nearest first.  The sort is stable, so ties keep traversal order.
+   */
+  private sortedByDistance(referenceFrame: string, point: Vec3): FrameDistance[] {
+    return this.frameOriginsIn(referenceFrame)
+      .map(([id, origin]) => ({ id, distance: origin.subtract(point).length() }))
+      .sort((a, b) => a.distance - b.distance);
+  }
+
+  /**
+   * Returns the lowest common ancestor of `a` and `b` (which may be `a` or `b`
+   * itself), or `undefined` if they live in different trees.
+   */
+  private lowestCommonAncestor(a: string, b: string): string | undefined {
+    const bChain = new Set(this.chainToRoot(b));
+    return this.chainToRoot(a).find((id) => bChain.has(id));
+  }
+
+  /**
+   * Apply the ROS naming rules of {@link setRosNaming}, if enabled, then the
+   * longest matching remap of {@link setRemap} to `id`.
+   */
+  private normalizeId(id: string): string {
+    return normalizeFrameId(id, this.rosNaming, this.remaps);
+  }
+
+  /** Throw an {@link InvalidFrameIdError} if `id` violates the frame id policy. */
+  private assertValidId(id: string): void {
+    const policy = this.frameIdPolicy;
+    if (policy === undefined) {
+      return;
+    }
+    if (id === "" && policy.nonEmpty !== false) {
+      throw new InvalidFrameIdError(id, "ids must not be empty");
+    }
+    if (policy.maxLength !== undefined && id.length > policy.maxLength) {
+      throw new InvalidFrameIdError(id, `ids are limited to ${policy.maxLength} characters`);
+    }
+    if (policy.pattern !== undefined && !policy.pattern.test(id)) {
+      throw new InvalidFrameIdError(id, `ids must match ${policy.pattern}`);
+    }
+    const reserved = policy.reservedPrefixes?.find((prefix) => id.startsWith(prefix));
+    if (reserved !== undefined) {
+      throw new InvalidFrameIdError(id, `the prefix "${reserved}" is reserved`);
+    }
+  }
+
+  /**
+   * Apply input validation and the rotation norm policy to a transform about
+   * to be stored for `id`, returning the transform to store.
+   *
+   * @throws {InvalidTransformError} if validation is enabled and `transform`
+   *                                 has a `NaN` or infinite component, or if
+   *                                 the rotation violates the norm policy.
+   */
+  private checkTransform(id: string, transform: Transform): Transform {
+    if (this.validateInputs) {
+      const component = nonFiniteComponent(transform);
+      if (component !== undefined) {
+        throw new InvalidTransformError(
+          id,
+          component,
+          `Transform for frame "${id}" has a non-finite ${component}.`,
+        );
+      }
+    }
+    if (this.rotationNormMode === "off") {
+      return transform;
+    }
+    const { rotation } = transform;
+    const norm = Math.hypot(rotation.x, rotation.y, rotation.z, rotation.w);
+    if (this.rotationNormMode === "strict" && Math.abs(norm - 1) > this.rotationNormEpsilon) {
+      throw new InvalidTransformError(
+        id,
+        "rotation",
+        `Rotation for frame "${id}" has norm ${norm}; expected 1 ± ${this.rotationNormEpsilon}.`,
+      );
+    }
+    if (this.rotationNormMode === "normalize" && norm !== 1) {
+      if (!(norm > 0)) {
+        throw new InvalidTransformError(
+          id,
+          "rotation",
+          `Rotation for frame "${id}" has zero norm and cannot be normalized.`,
+        );
+      }
+      return new Transform(transform.translation, rotation.normalize());
+    }
+    return transform;
+  }
+
+  /**
+   * Throw a {@link FrameLockedError} if `id` is locked, or a
+   * {@link ReadOnlyNamespaceError} if it is in a read-only namespace.
+   */
+  private assertUnlocked(id: string): void {
+    if (this.lockedFrames.has(id)) {
+      throw new FrameLockedError(id);
+    }
+    this.assertWritable(id);
+  }
+
+  /**
+   * Throw a {@link ReadOnlyNamespaceError} if `id` is in a read-only
+   * namespace, for mutations that do not replace its transform.
+   */
+  private assertWritable(id: string): void {
+    const prefix = this.namespaceOf(id);
+    if (prefix !== undefined) {
+      const namespace = this.namespaces.get(prefix)!;
+      if (namespace.policy.readOnly) {
+        namespace.rejected++;
+        throw new ReadOnlyNamespaceError(id, prefix);
+      }
+    }
+  }
+
+  /** Returns the prefix of the namespace `id` belongs to, if any. */
+  private namespaceOf(id: string): string | undefined {
+    let match: string | undefined;
+    for (const prefix of this.namespaces.keys()) {
+      if (id.startsWith(prefix) && prefix.length > (match?.length ?? -1)) {
+        match = prefix;
+      }
+    }
+    return match;
+  }
+
+  /**
+   * Throw if the namespace of `id` does not accept one more frame: a
+   * {@link ReadOnlyNamespaceError} if it is read-only, a
+   * {@link QuotaExceededError} if it is full.
+   */
+  private assertNamespaceAccepts(id: string): void {
+    const prefix = this.namespaceOf(id);
+    if (prefix === undefined) {
+      return;
+    }
+    const namespace = this.namespaces.get(prefix)!;
+    const { readOnly, maxFrames } = namespace.policy;
+    if (readOnly) {
+      namespace.rejected++;
+      throw new ReadOnlyNamespaceError(id, prefix);
+    }
+    if (maxFrames !== undefined) {
+      let count = 1;
+      for (const other of this.frames.keys()) {
+        if (this.namespaceOf(other) === prefix) count++;
+      }
+      if (count > maxFrames) {
+        namespace.rejected++;
+        throw new QuotaExceededError("maxFrames", maxFrames, [id], prefix);
+      }
+    }
+  }
+
+  /** Enforce the `"owner-only"` policy of {@link setAuthorityPolicy}. */
+  private assertAuthorized(id: string, source: string | undefined): void {
+    if (this.authorityPolicy !== "owner-only" || source === undefined) {
+      return;
+    }
+    const owner = this.frameOwners.get(id);
+    if (owner !== undefined && owner !== source) {
+      throw new UnauthorizedSourceError(id, source, owner);
+    }
+  }
+
+  /** Record `source` as the latest updater of `id`, claiming it if unowned. */
+  private recordSource(id: string, source: string | undefined): void {
+    if (source === undefined) {
+      this.lastSources.delete(id);
+      return;
+    }
+    this.lastSources.set(id, source);
+    if (this.authorityPolicy === "owner-only" && !this.frameOwners.has(id)) {
+      this.frameOwners.set(id, source);
+    }
+  }
+
+  /**
+   * Verify that `newParentId` (if given) is registered and is neither `id`
+   * nor one of its descendants.
+   */
+  private assertValidParent(id: string, newParentId: string | undefined): void {
+    if (newParentId === undefined) {
+      return;
+    }
+    if (!this.frames.has(newParentId)) {
+      throw new FrameNotFoundError(newParentId, `Parent frame "${newParentId}" not found.`);
+    }
+    if (this.chainToRoot(newParentId).includes(id)) {
+      throw new CycleDetectedError(id);
+    }
+    if (this.frames.get(id)?.parentId !== newParentId) {
+      this.assertQuota("maxChildren", this.childrenMap.get(newParentId)!.size + 1, [newParentId]);
+    }
+    if (this.quotas.maxDepth !== undefined) {
+      // Depth of the deepest frame of the moved subtree once moved.
+      const base = this.chainToRoot(id).length;
+      const height = this.subtreeIds(id).reduce(
+        (max, d) => Math.max(max, this.chainToRoot(d).length - base),
+        0,
+      );
+      this.assertQuota("maxDepth", this.chainToRoot(newParentId).length + height, [id]);
+    }
+  }
+
+  /**
+   * Run `fn` and undo everything it did, rethrowing any error — the basis of
+   * the `validateOnly` batch option.  Listeners are not notified and frame
+   * versions are left untouched; the revision advances as for
+   * {@link rollback}, since revisions must never repeat.
+   *
+   * @throws {TFError} (`INVALID_STATE`) if a transaction is already in
+   *                   progress: validation does not nest.
+   */
+  private dryRun(operation: string, fn: () => void): void {
+    if (this.openTransaction !== undefined) {
+      throw new TFError("INVALID_STATE", "validateOnly is not available inside a transaction.", {
+        frameIds: [],
+        operation,
+      });
+    }
+    const versions = new Map(this.frameVersions);
+    this.begin();
+    try {
+      fn();
+    } finally {
+      this.rollback();
+      this.frameVersions.clear();
+      versions.forEach((version, id) => this.frameVersions.set(id, version));
+    }
+  }
+
+  /** Throw a {@link QuotaExceededError} if `value` exceeds the `quota` limit. */
+  private assertQuota(quota: keyof TreeQuotas, value: number, frameIds: string[]): void {
+    const limit = this.quotas[quota];
+    if (limit !== undefined && value > limit) {
+      throw new QuotaExceededError(quota, limit, frameIds);
+    }
+  }
+
+  /**
+   * Re-link an already validated frame under `newParentId` with the given
+   * local transform, keeping the children map and subtree sizes in sync and
+   * dirtying the moved subtree.
+   */
+  private relinkFrame(id: string, newParentId: string | undefined, transform: Transform): void {
+    const { parentId } = this.frames.get(id)!;
+    const size = this.subtreeSizes.get(id)!;
+    if (parentId !== undefined) {
+      this.childrenMap.get(parentId)!.delete(id);
+      this.adjustSubtreeSizes(parentId, -size);
+    }
+    this.frames.set(
+      id,
+      newParentId !== undefined ? { id, parentId: newParentId, transform } : { id, transform },
+    );
+    if (newParentId !== undefined) {
+      this.childrenMap.get(newParentId)!.add(id);
+      this.adjustSubtreeSizes(newParentId, size);
+    }
+    this.record({
+      op: "relink",
+      id,
+      parentId: newParentId ?? null,
+      transform: transformToJSON(transform),
+    });
+    if (parentId !== newParentId) {
+      this.emitStructureEvent({
+        type: "reparented",
+        id,
+        oldParentId: parentId ?? null,
+        parentId: newParentId ?? null,
+      });
+    }
+    this.markSubtreeDirty(id);
+  }
+
+  /** Returns every frame as a JSON record, parents first (see {@link toJSON}). */
+  private frameRecords(): FrameNodeJSON[] {
+    return this.parentFirstOrder().map((id) => {
+      const frame = this.frames.get(id)!;
+      return {
+        id: frame.id,
+        parentId: frame.parentId ?? null,
+        transform: transformToJSON(frame.transform),
+      };
+    });
+  }
+
+  /** Returns `true` if `id` passes the {@link setExportFilter} export filter. */
+  private isExported(id: string): boolean {
+    const filter = this.exportFilter;
+    if (filter === undefined) {
+      return true;
+    }
+    if (filter.staticOnly && this.updatedFrames.has(id)) {
+      return false;
+    }
+    if (filter.prefixes === undefined && filter.ids === undefined) {
+      return true;
+    }
+    return (
+      (filter.prefixes?.some((prefix) => id.startsWith(prefix)) ?? false) ||
+      (filter.ids?.includes(id) ?? false)
+    );
+  }
+
+  /**
+   * Returns every frame id ordered so that parents precede their children,
+   * otherwise preserving registration order.
+   */
+  private parentFirstOrder(): string[] {
+    const ordered: string[] = [];
+    const emitted = new Set<string>();
+    for (const id of this.frames.keys()) {
+      // Emit any not-yet-emitted ancestors (top-down) before the frame itself.
+      const pending: string[] = [];
+      let current: string | undefined = id;
+      while (current !== undefined && !emitted.has(current)) {
+        pending.push(current);
+        current = this.frames.get(current)!.parentId;
+      }
+      for (let i = pending.length - 1; i >= 0; i--) {
+        emitted.add(pending[i]);
+        ordered.push(pending[i]);
+      }
+    }
+    return ordered;
+  }
+
+  /** Returns the id of the root frame of the tree containing `id`. */
+  private rootOf(id: string): string {
+    const chain = this.chainToRoot(id);
+    return chain[chain.length - 1];
+  }
+
   /**
    * Returns the ordered list of frame ids from `id` up to (and including)
    * the root frame, i.e. `[id, parent, grandparent, …, root]`.
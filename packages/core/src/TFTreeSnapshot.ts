@@ -0,0 +1,153 @@
+import { Transform } from "./math/Transform.js";
+import { FrameNotFoundError } from "./FrameNotFoundError.js";
+import { DisconnectedFramesError } from "./DisconnectedFramesError.js";
+import type { FrameNode } from "./types.js";
+
+/**
+ * A read-only view of a {@link TFTree} frozen at one revision, created with
+ * {@link TFTree.snapshot}.  Queries keep answering from that state however
+ * the live tree is mutated afterwards, so a render pass can read a
+ * consistent set of poses while producers keep updating the tree.
+ *
+ * Snapshots share the (immutable) frame records and transforms with the
+ * tree, so taking one costs a copy of the frame index, not of the
+ * transforms.  World transforms are computed lazily and cached per snapshot.
+ *
+ * @example
+ * ```ts
+ * const view = tf.snapshot();
+ * for (const mesh of meshes) {
+ *   mesh.pose = view.getTransform("world", mesh.frameId);
+ * }
+ * ```
+ */
+export class TFTreeSnapshot {
+  /** The {@link TFTree.revision} the snapshot was taken at. */
+  readonly revision: number;
+  private readonly frames: ReadonlyMap<string, FrameNode>;
+  private readonly aliases: ReadonlyMap<string, string>;
+  private readonly normalizeId: (id: string) => string;
+  private readonly worldTransforms = new Map<string, Transform>();
+
+  /**
+   * @param revision Revision of the tree at the time of the snapshot.
+   * @param frames   Frame records, which must not be mutated afterwards.
+   * @param aliases  Aliases mapped to canonical ids, likewise.
+   * @param normalizeId The tree's id normalization (ROS naming and remaps)
+   *                    at the time of the snapshot, applied before aliases.
+   */
+  constructor(
+    revision: number,
+    frames: ReadonlyMap<string, FrameNode>,
+    aliases: ReadonlyMap<string, string>,
+    normalizeId: (id: string) => string = (id) => id,
+  ) {
+    this.revision = revision;
+    this.frames = frames;
+    this.aliases = aliases;
+    this.normalizeId = normalizeId;
+  }
+
+  /**
+   * Returns the canonical frame id for `id`, normalized and with aliases
+   * resolved as by {@link TFTree.resolveAlias}.
+   */
+  resolveAlias(id: string): string {
+    id = this.normalizeId(id);
+    return this.aliases.get(id) ?? id;
+  }
+
+  /** Returns `true` if the frame (or alias) was registered. */
+  hasFrame(id: string): boolean {
+    return this.frames.has(this.resolveAlias(id));
+  }
+
+  /** Returns every frame id, in registration order. */
+  frameIds(): string[] {
+    return Array.from(this.frames.keys());
+  }
+
+  /**
+   * Returns the parent of `id`, or `undefined` for a root frame.
+   *
+   * @throws {FrameNotFoundError} if `id` was not registered.
+   */
+  getParentId(id: string): string | undefined {
+    return this.getFrameNode(id).parentId;
+  }
+
+  /**
+   * Returns the transform of `id` relative to its parent.
+   *
+   * @throws {FrameNotFoundError} if `id` was not registered.
+   */
+  getLocalTransform(id: string): Transform {
+    return this.getFrameNode(id).transform;
+  }
+
+  /**
+   * Returns the transform of `id` relative to the root of its tree.
+   *
+   * @throws {FrameNotFoundError} if `id` was not registered.
+   */
+  getWorldTransform(id: string): Transform {
+    return this.worldTransformOf(this.getFrameNode(id));
+  }
+
+  /**
+   * Compute the transform between two frames, with the same convention as
+   * {@link TFTree.getTransform}.
+   *
+   * @throws {FrameNotFoundError} if either frame was not registered.
+   * @throws {DisconnectedFramesError} if the frames are in different trees.
+   */
+  getTransform(from: string, to: string): Transform {
+    const source = this.getFrameNode(from);
+    const target = this.getFrameNode(to);
+    if (source === target) {
+      return Transform.identity();
+    }
+    if (this.rootOf(source) !== this.rootOf(target)) {
+      throw new DisconnectedFramesError(source.id, target.id);
+    }
+    return this.worldTransformOf(source).invert().compose(this.worldTransformOf(target));
+  }
+
+  /** Returns `true` if `a` and `b` were both registered in the same tree. */
+  isConnected(a: string, b: string): boolean {
+    return (
+      this.hasFrame(a) &&
+      this.hasFrame(b) &&
+      this.rootOf(this.getFrameNode(a)) === this.rootOf(this.getFrameNode(b))
+    );
+  }
+
+  private getFrameNode(id: string): FrameNode {
+    id = this.resolveAlias(id);
+    const frame = this.frames.get(id);
+    if (frame === undefined) {
+      throw new FrameNotFoundError(id);
+    }
+    return frame;
+  }
+
+  /** World transform of `frame`, walking parents by their registered ids. */
+  private worldTransformOf(frame: FrameNode): Transform {
+    let world = this.worldTransforms.get(frame.id);
+    if (world === undefined) {
+      world =
+        frame.parentId === undefined
+          ? frame.transform
+          : this.worldTransformOf(this.frames.get(frame.parentId)!).compose(frame.transform);
+      this.worldTransforms.set(frame.id, world);
+    }
+    return world;
+  }
+
+  private rootOf(frame: FrameNode): string {
+    while (frame.parentId !== undefined) {
+      frame = this.frames.get(frame.parentId)!;
+    }
+    return frame.id;
+  }
+}
@@ -0,0 +1,26 @@
+import { TFError } from "./TFError.js";
+
+/**
+ * Thrown when a source other than a frame's owner tries to update it while
+ * the `"owner-only"` policy of {@link TFTree.setAuthorityPolicy} is active.
+ */
+export class UnauthorizedSourceError extends TFError {
+  /** Id of the frame. */
+  readonly frameId: string;
+  /** The source that was rejected. */
+  readonly source: string;
+  /** The source owning the frame. */
+  readonly owner: string;
+
+  constructor(frameId: string, source: string, owner: string) {
+    super(
+      "UNAUTHORIZED_SOURCE",
+      `Source "${source}" may not update frame "${frameId}", which is owned by "${owner}".`,
+      { frameIds: [frameId] },
+    );
+    this.name = "UnauthorizedSourceError";
+    this.frameId = frameId;
+    this.source = source;
+    this.owner = owner;
+  }
+}
@@ -3,14 +3,123 @@ export { Quaternion } from "./math/Quaternion.js";
 export { Transform } from "./math/Transform.js";
 export { TFTree } from "./TFTree.js";
 export { BufferedTFTree } from "./BufferedTFTree.js";
+export { TFForest } from "./TFForest.js";
+export { TFTreeSnapshot } from "./TFTreeSnapshot.js";
+export { SharedTransformBuffer } from "./SharedTransformBuffer.js";
+export { TFError } from "./TFError.js";
+export { OutOfRangeError } from "./OutOfRangeError.js";
 export { CycleDetectedError } from "./CycleDetectedError.js";
+export { FrameLockedError } from "./FrameLockedError.js";
+export { InvalidTransformError } from "./InvalidTransformError.js";
+export { InvalidFrameIdError } from "./InvalidFrameIdError.js";
+export { QuotaExceededError } from "./QuotaExceededError.js";
+export { UnauthorizedSourceError } from "./UnauthorizedSourceError.js";
+export { ReadOnlyNamespaceError } from "./ReadOnlyNamespaceError.js";
+export { FrameNotFoundError } from "./FrameNotFoundError.js";
+export { DuplicateFrameError } from "./DuplicateFrameError.js";
+export { DisconnectedFramesError } from "./DisconnectedFramesError.js";
+export { ChangeStream } from "./ChangeStream.js";
+export { encodeChangePacket, decodeChangePacket } from "./ChangePacket.js";
+export { encodeSyncPacket, decodeSyncPacket } from "./SyncPacket.js";
 export type {
+  TFErrorCode,
+  TFErrorContext,
   ITransform,
   FrameNode,
   ITransformTree,
   FrameNodeJSON,
+  TransformJSON,
   TFTreeJSON,
+  JSONRepair,
+  RepairedJSON,
   TransformStamped,
   BufferedTFTreeOptions,
+  UpdateSourceOptions,
   ChangeCallback,
+  WorldTransformChange,
+  ChangeSubscriptionOptions,
+  BatchChangeCallback,
+  ChangeBatch,
+  ChangePacket,
+  SyncPacket,
+  SyncOptions,
+  ExportFilter,
+  BatchSubscriptionOptions,
+  ChangeStreamOptions,
+  StructureEvent,
+  StructureChangeCallback,
+  JointType,
+  JointOptions,
+  Joint,
+  JointLimitPolicy,
+  JointLimits,
+  JointLimitCallback,
+  JointMimic,
+  ChainLink,
+  KinematicChain,
+  IkOptions,
+  IkFailureReason,
+  IkResult,
+  DhConvention,
+  DhLink,
+  DhChainOptions,
+  CalibrationMetadata,
+  Calibration,
+  CalibrationAuditOptions,
+  CalibrationIssueKind,
+  CalibrationIssue,
+  HandEyeSetup,
+  HandEyeOptions,
+  HandEyeResult,
+  GeoAnchor,
+  ResolvedFrame,
+  FrameResolver,
+  RegionCallback,
+  ConnectivityCallback,
+  WatchedPairChanges,
+  WatchPriority,
+  RemoveFrameOptions,
+  ExtractSubtreeOptions,
+  ReattachOptions,
+  JournalOperation,
+  JournalEntry,
+  TreeChange,
+  SnapshotDiffOptions,
+  SnapshotDifference,
+  MergeSide,
+  MergeResolution,
+  MergeOptions,
+  MergeConflict,
+  MergeReport,
+  TraversalOrder,
+  CoveringSubtree,
+  FrameComponent,
+  FrameIdPolicy,
+  RosNamingOptions,
+  TreeQuotas,
+  NamespacePolicy,
+  NamespaceStats,
+  BatchOptions,
+  UpdateOptions,
+  StampedUpdateOptions,
+  AuthorityPolicy,
+  RotationNormMode,
+  ValidationIssueKind,
+  ValidationIssue,
+  ValidationReport,
+  DiagnosticKind,
+  Diagnostic,
+  CacheMismatch,
+  DiagnoseOptions,
+  DebugDumpOptions,
+  AABB,
+  OBB,
+  Ray,
+  FrameDistance,
+  FloatArray,
+  PointCloudOptions,
+  CameraIntrinsics,
+  ProjectedPoints,
+  ForestFrame,
+  ForestBridge,
 } from "./types.js";
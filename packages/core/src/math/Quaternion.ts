@@ -96,6 +96,12 @@ export class Quaternion {
     return Math.abs(Math.abs(dot) - 1) <= epsilon;
   }
 
+  /** Angle in radians, in `[0, π]`, of the rotation taking this orientation to `other`. */
+  angleTo(other: Quaternion): number {
+    const dot = this.x * other.x + this.y * other.y + this.z * other.z + this.w * other.w;
+    return 2 * Math.acos(Math.min(1, Math.abs(dot)));
+  }
+
   toArray(): [number, number, number, number] {
     return [this.x, this.y, this.z, this.w];
   }
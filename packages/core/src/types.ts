@@ -2,6 +2,51 @@ import { Vec3 } from "./math/Vec3.js";
 import { Quaternion } from "./math/Quaternion.js";
 import { Transform } from "./math/Transform.js";
 
+/**
+ * Stable, machine-readable code carried by every error thrown by this
+ * package (see {@link TFError}).  Codes are never renamed once published.
+ *
+ * - `"FRAME_NOT_FOUND"` – a frame id is not registered.
+ * - `"DUPLICATE_FRAME"` – an id is already registered.
+ * - `"INVALID_FRAME_ID"` – an id is rejected by the frame id policy.
+ * - `"CYCLE_DETECTED"` – the operation would create a cycle.
+ * - `"DISCONNECTED_FRAMES"` – the frames live in different trees.
+ * - `"FRAME_LOCKED"` – the frame is locked.
+ * - `"QUOTA_EXCEEDED"` – the operation would exceed a configured quota.
+ * - `"UNAUTHORIZED_SOURCE"` – an update comes from a source not owning the
+ *   frame.
+ * - `"READ_ONLY_NAMESPACE"` – the frame is in a read-only namespace.
+ * - `"INVALID_TRANSFORM"` – a transform has a non-finite component or an
+ *   invalid rotation.
+ * - `"ALIAS_NOT_FOUND"` – an alias is not registered.
+ * - `"INVALID_ARGUMENT"` – an argument is not valid for this operation.
+ * - `"INVALID_STATE"` – the tree is not in a state that allows the operation.
+ * - `"OUT_OF_RANGE"` – the requested data is no longer (or not yet) retained.
+ */
+export type TFErrorCode =
+  | "FRAME_NOT_FOUND"
+  | "DUPLICATE_FRAME"
+  | "INVALID_FRAME_ID"
+  | "CYCLE_DETECTED"
+  | "DISCONNECTED_FRAMES"
+  | "FRAME_LOCKED"
+  | "QUOTA_EXCEEDED"
+  | "UNAUTHORIZED_SOURCE"
+  | "READ_ONLY_NAMESPACE"
+  | "INVALID_TRANSFORM"
+  | "ALIAS_NOT_FOUND"
+  | "INVALID_ARGUMENT"
+  | "INVALID_STATE"
+  | "OUT_OF_RANGE";
+
+/** Structured context attached to every error thrown by this package. */
+export interface TFErrorContext {
+  /** Ids of the frames involved, if any. */
+  readonly frameIds: readonly string[];
+  /** Name of the method that failed, where known. */
+  readonly operation?: string;
+}
+
 /**
  * Structural interface for a rigid-body transform:
  * a translation ({@link Vec3}) and a rotation ({@link Quaternion}).
@@ -24,6 +69,16 @@ export interface FrameNode {
   readonly transform: Transform;
 }
 
+/**
+ * JSON-serializable representation of a {@link Transform}.
+ */
+export interface TransformJSON {
+  /** [x, y, z] translation. */
+  readonly translation: [number, number, number];
+  /** [x, y, z, w] quaternion rotation. */
+  readonly rotation: [number, number, number, number];
+}
+
 /**
  * JSON-serializable representation of a single frame node.
  * Used by {@link TFTree.toJSON} and {@link TFTree.fromJSON}.
@@ -32,12 +87,7 @@ export interface FrameNodeJSON {
   readonly id: string;
   /** Parent frame id; null for a root frame. */
   readonly parentId: string | null;
-  readonly transform: {
-    /** [x, y, z] translation. */
-    readonly translation: [number, number, number];
-    /** [x, y, z, w] quaternion rotation. */
-    readonly rotation: [number, number, number, number];
-  };
+  readonly transform: TransformJSON;
 }
 
 /**
@@ -49,6 +99,30 @@ export interface TFTreeJSON {
   readonly frames: FrameNodeJSON[];
 }
 
+/**
+ * A fix applied by {@link TFTree.repairJSON}.
+ *
+ * - `"duplicate-dropped"` – a later frame with an already used id was
+ *   dropped.
+ * - `"dangling-parent"` – the frame's parent was missing; it became a root.
+ * - `"cycle-broken"` – the frame closed a parent cycle; it became a root.
+ *
+ * `parentId` is the parent the frame had in the input.
+ */
+export interface JSONRepair {
+  readonly type: "duplicate-dropped" | "dangling-parent" | "cycle-broken";
+  readonly id: string;
+  readonly parentId: string | null;
+}
+
+/** Result of {@link TFTree.repairJSON}. */
+export interface RepairedJSON {
+  /** The repaired data, in parent-before-child order. */
+  readonly data: TFTreeJSON;
+  /** Every fix applied, in the order applied. */
+  readonly repairs: JSONRepair[];
+}
+
 /**
  * A transform paired with a wall-clock (or monotonic) timestamp in milliseconds.
  * Used by {@link BufferedTFTree} to record historical transforms.
@@ -73,13 +147,1153 @@ export interface BufferedTFTreeOptions {
   maxBufferDuration?: number;
 }
 
+/**
+ * Timing and precedence of an update source registered with
+ * {@link BufferedTFTree.setUpdateSource}.
+ */
+export interface UpdateSourceOptions {
+  /**
+   * Milliseconds added to the source's timestamps to express them on the
+   * tree's clock (tree time − source time).
+   *
+   * @default 0
+   */
+  clockOffset?: number;
+  /**
+   * Estimated delay, in milliseconds, between the moment a transform was
+   * valid and the timestamp the source stamps it with; subtracted from
+   * every timestamp.
+   *
+   * @default 0
+   */
+  latency?: number;
+  /**
+   * Precedence over other sources updating the same frame: while a source
+   * of higher priority has updated a frame within the buffer window, updates
+   * of the frame from lower-priority sources are ignored.
+   *
+   * @default 0
+   */
+  priority?: number;
+  /**
+   * Estimate {@link clockOffset} from the updates themselves instead: it is
+   * set to the smallest `receivedAt − timestamp` seen over the buffer
+   * window, so a drifting clock is followed.  The estimate includes the
+   * smallest transport delay; set {@link latency} to it if it is known.
+   *
+   * @default false
+   */
+  estimateClockOffset?: boolean;
+}
+
+/**
+ * Options for {@link TFTree.removeFrame}.
+ */
+export interface RemoveFrameOptions {
+  /**
+   * Reparent the removed frame's children to its parent (or make them roots
+   * when the removed frame is a root) instead of refusing the removal.
+   *
+   * @default false
+   */
+  promoteChildren?: boolean;
+  /**
+   * When promoting children, precompose the removed frame's transform into
+   * each child's local transform so the children do not move.  Ignored
+   * unless `promoteChildren` is set.
+   *
+   * @default false
+   */
+  preserveWorld?: boolean;
+}
+
+/**
+ * Options for {@link TFTree.reattach}.
+ */
+export interface ReattachOptions {
+  /**
+   * Keep the frame's current world pose instead of restoring the pose it had
+   * when it was detached — e.g. after the user dragged the floating object.
+   *
+   * @default false
+   */
+  keepCurrentPose?: boolean;
+}
+
+/**
+ * Options for {@link TFTree.extractSubtree}.
+ */
+export interface ExtractSubtreeOptions {
+  /**
+   * Remove the extracted frames from the source tree.
+   *
+   * @default false
+   */
+  remove?: boolean;
+}
+
+/**
+ * A single primitive mutation recorded in a {@link TFTree} journal.
+ *
+ * Composite operations (e.g. {@link TFTree.removeSubtree} or
+ * {@link TFTree.attachTree}) are journaled as the sequence of primitives they
+ * perform, so replaying the operations in order reproduces the same tree.
+ */
+export type JournalOperation =
+  | {
+      readonly op: "add";
+      readonly id: string;
+      readonly parentId: string | null;
+      readonly transform: TransformJSON;
+    }
+  | { readonly op: "update"; readonly id: string; readonly transform: TransformJSON }
+  | {
+      readonly op: "relink";
+      readonly id: string;
+      readonly parentId: string | null;
+      readonly transform: TransformJSON;
+    }
+  | { readonly op: "remove"; readonly id: string }
+  | { readonly op: "rename"; readonly id: string; readonly newId: string }
+  | { readonly op: "clear" };
+
+/**
+ * A journaled mutation together with its sequence number.  Sequence numbers
+ * start at 1 and increase by one per recorded mutation.
+ */
+export type JournalEntry = JournalOperation & { readonly seq: number };
+
+/**
+ * A single structural or transform difference between two trees, as produced
+ * by {@link TFTree.diff} and consumed by {@link TFTree.applyPatch}.
+ *
+ * - `"added"` – the frame exists only in the target tree.
+ * - `"removed"` – the frame exists only in the source tree.
+ * - `"reparented"` – the frame has a different parent (its local transform
+ *   is included because it usually changes too).
+ * - `"moved"` – same parent, different local transform.
+ */
+export type TreeChange =
+  | {
+      readonly type: "added";
+      readonly id: string;
+      readonly parentId: string | null;
+      readonly transform: TransformJSON;
+    }
+  | { readonly type: "removed"; readonly id: string }
+  | {
+      readonly type: "reparented";
+      readonly id: string;
+      readonly parentId: string | null;
+      readonly transform: TransformJSON;
+    }
+  | { readonly type: "moved"; readonly id: string; readonly transform: TransformJSON };
+
+/** Options for {@link TFTree.diffAgainst}. */
+export interface SnapshotDiffOptions {
+  /**
+   * Frames that moved by at most this distance, in world units, and turned
+   * by at most `minRotation` are not reported.
+   *
+   * @default 0
+   */
+  minTranslation?: number;
+  /**
+   * Rotation angle, in radians, a frame must exceed to be reported (unless
+   * it moved by more than `minTranslation`).
+   *
+   * @default 0
+   */
+  minRotation?: number;
+  /**
+   * Compare local transforms (relative to the parent, the default) or world
+   * transforms (relative to the root, so a frame is reported when any
+   * ancestor moved).
+   *
+   * @default "local"
+   */
+  space?: "local" | "world";
+}
+
+/**
+ * A difference between a {@link TFTree} and an earlier snapshot of it, as
+ * reported by {@link TFTree.diffAgainst}.
+ *
+ * - `"added"` / `"removed"` – the frame exists only now / only in the
+ *   snapshot.
+ * - `"reparented"` – the frame has a different parent than in the snapshot.
+ * - `"moved"` – the frame moved by `translation` world units and turned by
+ *   `rotation` radians, beyond at least one of the thresholds.
+ */
+export type SnapshotDifference =
+  | { readonly type: "added"; readonly id: string; readonly parentId: string | null }
+  | { readonly type: "removed"; readonly id: string; readonly parentId: string | null }
+  | {
+      readonly type: "reparented";
+      readonly id: string;
+      readonly parentId: string | null;
+      readonly previousParentId: string | null;
+    }
+  | {
+      readonly type: "moved";
+      readonly id: string;
+      readonly translation: number;
+      readonly rotation: number;
+    };
+
+/** One of the two replicas in a {@link TFTree.merge}: the tree merged into, or the other. */
+export type MergeSide = "ours" | "theirs";
+
+/**
+ * Why a {@link MergeConflict} was resolved the way it was:
+ *
+ * - `"namespace"` – the frame lies in a namespace owned by the winner.
+ * - `"timestamp"` – the winner modified the frame last.
+ * - `"tie"` – both were modified at the same time; the greater serialized
+ *   frame wins.
+ * - `"locked"` – the frame is locked or in a read-only namespace in this
+ *   tree, so this tree's side is kept.
+ * - `"cycle"` – the other side's parent would create a cycle here, so this
+ *   tree's side is kept.
+ */
+export type MergeResolution = "namespace" | "timestamp" | "tie" | "locked" | "cycle";
+
+/** Options for {@link TFTree.merge}. */
+export interface MergeOptions {
+  /**
+   * Frame id prefixes (e.g. `"robot1/"`) mapped to the side that owns them;
+   * conflicts inside a namespace always go to its owner.
+   */
+  namespaces?: Record<string, MergeSide>;
+}
+
+/** A frame that differs between two merged replicas. */
+export interface MergeConflict {
+  readonly frameId: string;
+  /** The frame in the tree merged into, before the merge. */
+  readonly ours: FrameNodeJSON;
+  /** The frame in the other tree. */
+  readonly theirs: FrameNodeJSON;
+  /** The side whose parent and transform the merged tree keeps. */
+  readonly winner: MergeSide;
+  readonly reason: MergeResolution;
+}
+
+/** Result of {@link TFTree.merge}. */
+export interface MergeReport {
+  /** Frames only present in the other tree, in the order they were added. */
+  readonly added: string[];
+  /** Frames that differed, in the order they were resolved. */
+  readonly conflicts: MergeConflict[];
+}
+
+/**
+ * World transforms passed to a {@link ChangeCallback}.
+ */
+export interface WorldTransformChange {
+  /** World transform at the previous notification (or at subscription time). */
+  readonly previous: Transform;
+  /** World transform now. */
+  readonly current: Transform;
+}
+
 /**
  * Callback invoked when a frame's world transform changes — either because the
  * frame itself was updated or because one of its ancestors was updated.
  *
  * @param frameId The id of the frame whose world transform has changed.
+ * @param change  The frame's previous and new world transform, e.g. to derive
+ *                velocities without keeping a shadow copy.
+ */
+export type ChangeCallback = (frameId: string, change: WorldTransformChange) => void;
+
+/**
+ * Options for {@link TFTree.onChange}.  A listener with thresholds is only
+ * notified once the frame's world transform has moved by more than one of
+ * them since the last notification it received, suppressing sensor jitter.
+ */
+export interface ChangeSubscriptionOptions {
+  /**
+   * Minimum translation, in world units, that triggers a notification.
+   *
+   * @default 0
+   */
+  minTranslation?: number;
+  /**
+   * Minimum rotation angle, in radians, that triggers a notification.
+   *
+   * @default 0
+   */
+  minRotation?: number;
+}
+
+/**
+ * Options for {@link TFTree.onAnyChange} that narrow a subscription to part
+ * of the scene.  When both are given, a frame must satisfy both.
+ */
+export interface BatchSubscriptionOptions {
+  /**
+   * Only report frames whose id matches this glob pattern, e.g.
+   * `"robot1/**"` (same syntax as {@link TFTree.findFrames}).
+   */
+  pattern?: string;
+  /** Only report `subtreeRoot` and its descendants. */
+  subtreeRoot?: string;
+}
+
+/**
+ * Options for {@link TFTree.changes}.
+ */
+export interface ChangeStreamOptions extends BatchSubscriptionOptions {
+  /**
+   * Number of unconsumed batches to queue before further changes are merged
+   * into the last queued batch.
+   *
+   * @default 16
+   */
+  highWaterMark?: number;
+}
+
+/**
+ * Callback registered with {@link TFTree.onAnyChange}.  Invoked once per
+ * mutation (or once per committed transaction) with the ids of every frame
+ * whose world transform changed.
+ *
+ * @param frameIds The ids of all frames whose world transform has changed.
+ * @param seq      Sequence number of the batch, usable with
+ *                 {@link TFTree.eventsSince}.
+ */
+export type BatchChangeCallback = (frameIds: string[], seq: number) => void;
+
+/**
+ * A delivered change batch, as retained by {@link TFTree.enableEventLog}.
+ */
+export interface ChangeBatch {
+  /** Sequence number; the first batch is 1 and each batch adds one. */
+  readonly seq: number;
+  /** The ids of all frames whose world transform changed. */
+  readonly frameIds: string[];
+}
+
+/**
+ * A change to the shape of the tree (as opposed to a transform update),
+ * delivered to {@link TFTree.onStructureChange} listeners.
+ *
+ * - `"added"` / `"removed"` – a frame was registered / removed.
+ * - `"reparented"` – a frame moved from `oldParentId` to `parentId`.
+ * - `"renamed"` – frame `oldId` is now called `id`.
+ * - `"cleared"` – every frame was removed by {@link TFTree.clear}.
+ */
+export type StructureEvent =
+  | { readonly type: "added"; readonly id: string; readonly parentId: string | null }
+  | { readonly type: "removed"; readonly id: string; readonly parentId: string | null }
+  | {
+      readonly type: "reparented";
+      readonly id: string;
+      readonly oldParentId: string | null;
+      readonly parentId: string | null;
+    }
+  | { readonly type: "renamed"; readonly oldId: string; readonly id: string }
+  | { readonly type: "cleared" };
+
+/**
+ * Callback registered with {@link TFTree.onStructureChange}.
+ *
+ * @param event Description of the structural change.
+ */
+export type StructureChangeCallback = (event: StructureEvent) => void;
+
+/** A frame supplied by a {@link FrameResolver}. */
+export interface ResolvedFrame {
+  /** Id of the parent frame, or `undefined` for a root frame. */
+  readonly parentId?: string | undefined;
+  /** Transform of the frame relative to its parent. */
+  readonly transform: Transform;
+}
+
+/**
+ * Callback registered with {@link TFTree.setFrameResolver}, asked for a frame
+ * the tree does not have.  Returns the frame, or `undefined` if it is
+ * unknown here too.  The frame's parent is resolved in turn if missing.
+ */
+export type FrameResolver = (id: string) => ResolvedFrame | undefined;
+
+/**
+ * Callback registered with {@link TFTree.onRegionChange}.  Invoked only when
+ * the watched frame crosses the region boundary.
+ *
+ * @param frameId The id of the watched frame.
+ * @param inside  `true` if the frame just entered the region, `false` if it
+ *                just left it.
+ */
+export type RegionCallback = (frameId: string, inside: boolean) => void;
+
+/**
+ * Callback registered with {@link TFTree.onConnectivityChange}.
+ *
+ * @param connected `true` if the two frames just became connected, `false`
+ *                  if they were just disconnected.
+ */
+export type ConnectivityCallback = (connected: boolean) => void;
+
+/**
+ * How eagerly a pair registered with {@link TFTree.watchTransform} is
+ * recomputed.
+ *
+ * - `"high"` – on every change to one of its frames.
+ * - `"low"` – only on {@link TFTree.flushWatches}.
+ */
+export type WatchPriority = "high" | "low";
+
+/**
+ * Which frames {@link TFTree.toJSON} and {@link TFTree.encodeSyncPacket}
+ * include, set with {@link TFTree.setExportFilter}.  A frame is exported if
+ * it matches one of `prefixes` or `ids` (any frame when both are omitted)
+ * and, with `staticOnly`, is static.
+ */
+export interface ExportFilter {
+  /** Export the frames whose id starts with one of these prefixes. */
+  prefixes?: readonly string[];
+  /** Export these frames. */
+  ids?: readonly string[];
+  /**
+   * Only export frames whose transform was never updated after they were
+   * added.
+   *
+   * @default false
+   */
+  staticOnly?: boolean;
+}
+
+/**
+ * Options for {@link TFTree.encodeSyncPacket}.
+ */
+export interface SyncOptions {
+  /**
+   * Translations are sent as multiples of this step, in tree units.
+   * Defaults to `1e-4` (0.1 mm for trees in metres).
+   */
+  translationStep?: number;
+}
+
+/**
+ * A replication delta decoded by {@link decodeSyncPacket}.
+ */
+export interface SyncPacket {
+  /** Sender's journal sequence number the packet brings a replica up to. */
+  readonly seq: number;
+  /** Operations to apply, in order, with dequantized transforms. */
+  readonly operations: JournalOperation[];
+}
+
+/**
+ * A change batch decoded by {@link decodeChangePacket}.
+ */
+export interface ChangePacket {
+  /** Sequence number of the batch (see {@link TFTree.lastEventSeq}). */
+  readonly seq: number;
+  /** The ids of the changed frames. */
+  readonly frameIds: string[];
+  /**
+   * Seven numbers per frame, in the order of {@link frameIds}: world
+   * translation `x, y, z` followed by rotation quaternion `x, y, z, w`.
+   */
+  readonly data: Float64Array;
+}
+
+/**
+ * Watched transform pairs whose value changed, as returned by
+ * {@link TFTree.flushWatches}.
+ */
+export interface WatchedPairChanges {
+  /** Slot index of each changed pair, in ascending order. */
+  readonly slots: number[];
+  /**
+   * Seven numbers per changed pair, in the order of {@link slots}:
+   * translation `x, y, z` followed by rotation quaternion `x, y, z, w`.
+   */
+  readonly data: Float64Array;
+}
+
+/**
+ * Visiting order used by {@link TFTree.traverse}.
+ *
+ * - `"bfs"` – breadth-first, level by level.
+ * - `"dfs-pre"` – depth-first, each frame before its descendants.
+ * - `"dfs-post"` – depth-first, each frame after its descendants.
+ *
+ * Siblings are always visited in registration order.
+ */
+export type TraversalOrder = "bfs" | "dfs-pre" | "dfs-post";
+
+/**
+ * Result of {@link TFTree.coveringSubtree}: the minimal connected slice of a
+ * tree that contains a given set of frames.
+ */
+export interface CoveringSubtree {
+  /** Lowest common ancestor of the requested frames. */
+  readonly rootId: string;
+  /** Member frame ids in depth-first pre-order, starting with `rootId`. */
+  readonly frameIds: string[];
+}
+
+/**
+ * One connected tree of a possibly disconnected forest, as returned by
+ * {@link TFTree.getComponents}.
+ */
+export interface FrameComponent {
+  /** The component's root frame (a frame with no parent). */
+  readonly rootId: string;
+  /** Member frame ids in depth-first pre-order, starting with `rootId`. */
+  readonly frameIds: string[];
+}
+
+/**
+ * Rules for acceptable frame ids, set with {@link TFTree.setFrameIdPolicy}.
+ */
+export interface FrameIdPolicy {
+  /** Reject the empty string.  Defaults to `true`. */
+  nonEmpty?: boolean;
+  /** Maximum id length in characters. */
+  maxLength?: number;
+  /** Pattern the whole id must match, e.g. `/^[A-Za-z0-9_\/]+$/`. */
+  pattern?: RegExp;
+  /** Prefixes reserved by the embedder that ids may not start with. */
+  reservedPrefixes?: readonly string[];
+}
+
+/**
+ * Options for {@link TFTree.updateTransform}, {@link TFTree.updateTransforms}
+ * and {@link BufferedTFTree.setTransform}.
+ */
+export interface UpdateOptions {
+  /**
+   * Id of the producer issuing the update (a node, topic or connection),
+   * recorded as the frame's last source and checked against its owner.
+   */
+  source?: string;
+}
+
+/**
+ * Options for {@link BufferedTFTree.setTransform}.
  */
-export type ChangeCallback = (frameId: string) => void;
+export interface StampedUpdateOptions extends UpdateOptions {
+  /**
+   * When the update was received, on the tree's clock (see
+   * {@link TFTree.setClock}); used to estimate the source's clock offset.
+   *
+   * @default the tree's current time
+   */
+  receivedAt?: number;
+}
+
+/**
+ * How {@link TFTree.updateTransform} treats update sources:
+ *
+ * - `"record"` (the default) – sources are only recorded.
+ * - `"owner-only"` – updates from a source other than the frame's owner are
+ *   rejected; the first source to update an unowned frame becomes its owner.
+ */
+export type AuthorityPolicy = "record" | "owner-only";
+
+/**
+ * Options for batch operations such as {@link TFTree.updateTransforms},
+ * {@link TFTree.applyPatch}, {@link TFTree.applyJournal} and
+ * {@link TFTree.applySyncPacket}.
+ */
+export interface BatchOptions {
+  /**
+   * Perform all validation but apply nothing; the call throws exactly when
+   * the real one would.  Not available inside a transaction, where the call
+   * throws a {@link TFError} with code `INVALID_STATE`: commit or roll back
+   * first.
+   */
+  validateOnly?: boolean;
+}
+
+/**
+ * Resource limits set with {@link TFTree.setQuotas}.  Omitted limits are not
+ * enforced.
+ */
+export interface TreeQuotas {
+  /** Maximum number of registered frames. */
+  maxFrames?: number;
+  /** Maximum number of children of any one frame. */
+  maxChildren?: number;
+  /** Maximum number of ancestors of any frame. */
+  maxDepth?: number;
+  /**
+   * Maximum number of entries in one batch — {@link TFTree.updateTransforms},
+   * {@link TFTree.applyPatch} or {@link TFTree.applyJournal}.
+   */
+  maxBatchSize?: number;
+}
+
+/**
+ * Policy of a frame namespace, set with {@link TFTree.setNamespacePolicy}.
+ */
+export interface NamespacePolicy {
+  /**
+   * Reject adding frames to the namespace and removing, renaming, reparenting
+   * or replacing the transforms of its frames.  To unload a plugin, remove
+   * the policy first.
+   *
+   * @default false
+   */
+  readOnly?: boolean;
+  /** Maximum number of frames in the namespace. */
+  maxFrames?: number;
+}
+
+/** Statistics of a namespace, as returned by {@link TFTree.getNamespaceStats}. */
+export interface NamespaceStats {
+  /** Number of frames currently in the namespace. */
+  readonly frames: number;
+  /** Number of mutations of its frames since the policy was set. */
+  readonly mutations: number;
+  /** Number of operations rejected by its policy since it was set. */
+  readonly rejected: number;
+}
+
+/** Options for {@link TFTree.setRosNaming}. */
+export interface RosNamingOptions {
+  /**
+   * ROS `tf_prefix` applied to relative ids, e.g. `"robot1"`.  Leading and
+   * trailing slashes are ignored.
+   */
+  tfPrefix?: string;
+}
+
+/**
+ * How {@link TFTree.setRotationNormMode} handles incoming rotations that are
+ * not unit quaternions.
+ */
+export type RotationNormMode = "off" | "strict" | "normalize";
+
+/**
+ * Kind of broken invariant reported by {@link TFTree.validate}.
+ *
+ * - `"missing-parent"` – a frame's parent is not registered.
+ * - `"cycle"` – a frame's chain of parents loops back on itself.
+ * - `"children-mismatch"` – the children index disagrees with parent links.
+ * - `"subtree-size"` – a cached subtree size is wrong.
+ * - `"dangling-entry"` – a cache, dirty flag or version refers to an
+ *   unregistered frame.
+ * - `"unnormalized-rotation"` – a stored rotation is not a unit quaternion.
+ */
+export type ValidationIssueKind =
+  | "missing-parent"
+  | "cycle"
+  | "children-mismatch"
+  | "subtree-size"
+  | "dangling-entry"
+  | "unnormalized-rotation";
+
+/** A single broken invariant found by {@link TFTree.validate}. */
+export interface ValidationIssue {
+  readonly kind: ValidationIssueKind;
+  /** The frame the issue was found at. */
+  readonly frameId: string;
+  /** Human-readable description. */
+  readonly message: string;
+}
+
+/** Result of {@link TFTree.validate}. */
+export interface ValidationReport {
+  /** `true` if no issue was found. */
+  readonly valid: boolean;
+  /** Every issue found. */
+  readonly issues: ValidationIssue[];
+}
+
+/**
+ * Kind of suspicious data reported by {@link TFTree.diagnose}.
+ *
+ * - `"degenerate-rotation"` – a rotation quaternion is (nearly) zero.
+ * - `"unnormalized-rotation"` – a rotation is far from unit norm.
+ * - `"large-translation"` – a translation is implausibly large.
+ * - `"deep-chain"` – the frame is nested deeper than expected.
+ * - `"never-updated"` – the frame kept its registration transform; fine for
+ *   static frames, suspicious for ones that should be streamed.
+ */
+export type DiagnosticKind =
+  | "degenerate-rotation"
+  | "unnormalized-rotation"
+  | "large-translation"
+  | "deep-chain"
+  | "never-updated";
+
+/** A likely data problem found by {@link TFTree.diagnose}. */
+export interface Diagnostic {
+  readonly kind: DiagnosticKind;
+  /** `"info"` for findings that are often legitimate. */
+  readonly severity: "warning" | "info";
+  readonly frameId: string;
+  /** Human-readable description. */
+  readonly message: string;
+}
+
+/** A stale cached world transform found by {@link TFTree.verifyCache}. */
+export interface CacheMismatch {
+  readonly frameId: string;
+  /** World transform held in the cache. */
+  readonly cached: Transform;
+  /** World transform recomputed from the local transforms. */
+  readonly expected: Transform;
+}
+
+/** Thresholds for {@link TFTree.diagnose}. */
+export interface DiagnoseOptions {
+  /** Allowed deviation of a rotation's norm from 1.  Defaults to `1e-3`. */
+  normTolerance?: number;
+  /** Largest plausible local translation length.  Defaults to `1e6`. */
+  maxTranslation?: number;
+  /** Largest expected number of ancestors of a frame.  Defaults to `64`. */
+  maxDepth?: number;
+}
+
+/** Options for {@link TFTree.debugDump}. */
+export interface DebugDumpOptions {
+  /** Number of decimals printed per component.  Defaults to `3`. */
+  precision?: number;
+}
+
+/**
+ * Axis-aligned bounding box described by its minimum and maximum corners.
+ */
+export interface AABB {
+  readonly min: Vec3;
+  readonly max: Vec3;
+}
+
+/** A ray starting at `origin` and pointing along `direction`. */
+export interface Ray {
+  readonly origin: Vec3;
+  readonly direction: Vec3;
+}
+
+/**
+ * Oriented bounding box: a box of size `2 * halfExtents` centred at `center`
+ * and rotated by `rotation`, all relative to the frame it is expressed in.
+ */
+export interface OBB {
+  readonly center: Vec3;
+  readonly halfExtents: Vec3;
+  readonly rotation: Quaternion;
+}
+
+/**
+ * A frame id paired with a distance, as returned by
+ * {@link TFTree.framesByDistance}.
+ */
+export interface FrameDistance {
+  readonly id: string;
+  readonly distance: number;
+}
+
+/**
+ * A floating-point buffer accepted by the bulk geometry methods of
+ * {@link TFTree}, including views over a `SharedArrayBuffer`.
+ */
+export type FloatArray = Float32Array | Float64Array;
+
+/**
+ * Kind of joint registered with {@link TFTree.addJoint}:
+ *
+ * - `"revolute"` – rotation about the axis by the position, in radians.
+ * - `"prismatic"` – translation along the axis by the position, in tree
+ *   units.
+ * - `"planar"` – motion in the plane normal to the axis, e.g. a mobile
+ *   base: position `[x, y, yaw]`.  In-plane `x` is the projection of the
+ *   frame's `x` axis onto the plane (its `y` axis if the normal is close to
+ *   `x`), and `yaw` a rotation about the normal.
+ * - `"floating"` – free motion, e.g. a free body: position
+ *   `[x, y, z, qx, qy, qz, qw]`, a translation and a rotation quaternion.
+ *
+ * Limits and mimic couplings apply to single-DoF (revolute and prismatic)
+ * joints only.
+ */
+export type JointType = "revolute" | "prismatic" | "planar" | "floating";
+
+/** Options for {@link TFTree.addJoint}. */
+export interface JointOptions {
+  readonly type: JointType;
+  /**
+   * Axis of motion, in the joint frame at position zero; normalized.  The
+   * plane normal of a planar joint; unused by a floating one.
+   *
+   * @default (1, 0, 0), as in URDF
+   */
+  readonly axis?: Vec3;
+  /**
+   * Local transform of the frame at position zero.
+   *
+   * @default the frame's current local transform
+   */
+  readonly origin?: Transform;
+  /**
+   * Initial position: a number for a single-DoF joint, an array of
+   * coordinates (see {@link JointType}) for a planar or floating one.
+   *
+   * @default 0, or the identity pose
+   */
+  readonly position?: number | readonly number[];
+  /** Range of positions the joint may take, e.g. its mechanical stops. */
+  readonly limits?: JointLimits;
+  /**
+   * Make the joint follow another one, as a URDF `<mimic>` element does for
+   * gripper fingers and linkages: its position is kept at
+   * `multiplier * position + offset` of that joint, within its own limits.
+   * A mimic joint can't be moved directly, and `position` must be omitted.
+   */
+  readonly mimic?: JointMimic;
+}
+
+/** Coupling of a mimic joint, see {@link JointOptions.mimic}. */
+export interface JointMimic {
+  /** Name of the joint followed, which must already be registered. */
+  readonly joint: string;
+  /** @default 1 */
+  readonly multiplier?: number;
+  /** @default 0 */
+  readonly offset?: number;
+}
+
+/**
+ * What a joint does with a position outside its {@link JointLimits}:
+ *
+ * - `"clamp"` – the position is clamped to the limits.
+ * - `"warn"` – the position is clamped, and listeners registered with
+ *   {@link TFTree.onJointLimit} are notified.
+ * - `"throw"` – the position is rejected with an {@link OutOfRangeError}.
+ */
+export type JointLimitPolicy = "clamp" | "warn" | "throw";
+
+/** Position limits of a joint, in radians or tree units. */
+export interface JointLimits {
+  /** @default -Infinity */
+  readonly lower?: number;
+  /** @default Infinity */
+  readonly upper?: number;
+  /** @default "clamp" */
+  readonly policy?: JointLimitPolicy;
+}
+
+/** A joint registered with {@link TFTree.addJoint}. */
+export interface Joint {
+  readonly name: string;
+  /** The frame the joint moves relative to its parent. */
+  readonly frameId: string;
+  readonly type: JointType;
+  /** Unit axis of motion. */
+  readonly axis: Vec3;
+  /** Local transform of the frame at position zero. */
+  readonly origin: Transform;
+  /** The position of a single-DoF joint; the first coordinate otherwise. */
+  readonly position: number;
+  /**
+   * Every coordinate of the position: `[position]` for a single-DoF joint,
+   * more for a planar or floating one (see {@link JointType}).
+   */
+  readonly positions: readonly number[];
+  readonly limits: Required<JointLimits>;
+  /**
+   * The joint followed, for a mimic joint.  Removing that joint makes this
+   * one independent.
+   */
+  readonly mimic?: Required<JointMimic>;
+}
+
+/**
+ * Callback for {@link TFTree.onJointLimit}: `requested` was clamped to the
+ * position of `joint`.
+ */
+export type JointLimitCallback = (joint: Joint, requested: number) => void;
+
+/** One link of a {@link KinematicChain}: a frame below the chain's base. */
+export interface ChainLink {
+  readonly frameId: string;
+  /** The frame's current transform relative to the previous frame. */
+  readonly transform: Transform;
+  /** The joint driving the frame, if any. */
+  readonly joint?: Joint;
+}
+
+/**
+ * The serial chain between two frames, as returned by
+ * {@link TFTree.getChain}: the input of a controller or an IK solver.
+ */
+export interface KinematicChain {
+  /** Frames from the base to the tip, both included. */
+  readonly frameIds: string[];
+  /** One link per frame after the base, from the base outwards. */
+  readonly links: ChainLink[];
+  /** The joints of {@link links}, from the base outwards. */
+  readonly joints: Joint[];
+}
+
+/** Options for {@link TFTree.solveIk}. */
+export interface IkOptions {
+  /** @default 100 */
+  maxIterations?: number;
+  /**
+   * Distance from the target position at which the solver stops, in tree
+   * units.
+   *
+   * @default 1e-4
+   */
+  positionTolerance?: number;
+  /**
+   * Angle from the target orientation at which the solver stops, in
+   * radians.
+   *
+   * @default 1e-3
+   */
+  orientationTolerance?: number;
+  /**
+   * Damping factor λ of the damped least-squares step: larger values are
+   * slower to converge but better behaved near singularities.
+   *
+   * @default 0.05
+   */
+  damping?: number;
+  /**
+   * Reach the target position only, ignoring its orientation, e.g. to drag
+   * an end effector around.
+   *
+   * @default false
+   */
+  positionOnly?: boolean;
+  /**
+   * Move the joints to the solution when the solver succeeds.
+   *
+   * @default false
+   */
+  apply?: boolean;
+}
+
+/**
+ * Why {@link TFTree.solveIk} failed:
+ *
+ * - `"max-iterations"` – the target was not reached within `maxIterations`.
+ * - `"stalled"` – no step reduces the error any further, e.g. because the
+ *   target is out of reach or beyond the joint limits.
+ */
+export type IkFailureReason = "max-iterations" | "stalled";
+
+/** Result of {@link TFTree.solveIk}. */
+export interface IkResult {
+  readonly success: boolean;
+  /** Set when `success` is `false`. */
+  readonly reason?: IkFailureReason;
+  /**
+   * Positions of the chain's joints, by joint name, from the base outwards:
+   * the solution, or the closest configuration found.
+   */
+  readonly positions: Record<string, number>;
+  /** Number of iterations performed. */
+  readonly iterations: number;
+  /** Remaining distance from the target position, in tree units. */
+  readonly positionError: number;
+  /** Remaining angle from the target orientation, in radians. */
+  readonly orientationError: number;
+}
+
+/**
+ * Denavit–Hartenberg convention of {@link TFTree.addDhChain}:
+ *
+ * - `"standard"` – link `i` is `Rz(theta) · Tz(d) · Tx(a) · Rx(alpha)`
+ *   relative to link `i - 1`, with joint `i` moving about `z` of link
+ *   `i - 1`.
+ * - `"modified"` – Craig's convention: link `i` is
+ *   `Rx(alpha) · Tx(a) · Rz(theta) · Tz(d)` relative to link `i - 1`, with
+ *   joint `i` moving about `z` of link `i` itself.
+ */
+export type DhConvention = "standard" | "modified";
+
+/** One link of a chain built with {@link TFTree.addDhChain}. */
+export interface DhLink {
+  /** Id of the frame created for the link. */
+  readonly frameId: string;
+  /** Link length, along `x`. */
+  readonly a: number;
+  /** Link twist about `x`, in radians. */
+  readonly alpha: number;
+  /** Link offset along `z`. */
+  readonly d: number;
+  /** Joint angle about `z`, in radians. */
+  readonly theta: number;
+  /**
+   * Register a joint driving the link frame about (revolute) or along
+   * (prismatic) its `z` axis, with position zero at `theta` and `d` as
+   * given, and unbounded unless `limits` is given.  Only supported with the
+   * `"modified"` convention, where the joint axis is the link frame's own
+   * `z` axis.
+   */
+  readonly joint?: {
+    readonly name: string;
+    readonly type: "revolute" | "prismatic";
+    readonly limits?: JointLimits;
+  };
+}
+
+/** Options for {@link TFTree.addDhChain}. */
+export interface DhChainOptions {
+  /** @default "standard" */
+  readonly convention?: DhConvention;
+}
+
+/** How a frame was calibrated, for {@link TFTree.setCalibration}. */
+export interface CalibrationMetadata {
+  /** The calibration method, e.g. `"hand-eye"` or `"target-based"`. */
+  readonly method: string;
+  /**
+   * When the calibration was performed, in milliseconds of the tree's clock
+   * (see {@link TFTree.setClock}).
+   *
+   * @default the current time
+   */
+  readonly date?: number;
+  /** RMS residual reported by the calibration method, in its own units. */
+  readonly residualRms?: number;
+}
+
+/** A calibrated frame, as listed by {@link TFTree.getCalibrations}. */
+export interface Calibration {
+  readonly frameId: string;
+  /** The calibrated transform of the frame relative to its parent. */
+  readonly transform: Transform;
+  readonly method: string;
+  readonly date: number;
+  readonly residualRms?: number;
+}
+
+/** Options for {@link TFTree.auditCalibrations}. */
+export interface CalibrationAuditOptions {
+  /** Age in milliseconds past which a calibration is reported `"stale"`. */
+  readonly maxAge?: number;
+  /** Residual past which a calibration is reported `"high-residual"`. */
+  readonly maxResidual?: number;
+}
+
+/**
+ * Kind of a {@link CalibrationIssue}:
+ *
+ * - `"unlocked"` – the frame was unlocked since it was calibrated.
+ * - `"modified"` – the frame's transform differs from the calibrated one.
+ * - `"stale"` – the calibration is older than `maxAge`.
+ * - `"high-residual"` – the calibration's residual exceeds `maxResidual`.
+ */
+export type CalibrationIssueKind = "unlocked" | "modified" | "stale" | "high-residual";
+
+/** A problem with a calibrated frame found by {@link TFTree.auditCalibrations}. */
+export interface CalibrationIssue {
+  readonly kind: CalibrationIssueKind;
+  readonly frameId: string;
+  /** Human-readable description. */
+  readonly message: string;
+}
+
+/**
+ * Camera mounting of a hand-eye calibration ({@link TFTree.solveHandEye}):
+ *
+ * - `"eye-in-hand"` – the camera rides on the robot's hand.
+ * - `"eye-to-hand"` – the camera is fixed and watches the hand.
+ */
+export type HandEyeSetup = "eye-in-hand" | "eye-to-hand";
+
+/** Options for {@link TFTree.solveHandEye}. */
+export interface HandEyeOptions {
+  /** @default "eye-in-hand" */
+  readonly setup?: HandEyeSetup;
+  /**
+   * Register the calibrated transform as frame `frameId` under `parentId`
+   * (the hand, or the robot base with `"eye-to-hand"`), with method
+   * `"hand-eye"` and the translation residual (see
+   * {@link TFTree.setCalibration}).
+   */
+  readonly install?: { readonly frameId: string; readonly parentId: string };
+}
+
+/** Result of {@link TFTree.solveHandEye}. */
+export interface HandEyeResult {
+  /** The camera relative to the hand, or to the robot base with `"eye-to-hand"`. */
+  readonly transform: Transform;
+  /** RMS rotation error of `AX = XB` over all motions, in radians. */
+  readonly rotationResidual: number;
+  /** RMS translation error of `AX = XB` over all motions, in tree units. */
+  readonly translationResidual: number;
+}
+
+/**
+ * Geodetic coordinates of a frame added with {@link TFTree.addGeoAnchor}, on
+ * the WGS84 ellipsoid.
+ */
+export interface GeoAnchor {
+  /** Degrees north of the equator. */
+  readonly latitude: number;
+  /** Degrees east of Greenwich. */
+  readonly longitude: number;
+  /** Meters above the ellipsoid. */
+  readonly altitude: number;
+  /** Degrees counterclockwise from east of the frame's `x` axis. */
+  readonly heading: number;
+}
+
+/**
+ * Pinhole camera intrinsics attached to a frame with
+ * {@link TFTree.setCameraIntrinsics}, in pixels.  The frame is taken to be
+ * an optical frame: `z` forward, `x` right, `y` down.
+ */
+export interface CameraIntrinsics {
+  readonly fx: number;
+  readonly fy: number;
+  readonly cx: number;
+  readonly cy: number;
+  /** Image width; when set, points projecting outside it are invalid. */
+  readonly width?: number;
+  /** Image height; when set, points projecting outside it are invalid. */
+  readonly height?: number;
+}
+
+/** Result of {@link TFTree.projectPoints}. */
+export interface ProjectedPoints<T extends FloatArray = Float64Array> {
+  /** Packed `u, v` pixel coordinates; `NaN` for invalid points. */
+  readonly pixels: T;
+  /** `1` where the point lies in front of the camera (and in the image). */
+  readonly valid: Uint8Array;
+}
+
+/** A frame of one of the trees of a {@link TFForest}. */
+export interface ForestFrame {
+  /** Name the tree was registered under. */
+  readonly tree: string;
+  readonly frame: string;
+}
+
+/** A link between frames of two trees, added with {@link TFForest.addBridge}. */
+export interface ForestBridge {
+  readonly a: ForestFrame;
+  readonly b: ForestFrame;
+  /** Pose of `b` relative to `a`. */
+  readonly transform: Transform;
+}
+
+/**
+ * Layout of an interleaved point cloud for {@link TFTree.transformPointCloud},
+ * e.g. `{ stride: 4 }` for XYZI or `{ stride: 6 }` for XYZRGB.
+ */
+export interface PointCloudOptions {
+  /** Number of elements per point.  Defaults to `3`. */
+  stride?: number;
+  /** Index of `x` within a point; `y` and `z` follow it.  Defaults to `0`. */
+  offset?: number;
+  /**
+   * Destination buffer with the same layout; the other channels are copied
+   * over.  Defaults to transforming the input in place.
+   */
+  out?: FloatArray;
+}
 
 /**
  * Public API of the transform-tree engine.
@@ -3,6 +3,7 @@ import { BufferedTFTree } from "../src/BufferedTFTree.js";
 import { Transform } from "../src/math/Transform.js";
 import { Vec3 } from "../src/math/Vec3.js";
 import { Quaternion } from "../src/math/Quaternion.js";
+import { InvalidTransformError } from "../src/InvalidTransformError.js";
 
 // ── helpers ──────────────────────────────────────────────────────────────────
 
@@ -112,6 +113,24 @@ describe("BufferedTFTree", () => {
     expect(p.z).toBeCloseTo(0, 4);
   });
 
+  it("setRotation() records a rotation and keeps the translation", () => {
+    tf.addFrame("world");
+    tf.addFrame("imu", "world", translate(0, 0, 1));
+    const q90 = Quaternion.fromAxisAngle(new Vec3(0, 0, 1), Math.PI / 2);
+    tf.setRotation("imu", 0, 0, 0, 1, T0);
+    expect(tf.setRotation("imu", q90.x, q90.y, q90.z, q90.w, T0 + 100)).toBe(true);
+
+    expect(tf.getLocalTransform("imu").equals(new Transform(new Vec3(0, 0, 1), q90))).toBe(true);
+    const t = tf.getTransformAt("world", "imu", T0 + 50);
+    expect(t.translation.equals(new Vec3(0, 0, 1))).toBe(true);
+    expect(t.rotation.angleTo(Quaternion.identity())).toBeCloseTo(Math.PI / 4, 4);
+    expect(() => tf.setRotation("ghost", 0, 0, 0, 1, T0)).toThrow(/not found/);
+    expect(() => tf.setRotation("imu", 0, 0, 0, 0, T0 + 200)).toThrowError(InvalidTransformError);
+    expect(() => tf.setRotation("imu", Infinity, 0, 0, 1, T0 + 200)).toThrowError(
+      InvalidTransformError,
+    );
+  });
+
   // ── getTransformAt – static frame fallback ────────────────────────────────────
 
   it("falls back to the static transform for frames with no history", () => {
@@ -197,6 +216,64 @@ describe("BufferedTFTree", () => {
     expect(t.transformPoint(Vec3.zero()).equals(new Vec3(2, 0, 0))).toBe(true);
   });
 
+  // ── clear ─────────────────────────────────────────────────────────────────────
+
+  it("clear() also drops all frame buffers", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.setTransform("robot", translate(1, 0, 0), T0);
+    tf.clear();
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(2, 0, 0));
+    // No history survives, so the static transform is used at any timestamp.
+    const t = tf.getTransformAt("world", "robot", 0);
+    expect(t.transformPoint(Vec3.zero()).equals(new Vec3(2, 0, 0))).toBe(true);
+  });
+
+  // ── transactions ──────────────────────────────────────────────────────────────
+
+  it("rollback() also restores frame buffers", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.setTransform("robot", translate(0, 0, 0), T0);
+    tf.setTransform("robot", translate(2, 0, 0), T0 + 100);
+
+    tf.begin();
+    tf.setTransform("robot", translate(100, 0, 0), T0 + 200);
+    tf.rollback();
+
+    const t = tf.getTransformAt("world", "robot", T0 + 200);
+    expect(t.transformPoint(Vec3.zero()).equals(new Vec3(2, 0, 0))).toBe(true);
+    expect(tf.getTransform("world", "robot").translation.equals(new Vec3(2, 0, 0))).toBe(true);
+  });
+
+  // ── renameFrame ───────────────────────────────────────────────────────────────
+
+  it("renameFrame() carries the frame buffer over to the new id", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.setTransform("robot", translate(0, 0, 0), T0);
+    tf.setTransform("robot", translate(4, 0, 0), T0 + 100);
+    tf.renameFrame("robot", "robot1");
+    const t = tf.getTransformAt("world", "robot1", T0 + 50);
+    expect(t.transformPoint(Vec3.zero()).equals(new Vec3(2, 0, 0))).toBe(true);
+  });
+
+  it("keys frame buffers by normalized ids", () => {
+    tf.addFrame("world");
+    tf.setRosNaming({ tfPrefix: "robot1" });
+    tf.addFrame("base", "/world");
+    tf.setTransform("base", translate(0, 0, 0), T0);
+    tf.setTransform("/robot1/base", translate(4, 0, 0), T0 + 100);
+    tf.renameFrame("base", "chassis");
+    expect(tf.getTransformAt("world", "chassis", T0 + 50).translation.x).toBeCloseTo(2, 6);
+
+    // Subtree removal drops the buffer too.
+    tf.removeSubtree("/robot1/chassis");
+    tf.addFrame("chassis", "/world", translate(3, 0, 0));
+    expect(tf.getTransformAt("world", "chassis", T0).translation.x).toBeCloseTo(3, 6);
+  });
+
   // ── multi-hop path ────────────────────────────────────────────────────────────
 
   it("resolves a multi-hop path using historical transforms at each edge", () => {
@@ -214,4 +291,119 @@ describe("BufferedTFTree", () => {
     const t = tf.getTransformAt("world", "camera", T0 + 50);
     expect(t.transformPoint(Vec3.zero()).equals(new Vec3(5, 0, 1))).toBe(true);
   });
+
+  // ── update sources ────────────────────────────────────────────────────────────
+
+  it("corrects source timestamps by clock offset and latency", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    // The robot's clock runs 500 ms behind the tree's and stamps 20 ms late.
+    tf.setUpdateSource("robot-feed", { clockOffset: 500, latency: 20 });
+    tf.setTransform("robot", translate(0, 0, 0), T0 - 500 + 20, { source: "robot-feed" });
+    tf.setTransform("robot", translate(4, 0, 0), T0 - 400 + 20, { source: "robot-feed" });
+
+    const t = tf.getTransformAt("world", "robot", T0 + 50);
+    expect(t.transformPoint(Vec3.zero()).equals(new Vec3(2, 0, 0))).toBe(true);
+    expect(tf.getLastSource("robot")).toBe("robot-feed");
+    expect(tf.getUpdateSources()).toEqual({
+      "robot-feed": { clockOffset: 500, latency: 20, priority: 0, estimateClockOffset: false },
+    });
+    expect(() => tf.setTransform("robot", translate(0, 0, 0), T0, { source: "ghost" })).toThrow(
+      /not registered/,
+    );
+  });
+
+  it("lets higher-priority sources hold frames against lower-priority ones", () => {
+    tf = new BufferedTFTree({ maxBufferDuration: 1_000 });
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.setUpdateSource("live", { priority: 2 });
+    tf.setUpdateSource("replay", { priority: 1 });
+
+    expect(tf.setTransform("robot", translate(1, 0, 0), T0, { source: "live" })).toBe(true);
+    expect(tf.setTransform("robot", translate(9, 0, 0), T0 + 10, { source: "replay" })).toBe(false);
+    expect(tf.getLocalTransform("robot").equals(translate(1, 0, 0))).toBe(true);
+
+    // Once the live feed has been silent for a whole buffer window, the replay takes over.
+    expect(tf.setTransform("robot", translate(9, 0, 0), T0 + 1_000, { source: "replay" })).toBe(
+      true,
+    );
+    expect(tf.getLastSource("robot")).toBe("replay");
+
+    // Removing a source releases its frames straight away.
+    tf.setTransform("robot", translate(1, 0, 0), T0 + 1_010, { source: "live" });
+    tf.setUpdateSource("live", undefined);
+    expect(tf.setTransform("robot", translate(8, 0, 0), T0 + 1_020, { source: "replay" })).toBe(
+      true,
+    );
+  });
+
+  it("estimates a source's clock offset from receipt times", () => {
+    tf = new BufferedTFTree({ maxBufferDuration: 1_000 });
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.setUpdateSource("remote", { estimateClockOffset: true });
+    // The remote clock runs 500 ms behind; messages take 10 to 30 ms to arrive.
+    const receive = (x: number, stamp: number, delay: number) =>
+      tf.setTransform("robot", translate(x, 0, 0), stamp, {
+        source: "remote",
+        receivedAt: stamp + 500 + delay,
+      });
+
+    receive(0, T0 - 500, 30);
+    expect(tf.getUpdateSources()["remote"].clockOffset).toBe(530);
+    receive(4, T0 - 400, 10);
+    receive(8, T0 - 300, 20);
+    expect(tf.getUpdateSources()["remote"].clockOffset).toBe(510);
+    const t = tf.getTransformAt("world", "robot", T0 + 210);
+    expect(t.transformPoint(Vec3.zero()).equals(new Vec3(8, 0, 0))).toBe(true);
+
+    // Samples older than the buffer window no longer count.
+    receive(8, T0 + 680, 40);
+    expect(tf.getUpdateSources()["remote"].clockOffset).toBe(520);
+  });
+
+  it("keeps the clock offset estimate of ignored, failed and rolled-back updates", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.addFrame("arm", "robot");
+    tf.setUpdateSource("live", { priority: 1 });
+    tf.setUpdateSource("remote", { estimateClockOffset: true });
+    const offset = () => tf.getUpdateSources()["remote"].clockOffset;
+    tf.setTransform("robot", translate(1, 0, 0), T0, { source: "live" });
+    tf.setTransform("arm", translate(1, 0, 0), T0, { source: "remote", receivedAt: T0 + 50 });
+
+    const ignored = tf.setTransform("robot", translate(2, 0, 0), T0, {
+      source: "remote",
+      receivedAt: T0 + 10,
+    });
+    expect(ignored).toBe(false);
+    expect(offset()).toBe(50);
+
+    tf.lockFrame("arm");
+    expect(() =>
+      tf.setTransform("arm", translate(2, 0, 0), T0, { source: "remote", receivedAt: T0 + 10 }),
+    ).toThrow();
+    expect(offset()).toBe(50);
+    tf.unlockFrame("arm");
+
+    tf.begin();
+    tf.setTransform("arm", translate(2, 0, 0), T0, { source: "remote", receivedAt: T0 + 10 });
+    expect(offset()).toBe(10);
+    tf.rollback();
+    expect(offset()).toBe(50);
+    tf.setTransform("arm", translate(3, 0, 0), T0, { source: "remote", receivedAt: T0 + 30 });
+    expect(offset()).toBe(30);
+  });
+
+  it("defaults receipt times to the tree's clock", () => {
+    let now = T0;
+    tf.setClock(() => now);
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.setUpdateSource("remote", { estimateClockOffset: true, latency: 5 });
+    now = T0 + 25;
+    tf.setTransform("robot", translate(1, 0, 0), T0 - 1_000, { source: "remote" });
+    expect(tf.getUpdateSources()["remote"]).toMatchObject({ clockOffset: 1_025, latency: 5 });
+  });
 });
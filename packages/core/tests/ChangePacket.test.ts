@@ -0,0 +1,49 @@
+import { describe, it, expect } from "vitest";
+import { encodeChangePacket, decodeChangePacket } from "../src/ChangePacket.js";
+import { TFTree } from "../src/TFTree.js";
+import { Transform } from "../src/math/Transform.js";
+import { Vec3 } from "../src/math/Vec3.js";
+import { Quaternion } from "../src/math/Quaternion.js";
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+describe("ChangePacket", () => {
+  it("round-trips ids and transforms through a single buffer", () => {
+    const q = Quaternion.fromAxisAngle(new Vec3(0, 0, 1), Math.PI / 2);
+    const buffer = encodeChangePacket(7, [
+      ["robot", new Transform(new Vec3(1, 2, 3), q)],
+      ["bras/épaule", Transform.identity()],
+    ]);
+    const { seq, frameIds, data } = decodeChangePacket(buffer);
+    expect(seq).toBe(7);
+    expect(frameIds).toEqual(["robot", "bras/épaule"]);
+    expect(Array.from(data.subarray(0, 3))).toEqual([1, 2, 3]);
+    expect(Array.from(data.subarray(3, 7))).toEqual(q.toArray());
+    expect(data[13]).toBe(1); // identity rotation: w = 1
+  });
+
+  it("encodes an empty batch", () => {
+    expect(decodeChangePacket(encodeChangePacket(0, []))).toEqual({
+      seq: 0,
+      frameIds: [],
+      data: new Float64Array(0),
+    });
+  });
+
+  it("TFTree.packChanges() encodes world transforms of a change batch", () => {
+    const tf = new TFTree();
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", new Transform(new Vec3(1, 0, 0)));
+    tf.addFrame("camera", "robot", new Transform(new Vec3(0, 0, 1)));
+
+    const packets: ArrayBuffer[] = [];
+    tf.onAnyChange((ids, seq) => packets.push(tf.packChanges(ids, seq)));
+    tf.updateTransform("robot", new Transform(new Vec3(2, 0, 0)));
+
+    const { seq, frameIds, data } = decodeChangePacket(packets[0]);
+    expect(seq).toBe(1);
+    expect(frameIds).toEqual(["robot", "camera"]);
+    expect(Array.from(data.subarray(7, 10))).toEqual([2, 0, 1]);
+    expect(() => tf.packChanges(["ghost"])).toThrow(/not found/);
+  });
+});
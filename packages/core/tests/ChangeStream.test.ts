@@ -0,0 +1,69 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import { TFTree } from "../src/TFTree.js";
+import { Transform } from "../src/math/Transform.js";
+import { Vec3 } from "../src/math/Vec3.js";
+
+// ── helpers ──────────────────────────────────────────────────────────────────
+
+function translate(x: number, y: number, z: number): Transform {
+  return new Transform(new Vec3(x, y, z));
+}
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("ChangeStream", () => {
+  let tf: TFTree;
+
+  beforeEach(() => {
+    tf = new TFTree();
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.addFrame("camera", "robot");
+  });
+
+  it("yields queued batches in order", async () => {
+    const stream = tf.changes();
+    tf.updateTransform("camera", translate(1, 0, 0));
+    tf.updateTransform("robot", translate(1, 0, 0));
+
+    expect(await stream.next()).toEqual({ value: ["camera"], done: false });
+    expect(await stream.next()).toEqual({ value: ["robot", "camera"], done: false });
+  });
+
+  it("resolves a pending next() when the next change arrives", async () => {
+    const stream = tf.changes();
+    const pending = stream.next();
+    tf.updateTransform("robot", translate(1, 0, 0));
+    expect(await pending).toEqual({ value: ["robot", "camera"], done: false });
+  });
+
+  it("merges batches beyond the high-water mark", async () => {
+    const stream = tf.changes({ highWaterMark: 1 });
+    tf.updateTransform("camera", translate(1, 0, 0));
+    tf.updateTransform("robot", translate(1, 0, 0));
+    tf.updateTransform("camera", translate(2, 0, 0));
+    expect(stream.size).toBe(1);
+    expect(await stream.next()).toEqual({ value: ["camera", "robot"], done: false });
+  });
+
+  it("supports scoping like onAnyChange()", async () => {
+    const stream = tf.changes({ subtreeRoot: "camera" });
+    tf.updateTransform("robot", translate(1, 0, 0));
+    expect(await stream.next()).toEqual({ value: ["camera"], done: false });
+  });
+
+  it("works with for await and unsubscribes on break", async () => {
+    const stream = tf.changes();
+    tf.updateTransform("camera", translate(1, 0, 0));
+    const seen: string[][] = [];
+    for await (const ids of stream) {
+      seen.push(ids);
+      break;
+    }
+    expect(seen).toEqual([["camera"]]);
+
+    tf.updateTransform("camera", translate(2, 0, 0));
+    expect(stream.size).toBe(0);
+    expect(await stream.next()).toEqual({ value: undefined, done: true });
+  });
+});
@@ -51,6 +51,16 @@ describe("Quaternion", () => {
     expect(q90.multiply(q90).equals(q180)).toBe(true);
   });
 
+  it("angleTo() returns the rotation angle between two orientations", () => {
+    const q30 = Quaternion.fromAxisAngle(new Vec3(0, 0, 1), Math.PI / 6);
+    const q90 = Quaternion.fromAxisAngle(new Vec3(0, 0, 1), Math.PI / 2);
+    expect(q30.angleTo(q90)).toBeCloseTo(Math.PI / 3, 5);
+    expect(q90.angleTo(q90)).toBeCloseTo(0, 5);
+    // q and -q are the same orientation.
+    const negated = new Quaternion(-q90.x, -q90.y, -q90.z, -q90.w);
+    expect(q90.angleTo(negated)).toBeCloseTo(0, 5);
+  });
+
   it("invert() of identity is identity", () => {
     expect(Quaternion.identity().invert().equals(Quaternion.identity())).toBe(true);
   });
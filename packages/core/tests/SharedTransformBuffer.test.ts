@@ -0,0 +1,55 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import { SharedTransformBuffer } from "../src/SharedTransformBuffer.js";
+import { TFTree } from "../src/TFTree.js";
+import { Transform } from "../src/math/Transform.js";
+import { Vec3 } from "../src/math/Vec3.js";
+import { TFError } from "../src/TFError.js";
+import { FrameNotFoundError } from "../src/FrameNotFoundError.js";
+
+// ── helpers ──────────────────────────────────────────────────────────────────
+
+function translate(x: number, y: number, z: number): Transform {
+  return new Transform(new Vec3(x, y, z));
+}
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("SharedTransformBuffer", () => {
+  let tf: TFTree;
+  let shared: SharedTransformBuffer;
+
+  beforeEach(() => {
+    tf = new TFTree();
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.addFrame("camera", "robot", translate(0, 0, 1));
+    shared = new SharedTransformBuffer(["robot", "camera"]);
+  });
+
+  it("only exposes transforms once they are swapped in", () => {
+    expect(shared.buffer.byteLength).toBe(SharedTransformBuffer.byteLength(2));
+    shared.write(tf, "world");
+    expect(shared.generation()).toBe(0);
+    expect(Array.from(shared.read())).toEqual(new Array(14).fill(0));
+
+    expect(shared.swap()).toBe(1);
+    expect(shared.getTransform("camera").equals(translate(1, 0, 1))).toBe(true);
+
+    tf.updateTransform("robot", translate(2, 0, 0));
+    shared.write(tf, "world");
+    expect(shared.getTransform("camera").equals(translate(1, 0, 1))).toBe(true);
+    shared.swap();
+    const data = shared.read(new Float32Array(14));
+    expect(Array.from(data.subarray(7, 10))).toEqual([2, 0, 1]);
+  });
+
+  it("shares published state with readers attached to the same memory", () => {
+    const reader = new SharedTransformBuffer(shared.frameIds, shared.buffer);
+    shared.write(tf, "world");
+    shared.swap();
+    expect(reader.generation()).toBe(1);
+    expect(reader.getTransform("robot").equals(translate(1, 0, 0))).toBe(true);
+    expect(() => reader.getTransform("world")).toThrowError(FrameNotFoundError);
+    expect(() => new SharedTransformBuffer(["robot"], shared.buffer)).toThrowError(TFError);
+  });
+});
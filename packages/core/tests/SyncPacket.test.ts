@@ -0,0 +1,172 @@
+import { describe, it, expect } from "vitest";
+import { encodeSyncPacket, decodeSyncPacket } from "../src/SyncPacket.js";
+import { TFTree } from "../src/TFTree.js";
+import { Transform } from "../src/math/Transform.js";
+import { Vec3 } from "../src/math/Vec3.js";
+import { Quaternion } from "../src/math/Quaternion.js";
+import { TFError } from "../src/TFError.js";
+import { OutOfRangeError } from "../src/OutOfRangeError.js";
+import type { JournalOperation } from "../src/types.js";
+
+// ── helpers ──────────────────────────────────────────────────────────────────
+
+function translate(x: number, y: number, z: number): Transform {
+  return new Transform(new Vec3(x, y, z));
+}
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("SyncPacket", () => {
+  it("round-trips every operation with quantized transforms", () => {
+    const operations: JournalOperation[] = [
+      {
+        op: "add",
+        id: "world",
+        parentId: null,
+        transform: { translation: [0, 0, 0], rotation: [0, 0, 0, 1] },
+      },
+      {
+        op: "add",
+        id: "bras/épaule",
+        parentId: "world",
+        transform: { translation: [1.23456, -2, 0.5], rotation: [0, 0, 0.6, 0.8] },
+      },
+      { op: "update", id: "world", transform: { translation: [0, 0, 1], rotation: [0, 0, 0, 1] } },
+      { op: "rename", id: "bras/épaule", newId: "arm" },
+      { op: "remove", id: "arm" },
+      { op: "clear" },
+    ];
+    const { seq, operations: decoded } = decodeSyncPacket(encodeSyncPacket(42, operations));
+    expect(seq).toBe(42);
+    expect(decoded.map((op) => op.op)).toEqual(operations.map((op) => op.op));
+    expect(decoded[3]).toEqual({ op: "rename", id: "bras/épaule", newId: "arm" });
+    const arm = decoded[1] as Extract<JournalOperation, { op: "add" }>;
+    expect(arm.parentId).toBe("world");
+    arm.transform.translation.forEach((v, i) => {
+      expect(v).toBeCloseTo([1.2346, -2, 0.5][i], 10);
+    });
+    arm.transform.rotation.forEach((v, i) => {
+      expect(v).toBeCloseTo([0, 0, 0.6, 0.8][i], 4);
+    });
+  });
+
+  it("rejects invalid steps and translations too large to quantize", () => {
+    const update: JournalOperation = {
+      op: "update",
+      id: "far",
+      transform: { translation: [1e6, 0, 0], rotation: [0, 0, 0, 1] },
+    };
+    expect(() => encodeSyncPacket(0, [], 0)).toThrowError(TFError);
+    expect(() => encodeSyncPacket(0, [update])).toThrowError(OutOfRangeError);
+    expect(decodeSyncPacket(encodeSyncPacket(0, [update], 0.01)).operations).toHaveLength(1);
+  });
+
+  it("rejects ids too long for their Uint16 length prefix", () => {
+    const remove = (length: number): JournalOperation => ({ op: "remove", id: "x".repeat(length) });
+    const [decoded] = decodeSyncPacket(encodeSyncPacket(0, [remove(0xffff)])).operations;
+    expect(decoded).toEqual(remove(0xffff));
+
+    let err: unknown;
+    try {
+      encodeSyncPacket(0, [remove(0x10000)]);
+    } catch (e) {
+      err = e;
+    }
+    expect(err).toBeInstanceOf(TFError);
+    expect(err).toMatchObject({
+      code: "INVALID_ARGUMENT",
+      context: { operation: "encodeSyncPacket" },
+    });
+  });
+
+  it("rejects truncated and malformed packets with INVALID_ARGUMENT", () => {
+    const decodeError = (buffer: ArrayBuffer) => {
+      try {
+        decodeSyncPacket(buffer);
+      } catch (e) {
+        return e;
+      }
+      return undefined;
+    };
+    const invalid = { code: "INVALID_ARGUMENT", context: { operation: "decodeSyncPacket" } };
+    expect(decodeError(new ArrayBuffer(8))).toMatchObject(invalid);
+    expect(decodeError(new ArrayBuffer(8))).toBeInstanceOf(TFError);
+
+    const packet = encodeSyncPacket(0, [{ op: "remove", id: "a" }]);
+    const opcode = new Uint8Array(packet.slice(0));
+    opcode[24 + 2 + 2] = 9; // after the one-character id "a"
+    expect(decodeError(opcode.buffer)).toMatchObject(invalid);
+    const index = new DataView(packet.slice(0));
+    index.setUint32(24 + 2 + 2 + 1, 5, true);
+    expect(decodeError(index.buffer)).toMatchObject(invalid);
+    expect(String(decodeError(index.buffer))).toMatch(/unknown id #5/);
+
+    const update = encodeSyncPacket(0, [
+      { op: "update", id: "a", transform: { translation: [0, 0, 0], rotation: [0, 0, 0, 1] } },
+    ]);
+    new DataView(update).setInt16(update.byteLength - 2, 0, true); // w of an identity rotation
+    expect(decodeError(update)).toMatchObject(invalid);
+    expect(String(decodeError(update))).toMatch(/zero-norm rotation/);
+  });
+
+  it("replicates a tree with TFTree.encodeSyncPacket() / applySyncPacket()", () => {
+    const source = new TFTree();
+    expect(() => source.encodeSyncPacket()).toThrow(/enableJournal/);
+    source.enableJournal();
+    source.addFrame("map");
+    source.addFrame("robot", "map", translate(1, 0, 0));
+    const replica = new TFTree();
+    let acked = replica.applySyncPacket(source.encodeSyncPacket());
+    expect(acked).toBe(2);
+
+    const yaw = Quaternion.fromAxisAngle(new Vec3(0, 0, 1), 0.3);
+    for (let i = 1; i <= 100; i++) {
+      source.updateTransform("robot", new Transform(new Vec3(i * 0.01, 0, 0), yaw));
+    }
+    source.addFrame("camera", "robot", translate(0, 0, 1));
+    const delta = source.encodeSyncPacket(acked);
+    // The 100 updates collapse into one.
+    expect(decodeSyncPacket(delta).operations.map((op) => op.op)).toEqual(["update", "add"]);
+    acked = replica.applySyncPacket(delta);
+    expect(acked).toBe(103);
+
+    const expected = source.getTransform("map", "camera");
+    expect(replica.getTransform("map", "camera").equals(expected, 1e-4)).toBe(true);
+    expect(decodeSyncPacket(source.encodeSyncPacket(acked)).operations).toEqual([]);
+  });
+
+  it("only sends changes of the frames passing the export filter", () => {
+    const client = new TFTree();
+    client.enableJournal();
+    client.addFrame("map");
+    client.addFrame("client/robot", "map");
+    client.addFrame("client/arm", "client/robot");
+    client.setExportFilter({ prefixes: ["client/"] });
+    client.updateTransform("map", translate(1, 0, 0));
+    client.updateTransform("client/arm", translate(0, 1, 0));
+    client.renameFrame("client/arm", "client/gripper");
+    client.clear();
+
+    const { operations } = decodeSyncPacket(client.encodeSyncPacket());
+    expect(operations.map((op) => [op.op, op.op === "clear" ? null : op.id])).toEqual([
+      ["add", "client/robot"],
+      ["add", "client/arm"],
+      ["update", "client/arm"],
+      ["rename", "client/arm"],
+    ]);
+  });
+
+  it("applies packets atomically", () => {
+    const source = new TFTree();
+    source.enableJournal();
+    source.addFrame("map");
+    source.addFrame("robot", "map");
+    const packet = source.encodeSyncPacket(1);
+    const replica = new TFTree();
+    expect(() => replica.applySyncPacket(packet)).toThrow(/not found/);
+    expect(() => replica.applySyncPacket(packet, { validateOnly: true })).toThrow(/not found/);
+    replica.addFrame("map");
+    replica.applySyncPacket(packet, { validateOnly: true });
+    expect(replica.hasFrame("robot")).toBe(false);
+  });
+});
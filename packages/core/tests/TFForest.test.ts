@@ -0,0 +1,107 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import { TFForest } from "../src/TFForest.js";
+import { TFTree } from "../src/TFTree.js";
+import { Transform } from "../src/math/Transform.js";
+import { Vec3 } from "../src/math/Vec3.js";
+import { Quaternion } from "../src/math/Quaternion.js";
+import { TFError } from "../src/TFError.js";
+import { FrameNotFoundError } from "../src/FrameNotFoundError.js";
+import { DisconnectedFramesError } from "../src/DisconnectedFramesError.js";
+
+// ── helpers ──────────────────────────────────────────────────────────────────
+
+function translate(x: number, y: number, z: number): Transform {
+  return new Transform(new Vec3(x, y, z));
+}
+
+function originOf(transform: Transform): Vec3 {
+  return transform.transformPoint(Vec3.zero());
+}
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("TFForest", () => {
+  let forest: TFForest;
+
+  beforeEach(() => {
+    forest = new TFForest();
+    const site = forest.addTree("site");
+    site.addFrame("world");
+    const robot1 = forest.addTree("robot1");
+    robot1.addFrame("map");
+    robot1.addFrame("base", "map", translate(1, 0, 0));
+    const robot2 = new TFTree();
+    robot2.addFrame("map");
+    robot2.addFrame("camera", "map", translate(0, 0, 2));
+    forest.addTree("robot2", robot2);
+  });
+
+  it("manages named trees", () => {
+    expect(forest.treeNames()).toEqual(["site", "robot1", "robot2"]);
+    expect(forest.getTree("robot1").hasFrame("base")).toBe(true);
+    expect(forest.hasTree("robot3")).toBe(false);
+    expect(() => forest.addTree("site")).toThrowError(TFError);
+    expect(() => forest.getTree("robot3")).toThrow(/not registered/);
+  });
+
+  it("delegates queries within one tree", () => {
+    const map = { tree: "robot1", frame: "map" };
+    const t = forest.getTransform(map, { tree: "robot1", frame: "base" });
+    expect(originOf(t).equals(new Vec3(1, 0, 0))).toBe(true);
+  });
+
+  it("routes getTransform() across a chain of bridges", () => {
+    forest.addBridge(
+      { tree: "site", frame: "world" },
+      { tree: "robot1", frame: "map" },
+      translate(10, 0, 0),
+    );
+    const yaw90 = Quaternion.fromAxisAngle(new Vec3(0, 0, 1), Math.PI / 2);
+    // robot2's map is described relative to the site, bridged from its side.
+    forest.addBridge(
+      { tree: "robot2", frame: "map" },
+      { tree: "site", frame: "world" },
+      new Transform(new Vec3(0, -20, 0), yaw90).invert(),
+    );
+
+    const camera = { tree: "robot2", frame: "camera" };
+    const inWorld = forest.getTransform({ tree: "site", frame: "world" }, camera);
+    expect(originOf(inWorld).equals(new Vec3(0, -20, 2), 1e-4)).toBe(true);
+
+    // robot1's base sits at (11, 0, 0) in the world, i.e. (20, -11, 0) in robot2's map.
+    const base = { tree: "robot1", frame: "base" };
+    const baseInCamera = forest.getTransform(camera, base);
+    expect(originOf(baseInCamera).equals(new Vec3(20, -11, -2), 1e-4)).toBe(true);
+    const back = forest.getTransform(base, camera);
+    expect(back.compose(baseInCamera).equals(Transform.identity(), 1e-4)).toBe(true);
+  });
+
+  it("throws when no bridges link the trees", () => {
+    forest.addBridge({ tree: "site", frame: "world" }, { tree: "robot1", frame: "map" });
+    expect(forest.areTreesLinked("robot1", "site")).toBe(true);
+    expect(forest.areTreesLinked("robot1", "robot2")).toBe(false);
+    expect(() =>
+      forest.getTransform({ tree: "robot1", frame: "map" }, { tree: "robot2", frame: "map" }),
+    ).toThrowError(DisconnectedFramesError);
+  });
+
+  it("validates bridge endpoints", () => {
+    expect(() =>
+      forest.addBridge({ tree: "site", frame: "ghost" }, { tree: "robot1", frame: "map" }),
+    ).toThrowError(FrameNotFoundError);
+    expect(() =>
+      forest.addBridge({ tree: "robot1", frame: "map" }, { tree: "robot1", frame: "base" }),
+    ).toThrow(/two different trees/);
+  });
+
+  it("removes bridges with removeBridge() and removeTree()", () => {
+    forest.addBridge({ tree: "site", frame: "world" }, { tree: "robot1", frame: "map" });
+    forest.addBridge({ tree: "robot2", frame: "map" }, { tree: "site", frame: "world" });
+    expect(forest.removeBridge("robot1", "site")).toBe(1);
+    expect(forest.areTreesLinked("robot1", "site")).toBe(false);
+
+    forest.removeTree("site");
+    expect(forest.bridges()).toEqual([]);
+    expect(forest.treeNames()).toEqual(["robot1", "robot2"]);
+  });
+});
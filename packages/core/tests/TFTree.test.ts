@@ -4,6 +4,18 @@ import { Transform } from "../src/math/Transform.js";
 import { Vec3 } from "../src/math/Vec3.js";
 import { Quaternion } from "../src/math/Quaternion.js";
 import { CycleDetectedError } from "../src/CycleDetectedError.js";
+import { FrameLockedError } from "../src/FrameLockedError.js";
+import { FrameNotFoundError } from "../src/FrameNotFoundError.js";
+import { TFError } from "../src/TFError.js";
+import { InvalidTransformError } from "../src/InvalidTransformError.js";
+import { InvalidFrameIdError } from "../src/InvalidFrameIdError.js";
+import { QuotaExceededError } from "../src/QuotaExceededError.js";
+import { DuplicateFrameError } from "../src/DuplicateFrameError.js";
+import { DisconnectedFramesError } from "../src/DisconnectedFramesError.js";
+import { UnauthorizedSourceError } from "../src/UnauthorizedSourceError.js";
+import { ReadOnlyNamespaceError } from "../src/ReadOnlyNamespaceError.js";
+import { OutOfRangeError } from "../src/OutOfRangeError.js";
+import type { StructureEvent, TransformJSON, TreeChange } from "../src/types.js";
 
 // ── helpers ─────────────────────────────────────────────────────────────────
 
@@ -59,6 +71,122 @@ describe("TFTree", () => {
     expect(tf.hasFrame("ghost")).toBe(false);
   });
 
+  // ── findFrames ───────────────────────────────────────────────────────────────
+
+  it("findFrames() '*' matches a single namespace segment", () => {
+    tf.addFrame("world");
+    tf.addFrame("camera/front", "world");
+    tf.addFrame("camera/rear", "world");
+    tf.addFrame("camera/rear/optical", "camera/rear");
+    expect(tf.findFrames("camera/*")).toEqual(["camera/front", "camera/rear"]);
+  });
+
+  it("findFrames() '**' matches across segments", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot1/base", "world");
+    tf.addFrame("robot1/arm/link1", "robot1/base");
+    tf.addFrame("robot2/base", "world");
+    expect(tf.findFrames("robot1/**")).toEqual(["robot1/base", "robot1/arm/link1"]);
+  });
+
+  it("findFrames() '?' matches one character and literals are escaped", () => {
+    tf.addFrame("wheel_1");
+    tf.addFrame("wheel_2");
+    tf.addFrame("wheel_10");
+    tf.addFrame("a.b");
+    tf.addFrame("axb");
+    expect(tf.findFrames("wheel_?")).toEqual(["wheel_1", "wheel_2"]);
+    expect(tf.findFrames("a.b")).toEqual(["a.b"]);
+  });
+
+  it("findFrames() returns an empty array when nothing matches", () => {
+    tf.addFrame("world");
+    expect(tf.findFrames("robot/*")).toEqual([]);
+  });
+
+  // ── subtreeSize ──────────────────────────────────────────────────────────────
+
+  it("subtreeSize() counts a frame and all of its descendants", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.addFrame("arm", "robot");
+    tf.addFrame("leg", "robot");
+    tf.addFrame("table", "world");
+    expect(tf.subtreeSize("world")).toBe(5);
+    expect(tf.subtreeSize("robot")).toBe(3);
+    expect(tf.subtreeSize("arm")).toBe(1);
+  });
+
+  it("subtreeSize() is kept up to date when frames are removed", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.addFrame("arm", "robot");
+    tf.removeFrame("arm");
+    expect(tf.subtreeSize("world")).toBe(2);
+    expect(tf.subtreeSize("robot")).toBe(1);
+  });
+
+  it("subtreeSize() throws for unknown frame", () => {
+    expect(() => tf.subtreeSize("ghost")).toThrow(/not found/);
+  });
+
+  // ── traverse ─────────────────────────────────────────────────────────────────
+
+  describe("traverse()", () => {
+    // world → a → a1
+    //           → a2
+    //       → b → b1
+    beforeEach(() => {
+      tf.addFrame("world");
+      tf.addFrame("a", "world");
+      tf.addFrame("b", "world");
+      tf.addFrame("a1", "a");
+      tf.addFrame("a2", "a");
+      tf.addFrame("b1", "b");
+    });
+
+    it("defaults to depth-first pre-order", () => {
+      expect(tf.traverse("world")).toEqual(["world", "a", "a1", "a2", "b", "b1"]);
+    });
+
+    it("supports breadth-first order", () => {
+      expect(tf.traverse("world", "bfs")).toEqual(["world", "a", "b", "a1", "a2", "b1"]);
+    });
+
+    it("supports depth-first post-order", () => {
+      expect(tf.traverse("world", "dfs-post")).toEqual(["a1", "a2", "a", "b1", "b", "world"]);
+    });
+
+    it("is limited to the subtree of the given root", () => {
+      expect(tf.traverse("a", "bfs")).toEqual(["a", "a1", "a2"]);
+      expect(tf.traverse("b1")).toEqual(["b1"]);
+    });
+
+    it("throws for an unknown root", () => {
+      expect(() => tf.traverse("ghost")).toThrow(/not found/);
+    });
+  });
+
+  // ── getLocalTransform ────────────────────────────────────────────────────────
+
+  it("getLocalTransform() returns the transform relative to the parent", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.addFrame("camera", "robot", translate(0, 0, 2));
+    expect(tf.getLocalTransform("camera").equals(translate(0, 0, 2))).toBe(true);
+  });
+
+  it("getLocalTransform() reflects the latest update", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.updateTransform("robot", rotate90Z());
+    expect(tf.getLocalTransform("robot").equals(rotate90Z())).toBe(true);
+  });
+
+  it("getLocalTransform() throws for unknown frame", () => {
+    expect(() => tf.getLocalTransform("ghost")).toThrow(/not found/);
+  });
+
   // ── updateTransform ──────────────────────────────────────────────────────────
 
   it("updateTransform() changes the stored transform", () => {
@@ -106,6 +234,35 @@ describe("TFTree", () => {
     ).toThrow(/not found/);
   });
 
+  it("updateTransforms() leaves the tree unchanged when any entry or step fails", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.addFrame("camera", "robot", translate(0, 0, 1));
+    tf.getTransform("world", "camera");
+    tf.enableJournal();
+    tf.setRotationNormMode("strict");
+
+    const skewed = new Transform(Vec3.zero(), new Quaternion(0, 0, 0, 2));
+    expect(() =>
+      tf.updateTransforms({ robot: translate(5, 0, 0), camera: skewed }),
+    ).toThrowError(InvalidTransformError);
+    expect(tf.getLocalTransform("robot").equals(translate(1, 0, 0))).toBe(true);
+
+    const internals = tf as unknown as { invalidateSubtree: () => never };
+    internals.invalidateSubtree = () => {
+      throw new Error("boom");
+    };
+    expect(() =>
+      tf.updateTransforms({ robot: translate(5, 0, 0), camera: translate(0, 0, 2) }),
+    ).toThrow("boom");
+    delete (internals as { invalidateSubtree?: unknown }).invalidateSubtree;
+
+    expect(tf.getLocalTransform("robot").equals(translate(1, 0, 0))).toBe(true);
+    expect(tf.getLocalTransform("camera").equals(translate(0, 0, 1))).toBe(true);
+    expect(tf.getTransform("world", "camera").translation.equals(new Vec3(1, 0, 1))).toBe(true);
+    expect(tf.exportJournal()).toEqual([]);
+  });
+
   it("updateTransforms() skips redundant dirty-marking for descendant frames", () => {
     // world → base → shoulder → wrist
     tf.addFrame("world");
@@ -142,6 +299,59 @@ describe("TFTree", () => {
     ).toBe(true);
   });
 
+  it("updateFrameRotation() replaces the rotation and keeps the translation", () => {
+    tf.addFrame("world");
+    tf.addFrame("imu", "world", translate(1, 2, 3));
+    const q = Quaternion.fromAxisAngle(new Vec3(1, 0, 0), 0.3);
+    tf.updateFrameRotation("imu", q.x, q.y, q.z, q.w);
+    expect(tf.getLocalTransform("imu")).toEqual(new Transform(new Vec3(1, 2, 3), q));
+    expect(() => tf.updateFrameRotation("ghost", 0, 0, 0, 1)).toThrowError(FrameNotFoundError);
+    tf.lockFrame("imu");
+    expect(() => tf.updateFrameRotation("imu", 0, 0, 0, 1)).toThrowError(FrameLockedError);
+    tf.unlockFrame("imu");
+
+    let err: unknown;
+    try {
+      tf.updateFrameRotation("imu", 0, NaN, 0, 1);
+    } catch (e) {
+      err = e;
+    }
+    expect(err).toBeInstanceOf(InvalidTransformError);
+    expect(err).toMatchObject({ frameId: "imu", component: "rotation.y" });
+    expect(() => tf.updateFrameRotation("imu", 0, 0, 0, 0)).toThrowError(InvalidTransformError);
+    expect(tf.getLocalTransform("imu")).toEqual(new Transform(new Vec3(1, 2, 3), q));
+  });
+
+  it("integrateTwist() dead-reckons a frame along its arc", () => {
+    tf.addFrame("odom");
+    tf.addFrame("base_link", "odom");
+    const forward = new Vec3(1, 0, 0);
+    const turn = new Vec3(0, 0, 1);
+
+    // Half a unit circle, in one step or many.
+    const arc = tf.integrateTwist("base_link", forward, turn, Math.PI);
+    expect(arc.translation.equals(new Vec3(0, 2, 0), 1e-12)).toBe(true);
+    expect(arc.rotation.equals(Quaternion.fromAxisAngle(turn, Math.PI))).toBe(true);
+    tf.updateTransform("base_link", Transform.identity());
+    for (let i = 0; i < 1000; i++) {
+      tf.integrateTwist("base_link", forward, turn, Math.PI / 1000);
+    }
+    expect(tf.getLocalTransform("base_link").equals(arc, 1e-9)).toBe(true);
+
+    // Straight ahead, the velocity is taken in the frame's own axes.
+    tf.integrateTwist("base_link", forward, Vec3.zero(), 0.5);
+    expect(tf.getLocalTransform("base_link").translation.equals(new Vec3(-0.5, 2, 0))).toBe(true);
+
+    expect(() => tf.integrateTwist("base_link", forward, turn, -1)).toThrowError(TFError);
+    expect(() => tf.integrateTwist("base_link", new Vec3(NaN, 0, 0), turn, 1)).toThrow(
+      /Invalid twist/,
+    );
+    tf.lockFrame("base_link");
+    expect(() => tf.integrateTwist("base_link", forward, turn, 1)).toThrowError(
+      FrameLockedError,
+    );
+  });
+
   it("updateTransforms() with an empty object is a no-op", () => {
     tf.addFrame("world");
     tf.addFrame("robot", "world", translate(1, 0, 0));
@@ -320,6 +530,288 @@ describe("TFTree", () => {
     expect(camOrigin.equals(new Vec3(2.5, 0, 0.8))).toBe(true);
   });
 
+  // ── addOrUpdateFrame ─────────────────────────────────────────────────────────
+
+  it("addOrUpdateFrame() registers missing frames and updates existing ones", () => {
+    expect(tf.addOrUpdateFrame("world", undefined, Transform.identity())).toBe(true);
+    expect(tf.addOrUpdateFrame("robot", "world", translate(1, 0, 0))).toBe(true);
+    expect(tf.addOrUpdateFrame("robot", undefined, translate(2, 0, 0))).toBe(false);
+    expect(tf.frameIds()).toEqual(["world", "robot"]);
+    expect(tf.getLocalTransform("robot").equals(translate(2, 0, 0))).toBe(true);
+  });
+
+  it("addOrUpdateFrame() reparents an existing frame when the parent differs", () => {
+    tf.addFrame("world");
+    tf.addFrame("table", "world", translate(5, 0, 0));
+    tf.addFrame("cup", "world");
+    expect(tf.addOrUpdateFrame("cup", "table", translate(0, 0, 1))).toBe(false);
+    expect(tf.getTransform("world", "cup").translation.equals(new Vec3(5, 0, 1))).toBe(true);
+    expect(() => tf.addOrUpdateFrame("table", "cup", Transform.identity())).toThrowError(
+      CycleDetectedError,
+    );
+  });
+
+  // ── setParent ────────────────────────────────────────────────────────────────
+
+  it("setParent() moves a frame and its subtree, keeping the local transform", () => {
+    tf.addFrame("world");
+    tf.addFrame("table", "world", translate(5, 0, 0));
+    tf.addFrame("cup", "world", translate(1, 0, 0));
+    tf.addFrame("handle", "cup", translate(0, 1, 0));
+
+    tf.setParent("cup", "table");
+
+    expect(tf.getLocalTransform("cup").equals(translate(1, 0, 0))).toBe(true);
+    expect(
+      tf
+        .getTransform("world", "handle")
+        .transformPoint(Vec3.zero())
+        .equals(new Vec3(6, 1, 0)),
+    ).toBe(true);
+    expect(tf.traverse("table")).toEqual(["table", "cup", "handle"]);
+    expect(tf.subtreeSize("table")).toBe(3);
+    expect(tf.subtreeSize("world")).toBe(4);
+  });
+
+  it("setParent() without a parent turns the frame into a root", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.setParent("robot");
+    expect(tf.isConnected("world", "robot")).toBe(false);
+    expect(tf.subtreeSize("world")).toBe(1);
+  });
+
+  it("setParent() notifies listeners in the moved subtree", () => {
+    tf.addFrame("world");
+    tf.addFrame("a", "world");
+    tf.addFrame("b", "world");
+    tf.addFrame("leaf", "a");
+    const calls: string[] = [];
+    tf.onChange("leaf", (id) => calls.push(id));
+    tf.setParent("a", "b");
+    expect(calls).toEqual(["leaf"]);
+  });
+
+  it("setParent() rejects cycles and unknown frames", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.addFrame("arm", "robot");
+    expect(() => tf.setParent("robot", "arm")).toThrowError(CycleDetectedError);
+    expect(() => tf.setParent("robot", "robot")).toThrowError(CycleDetectedError);
+    expect(() => tf.setParent("ghost", "world")).toThrow(/not found/);
+    expect(() => tf.setParent("robot", "ghost")).toThrow(/not found/);
+  });
+
+  it("toJSON() keeps parents first after reparenting under a later frame", () => {
+    tf.addFrame("world");
+    tf.addFrame("cup", "world");
+    tf.addFrame("table", "world");
+    tf.setParent("cup", "table");
+    const restored = TFTree.fromJSON(tf.toJSON());
+    expect(restored.toJSON().frames.map((f) => f.id)).toEqual(["world", "table", "cup"]);
+  });
+
+  it("toJSON() only includes the frames passing the export filter", () => {
+    tf.addFrame("world");
+    tf.addFrame("client/robot", "world");
+    tf.addFrame("client/camera", "client/robot");
+    tf.addFrame("client/marker", "world");
+    tf.addFrame("server/dock", "world");
+    tf.updateTransform("client/robot", translate(1, 0, 0));
+    const ids = () => tf.toJSON().frames.map((f) => f.id);
+
+    tf.setExportFilter({ prefixes: ["client/"], ids: ["world"] });
+    expect(ids()).toEqual(["world", "client/robot", "client/camera", "client/marker"]);
+    expect(tf.toJSON().frames[1]).toMatchObject({ id: "client/robot", parentId: "world" });
+    tf.setExportFilter({ prefixes: ["client/"], staticOnly: true });
+    expect(ids()).toEqual(["client/camera", "client/marker"]);
+    tf.setExportFilter({ staticOnly: true });
+    expect(ids()).toEqual(["world", "client/camera", "client/marker", "server/dock"]);
+    tf.setExportFilter(undefined);
+    expect(ids()).toHaveLength(5);
+    // Internal consumers of the frames are unaffected.
+    tf.setExportFilter({ ids: [] });
+    expect(new TFTree().diff(tf)).toHaveLength(5);
+  });
+
+  // ── setParentKeepWorld ───────────────────────────────────────────────────────
+
+  it("setParentKeepWorld() preserves the world pose of the frame and its subtree", () => {
+    tf.addFrame("world");
+    tf.addFrame("gripper", "world", new Transform(new Vec3(2, 0, 1), rotate90Z().rotation));
+    tf.addFrame("cup", "world", translate(3, 1, 0));
+    tf.addFrame("handle", "cup", translate(0, 0.1, 0));
+    const before = tf.getTransform("world", "handle");
+
+    tf.setParentKeepWorld("cup", "gripper");
+
+    expect(tf.getTransform("world", "handle").equals(before)).toBe(true);
+    // cup sits at (1, 1, -1) in world coordinates relative to the gripper,
+    // which after undoing the gripper's 90° yaw is (1, -1, -1).
+    expect(tf.getLocalTransform("cup").translation.equals(new Vec3(1, -1, -1), 1e-5)).toBe(true);
+  });
+
+  it("setParentKeepWorld() without a parent stores the world pose as the local transform", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.addFrame("tool", "robot", translate(0, 2, 0));
+    tf.setParentKeepWorld("tool");
+    expect(tf.getLocalTransform("tool").equals(translate(1, 2, 0))).toBe(true);
+  });
+
+  it("setParentKeepWorld() re-roots a separate map tree under a localized anchor", () => {
+    tf.addFrame("world");
+    tf.addFrame("anchor", "world", new Transform(new Vec3(4, 0, 0), rotate90Z().rotation));
+    tf.addFrame("map");
+    tf.addFrame("room", "map", translate(1, 2, 0));
+    tf.addFrame("door", "room", translate(0, 1, 0));
+    const before = tf.getTransform("map", "door");
+
+    tf.setParentKeepWorld("map", "anchor");
+
+    // The map's descendants keep their pose relative to the (former) map root,
+    // which now coincides with the world origin.
+    expect(tf.getTransform("world", "door").equals(before)).toBe(true);
+    expect(tf.getTransform("world", "map").equals(Transform.identity())).toBe(true);
+  });
+
+  it("setParentKeepWorld() rejects cycles", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    expect(() => tf.setParentKeepWorld("world", "robot")).toThrowError(CycleDetectedError);
+  });
+
+  // ── invertEdge / reroot ──────────────────────────────────────────────────────
+
+  it("invertEdge() swaps a frame with its parent without moving anything", () => {
+    tf.addFrame("world");
+    tf.addFrame("map", "world", translate(1, 0, 0));
+    tf.addFrame("odom", "map", new Transform(new Vec3(0, 2, 0), rotate90Z().rotation));
+    tf.addFrame("landmark", "map", translate(3, 0, 0));
+    const before = tf.getTransform("odom", "landmark");
+
+    tf.invertEdge("odom");
+
+    expect(tf.getTransform("odom", "map").equals(before.compose(translate(-3, 0, 0)))).toBe(true);
+    expect(tf.traverse("world")).toEqual(["world", "odom", "map", "landmark"]);
+    expect(tf.getTransform("odom", "landmark").equals(before)).toBe(true);
+    expect(tf.getTransform("world", "map").equals(translate(1, 0, 0))).toBe(true);
+    expect(() => tf.invertEdge("world")).toThrow(/root frame/);
+  });
+
+  it("reroot() makes a frame the root while keeping relative transforms", () => {
+    tf.addFrame("map");
+    tf.addFrame("odom", "map", translate(1, 0, 0));
+    tf.addFrame("base", "odom", rotate90Z());
+    tf.addFrame("laser", "base", translate(0, 0, 1));
+    const before = tf.getTransform("laser", "map");
+
+    tf.reroot("base");
+
+    expect(tf.traverse("base")).toEqual(["base", "laser", "odom", "map"]);
+    expect(tf.getComponents().map((c) => c.rootId)).toEqual(["base"]);
+    expect(tf.getTransform("laser", "map").equals(before)).toBe(true);
+  });
+
+  it("reroot() leaves the tree untouched if a frame on the path is locked", () => {
+    tf.addFrame("map");
+    tf.addFrame("odom", "map");
+    tf.addFrame("base", "odom");
+    tf.lockFrame("map");
+    expect(() => tf.reroot("base")).toThrowError(FrameLockedError);
+    expect(tf.traverse("map")).toEqual(["map", "odom", "base"]);
+  });
+
+  // ── detach / reattach ────────────────────────────────────────────────────────
+
+  describe("detach() / reattach()", () => {
+    beforeEach(() => {
+      tf.addFrame("world");
+      tf.addFrame("shelf", "world", translate(2, 0, 0));
+      tf.addFrame("box", "shelf", translate(0, 0, 1));
+      tf.addFrame("lid", "box", translate(0, 0, 0.1));
+    });
+
+    it("detach() turns a subtree into a floating root without moving it", () => {
+      tf.detach("box");
+      expect(tf.isDetached("box")).toBe(true);
+      expect(tf.getComponents().map((c) => c.rootId)).toEqual(["world", "box"]);
+      expect(tf.getLocalTransform("box").equals(translate(2, 0, 1))).toBe(true);
+      expect(() => tf.detach("world")).toThrow(/root frame/);
+    });
+
+    it("reattach() restores the pose the frame had when detached", () => {
+      tf.detach("box");
+      tf.updateTransform("box", translate(9, 9, 9));
+      tf.reattach("box");
+      expect(tf.isDetached("box")).toBe(false);
+      expect(tf.getLocalTransform("box").equals(translate(0, 0, 1))).toBe(true);
+      expect(tf.getTransform("world", "lid").translation.equals(new Vec3(2, 0, 1.1))).toBe(true);
+    });
+
+    it("reattach() can keep the current pose under a different parent", () => {
+      tf.addFrame("table", "world", translate(5, 0, 0));
+      tf.detach("box");
+      tf.updateTransform("box", translate(6, 0, 1));
+      tf.reattach("box", "table", { keepCurrentPose: true });
+      expect(tf.getLocalTransform("box").equals(translate(1, 0, 1))).toBe(true);
+      expect(() => tf.reattach("box")).toThrow(/not detached/);
+    });
+  });
+
+  // ── renameFrame ──────────────────────────────────────────────────────────────
+
+  it("renameFrame() renames a frame in place, keeping parent and children", () => {
+    tf.addFrame("world");
+    tf.addFrame("base", "world", translate(1, 0, 0));
+    tf.addFrame("arm", "base", translate(0, 1, 0));
+    tf.addFrame("table", "world");
+
+    tf.renameFrame("base", "base_link");
+
+    expect(tf.hasFrame("base")).toBe(false);
+    expect(tf.frameIds()).toEqual(["world", "base_link", "arm", "table"]);
+    expect(tf.traverse("world")).toEqual(["world", "base_link", "arm", "table"]);
+    expect(tf.subtreeSize("base_link")).toBe(2);
+    expect(
+      tf
+        .getTransform("world", "arm")
+        .transformPoint(Vec3.zero())
+        .equals(new Vec3(1, 1, 0)),
+    ).toBe(true);
+  });
+
+  it("renameFrame() carries listeners over to the new id", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    const calls: string[] = [];
+    tf.onChange("robot", (id) => calls.push(id));
+    tf.renameFrame("robot", "robot1");
+    tf.updateTransform("robot1", translate(1, 0, 0));
+    expect(calls).toEqual(["robot1"]);
+  });
+
+  it("renameFrame() keeps cached transforms valid", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(2, 0, 0));
+    tf.getTransform("world", "robot");
+    tf.renameFrame("world", "map");
+    tf.updateTransform("map", translate(1, 0, 0));
+    expect(
+      tf
+        .getTransform("robot", "map")
+        .transformPoint(Vec3.zero())
+        .equals(new Vec3(-2, 0, 0)),
+    ).toBe(true);
+  });
+
+  it("renameFrame() throws for unknown or taken ids", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    expect(() => tf.renameFrame("ghost", "x")).toThrow(/not found/);
+    expect(() => tf.renameFrame("robot", "world")).toThrow(/already registered/);
+  });
+
   // ── removeFrame ──────────────────────────────────────────────────────────────
 
   it("removeFrame() removes a leaf frame", () => {
@@ -361,246 +853,2724 @@ describe("TFTree", () => {
     expect(tf.frameIds()).toEqual(["world"]);
   });
 
-  it("getTransform() throws after a frame has been removed", () => {
+  it("removeFrame() with promoteChildren reparents children keeping local transforms", () => {
     tf.addFrame("world");
-    tf.addFrame("robot", "world");
-    tf.removeFrame("robot");
-    expect(() => tf.getTransform("world", "robot")).toThrow(/not found/);
-  });
+    tf.addFrame("mount", "world", translate(1, 0, 0));
+    tf.addFrame("sensor", "mount", translate(0, 1, 0));
 
-  // ── caching & dirty flags ────────────────────────────────────────────────────
+    tf.removeFrame("mount", { promoteChildren: true });
 
-  it("getTransform() returns consistent results on repeated calls (cache correctness)", () => {
+    expect(tf.hasFrame("mount")).toBe(false);
+    expect(tf.traverse("world")).toEqual(["world", "sensor"]);
+    expect(tf.getLocalTransform("sensor").equals(translate(0, 1, 0))).toBe(true);
+  });
+
+  it("removeFrame() with preserveWorld keeps the children's world poses", () => {
     tf.addFrame("world");
-    tf.addFrame("robot", "world", translate(2, 0, 0));
-    tf.addFrame("camera", "robot", translate(0, 0, 1));
+    tf.addFrame("mount", "world", new Transform(new Vec3(1, 0, 0), rotate90Z().rotation));
+    tf.addFrame("sensor", "mount", translate(1, 0, 0));
+    const before = tf.getTransform("world", "sensor");
 
-    const t1 = tf.getTransform("world", "camera");
-    const t2 = tf.getTransform("world", "camera");
+    tf.removeFrame("mount", { promoteChildren: true, preserveWorld: true });
 
-    expect(t1.equals(t2)).toBe(true);
-    expect(t1.transformPoint(Vec3.zero()).equals(new Vec3(2, 0, 1))).toBe(true);
+    expect(tf.getTransform("world", "sensor").equals(before)).toBe(true);
+    expect(tf.subtreeSize("world")).toBe(2);
   });
 
-  it("updateTransform() invalidates cached world transform of descendants", () => {
+  it("removeFrame() with promoteChildren on a root turns children into roots", () => {
     tf.addFrame("world");
-    tf.addFrame("robot", "world", translate(1, 0, 0));
-    tf.addFrame("camera", "robot", translate(0, 0, 1));
+    tf.addFrame("a", "world");
+    tf.addFrame("b", "world");
+    tf.removeFrame("world", { promoteChildren: true });
+    expect(tf.getComponents().map(({ rootId }) => rootId)).toEqual(["a", "b"]);
+  });
 
-    // Warm the cache.
-    expect(
-      tf
-        .getTransform("world", "camera")
-        .transformPoint(Vec3.zero())
-        .equals(new Vec3(1, 0, 1)),
-    ).toBe(true);
+  // ── clear ────────────────────────────────────────────────────────────────────
 
-    // Move the robot – camera should follow.
-    tf.updateTransform("robot", translate(5, 0, 0));
+  it("clear() removes every frame and leaves the tree reusable", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.getTransform("world", "robot");
+
+    tf.clear();
 
+    expect(tf.frameIds()).toEqual([]);
+    expect(tf.hasFrame("world")).toBe(false);
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(2, 0, 0));
+    expect(tf.subtreeSize("world")).toBe(2);
     expect(
       tf
-        .getTransform("world", "camera")
+        .getTransform("world", "robot")
         .transformPoint(Vec3.zero())
-        .equals(new Vec3(5, 0, 1)),
+        .equals(new Vec3(2, 0, 0)),
     ).toBe(true);
   });
 
-  it("updateTransform() only dirties the updated subtree, not unrelated frames", () => {
+  it("clear() drops change listeners", () => {
     tf.addFrame("world");
-    tf.addFrame("arm", "world", translate(1, 0, 0));
-    tf.addFrame("leg", "world", translate(0, 1, 0));
+    const calls: string[] = [];
+    tf.onChange("world", (id) => calls.push(id));
+    tf.clear();
+    tf.addFrame("world");
+    tf.updateTransform("world", translate(1, 0, 0));
+    expect(calls).toHaveLength(0);
+  });
 
-    // Warm the cache for both branches.
-    expect(
-      tf
-        .getTransform("world", "arm")
-        .transformPoint(Vec3.zero())
-        .equals(new Vec3(1, 0, 0)),
-    ).toBe(true);
-    expect(
-      tf
-        .getTransform("world", "leg")
-        .transformPoint(Vec3.zero())
-        .equals(new Vec3(0, 1, 0)),
-    ).toBe(true);
+  // ── attachTree ───────────────────────────────────────────────────────────────
 
-    // Update only arm.
-    tf.updateTransform("arm", translate(3, 0, 0));
+  it("attachTree() mounts another tree's roots under a frame with a prefix", () => {
+    const robot = new TFTree();
+    robot.addFrame("base_link");
+    robot.addFrame("camera", "base_link", translate(0, 0, 1));
 
-    // arm is updated; leg is unchanged.
-    expect(
-      tf
-        .getTransform("world", "arm")
-        .transformPoint(Vec3.zero())
-        .equals(new Vec3(3, 0, 0)),
-    ).toBe(true);
+    tf.addFrame("world");
+    tf.addFrame("spawn", "world", translate(5, 0, 0));
+
+    expect(tf.attachTree(robot, "spawn", "robot1/")).toEqual(["robot1/base_link", "robot1/camera"]);
     expect(
       tf
-        .getTransform("world", "leg")
+        .getTransform("world", "robot1/camera")
         .transformPoint(Vec3.zero())
-        .equals(new Vec3(0, 1, 0)),
+        .equals(new Vec3(5, 0, 1)),
     ).toBe(true);
+    // The source tree is left untouched.
+    expect(robot.frameIds()).toEqual(["base_link", "camera"]);
   });
 
-  // ── error handling ───────────────────────────────────────────────────────────
-
-  it("getTransform throws for unknown 'from' frame", () => {
+  it("attachTree() attaches every root of a forest", () => {
+    const other = new TFTree();
+    other.addFrame("a");
+    other.addFrame("b");
     tf.addFrame("world");
-    expect(() => tf.getTransform("ghost", "world")).toThrow(/not found/);
+    tf.attachTree(other, "world");
+    expect(tf.traverse("world")).toEqual(["world", "a", "b"]);
   });
 
-  it("getTransform throws for unknown 'to' frame", () => {
+  it("attachTree() is all-or-nothing on id collisions", () => {
+    const other = new TFTree();
+    other.addFrame("fresh");
+    other.addFrame("world", "fresh");
     tf.addFrame("world");
-    expect(() => tf.getTransform("world", "ghost")).toThrow(/not found/);
+    expect(() => tf.attachTree(other, "world")).toThrow(/already registered/);
+    expect(tf.frameIds()).toEqual(["world"]);
   });
 
-  it("getTransform throws for disconnected frames", () => {
+  it("attachTree() throws for an unknown mount frame", () => {
+    expect(() => tf.attachTree(new TFTree(), "ghost")).toThrow(/not found/);
+  });
+
+  // ── extractSubtree ───────────────────────────────────────────────────────────
+
+  it("extractSubtree() copies a subtree into a new tree rooted at the given frame", () => {
     tf.addFrame("world");
-    tf.addFrame("island");
-    expect(() => tf.getTransform("world", "island")).toThrow(/not connected/);
+    tf.addFrame("robot", "world", translate(5, 0, 0));
+    tf.addFrame("camera", "robot", translate(0, 0, 1));
+    tf.addFrame("table", "world");
+
+    const extracted = tf.extractSubtree("robot");
+
+    expect(extracted.frameIds()).toEqual(["robot", "camera"]);
+    expect(extracted.getComponents()).toEqual([{ rootId: "robot", frameIds: ["robot", "camera"] }]);
+    expect(extracted.getLocalTransform("robot").equals(translate(5, 0, 0))).toBe(true);
+    // Copy by default: the source tree is unchanged.
+    expect(tf.hasFrame("camera")).toBe(true);
   });
 
-  it("chainToRoot() detects a cycle via internal frame map manipulation", () => {
-    // Force a cycle by bypassing addFrame validation (direct map access via any cast)
+  it("extractSubtree() with remove moves the subtree out of the source", () => {
     tf.addFrame("world");
-    tf.addFrame("child", "world");
-    // Introduce cycle: child → world → child
+    tf.addFrame("robot", "world", translate(5, 0, 0));
+    tf.addFrame("camera", "robot");
+
+    const extracted = tf.extractSubtree("robot", { remove: true });
+
+    expect(tf.frameIds()).toEqual(["world"]);
+    tf.attachTree(extracted, "world");
+    expect(
+      tf
+        .getTransform("world", "camera")
+        .transformPoint(Vec3.zero())
+        .equals(new Vec3(5, 0, 0)),
+    ).toBe(true);
+  });
+
+  it("extractSubtree() throws for an unknown frame", () => {
+    expect(() => tf.extractSubtree("ghost")).toThrow(/not found/);
+  });
+
+  // ── splitAt ──────────────────────────────────────────────────────────────────
+
+  it("splitAt() cuts parent edges into independent trees", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot1", "world", translate(5, 0, 0));
+    tf.addFrame("arm1", "robot1", translate(0, 0, 1));
+    tf.addFrame("robot2", "world", translate(-5, 0, 0));
+    tf.addFrame("arm2", "robot2");
+    tf.addFrame("table", "world");
+    tf.addFrame("map");
+
+    const parts = tf.splitAt(["robot1", "robot2", "map"]);
+
+    expect(parts.map((part) => part.getComponents())).toEqual([
+      [{ rootId: "world", frameIds: ["world", "table"] }],
+      [{ rootId: "robot1", frameIds: ["robot1", "arm1"] }],
+      [{ rootId: "robot2", frameIds: ["robot2", "arm2"] }],
+      [{ rootId: "map", frameIds: ["map"] }],
+    ]);
+    expect(parts[1].getLocalTransform("robot1").equals(translate(5, 0, 0))).toBe(true);
+    expect(tf.subtreeSize("world")).toBe(6);
+
+    parts[0].attachTree(parts[1], "world");
+    expect(parts[0].getTransform("world", "arm1").equals(tf.getTransform("world", "arm1"))).toBe(
+      true,
+    );
+  });
+
+  it("splitAt() throws for an unknown frame", () => {
+    expect(() => tf.splitAt(["ghost"])).toThrow(FrameNotFoundError);
+  });
+
+  // ── transactions ─────────────────────────────────────────────────────────────
+
+  describe("transactions", () => {
+    beforeEach(() => {
+      tf.addFrame("world");
+      tf.addFrame("robot", "world", translate(1, 0, 0));
+      tf.addFrame("arm", "robot", translate(0, 1, 0));
+    });
+
+    it("rollback() restores structure and transforms", () => {
+      tf.getTransform("world", "arm"); // warm the cache
+      tf.begin();
+      tf.updateTransform("robot", translate(9, 0, 0));
+      tf.removeFrame("arm");
+      tf.addFrame("leg", "robot");
+      tf.rollback();
+
+      expect(tf.frameIds()).toEqual(["world", "robot", "arm"]);
+      expect(tf.traverse("world")).toEqual(["world", "robot", "arm"]);
+      expect(tf.subtreeSize("world")).toBe(3);
+      expect(
+        tf
+          .getTransform("world", "arm")
+          .transformPoint(Vec3.zero())
+          .equals(new Vec3(1, 1, 0)),
+      ).toBe(true);
+    });
+
+    it("defers change notifications until commit()", () => {
+      const calls: string[] = [];
+      tf.onChange("arm", (id) => calls.push(id));
+      tf.begin();
+      tf.updateTransform("robot", translate(2, 0, 0));
+      tf.updateTransform("arm", translate(0, 2, 0));
+      expect(calls).toHaveLength(0);
+      tf.commit();
+      expect(calls).toEqual(["arm"]);
+    });
+
+    it("discards notifications and restores listeners on rollback()", () => {
+      const calls: string[] = [];
+      tf.onChange("arm", (id) => calls.push(id));
+      tf.begin();
+      tf.removeFrame("arm");
+      tf.rollback();
+      expect(calls).toHaveLength(0);
+      tf.updateTransform("arm", translate(0, 3, 0));
+      expect(calls).toEqual(["arm"]);
+    });
+
+    it("keeps subscriptions made or removed during a rolled-back transaction", () => {
+      const calls: string[] = [];
+      const stale = tf.onChange("arm", () => calls.push("stale"));
+      tf.begin();
+      tf.onChange("robot", (id) => calls.push(id));
+      tf.onAnyChange(() => calls.push("batch"));
+      tf.onStructureChange(() => calls.push("structure"));
+      stale();
+      tf.renameFrame("arm", "hand");
+      tf.rollback();
+
+      tf.updateTransform("robot", translate(2, 0, 0));
+      tf.addFrame("leg", "robot");
+      expect(calls).toEqual(["robot", "batch", "structure"]);
+    });
+
+    it("keeps listeners of frames removed by a validateOnly batch", () => {
+      const calls: string[] = [];
+      tf.onChange("arm", (id) => calls.push(id));
+      tf.applyPatch([{ type: "removed", id: "arm" }], { validateOnly: true });
+      tf.updateTransform("arm", translate(0, 3, 0));
+      expect(calls).toEqual(["arm"]);
+    });
+
+    it("transaction() rolls back and rethrows when the callback throws", () => {
+      expect(() =>
+        tf.transaction(() => {
+          tf.updateTransform("robot", translate(7, 0, 0));
+          tf.addFrame("robot", "world"); // duplicate → throws
+        }),
+      ).toThrow(/already registered/);
+      expect(tf.inTransaction()).toBe(false);
+      expect(tf.getLocalTransform("robot").equals(translate(1, 0, 0))).toBe(true);
+    });
+
+    it("transaction() commits and returns the callback result", () => {
+      const result = tf.transaction(() => {
+        tf.updateTransform("robot", translate(3, 0, 0));
+        return 42;
+      });
+      expect(result).toBe(42);
+      expect(tf.getLocalTransform("robot").equals(translate(3, 0, 0))).toBe(true);
+    });
+
+    it("rejects nested begin() and commit()/rollback() without begin()", () => {
+      tf.begin();
+      expect(() => tf.begin()).toThrow(/already in progress/);
+      tf.commit();
+      expect(() => tf.commit()).toThrow(/No transaction/);
+      expect(() => tf.rollback()).toThrow(/No transaction/);
+    });
+  });
+
+  // ── mutation journal ─────────────────────────────────────────────────────────
+
+  describe("mutation journal", () => {
+    it("records nothing until enabled", () => {
+      tf.addFrame("world");
+      expect(tf.exportJournal()).toEqual([]);
+    });
+
+    it("assigns increasing sequence numbers to every mutation", () => {
+      tf.enableJournal();
+      tf.addFrame("world");
+      tf.addFrame("robot", "world", translate(1, 0, 0));
+      tf.updateTransform("robot", translate(2, 0, 0));
+      tf.renameFrame("robot", "robot1");
+      tf.removeFrame("robot1");
+
+      const journal = tf.exportJournal();
+      expect(journal.map((e) => e.seq)).toEqual([1, 2, 3, 4, 5]);
+      expect(journal.map((e) => e.op)).toEqual(["add", "add", "update", "rename", "remove"]);
+      expect(tf.exportJournal(3).map((e) => e.seq)).toEqual([4, 5]);
+    });
+
+    it("replays on another instance to reproduce the same tree", () => {
+      tf.enableJournal();
+      tf.addFrame("world");
+      tf.addFrame("a", "world", translate(1, 0, 0));
+      tf.addFrame("b", "world", translate(0, 1, 0));
+      tf.addFrame("leaf", "a", translate(0, 0, 1));
+      tf.setParentKeepWorld("leaf", "b");
+      tf.removeSubtree("a");
+      tf.updateTransforms({ b: translate(0, 2, 0) });
+
+      const replica = new TFTree();
+      replica.applyJournal(tf.exportJournal());
+      expect(replica.toJSON()).toEqual(tf.toJSON());
+    });
+
+    it("drops entries of rolled-back transactions", () => {
+      tf.enableJournal();
+      tf.addFrame("world");
+      tf.begin();
+      tf.addFrame("temp", "world");
+      tf.rollback();
+      tf.addFrame("robot", "world");
+      expect(tf.exportJournal().map((e) => [e.seq, e.op])).toEqual([
+        [1, "add"],
+        [2, "add"],
+      ]);
+    });
+
+    it("applyJournal() is atomic and validates sequence order", () => {
+      tf.addFrame("world");
+      expect(() =>
+        tf.applyJournal([
+          {
+            seq: 1,
+            op: "add",
+            id: "robot",
+            parentId: "world",
+            transform: { translation: [1, 0, 0], rotation: [0, 0, 0, 1] },
+          },
+          { seq: 2, op: "remove", id: "ghost" },
+        ]),
+      ).toThrow(/not found/);
+      expect(tf.frameIds()).toEqual(["world"]);
+      expect(() =>
+        tf.applyJournal([
+          { seq: 2, op: "clear" },
+          { seq: 1, op: "clear" },
+        ]),
+      ).toThrow(/must increase/);
+    });
+  });
+
+  // ── locking ──────────────────────────────────────────────────────────────────
+
+  describe("frame locking", () => {
+    beforeEach(() => {
+      tf.addFrame("world");
+      tf.addFrame("camera", "world", translate(0, 0, 1));
+      tf.lockFrame("camera");
+    });
+
+    it("rejects transform updates to a locked frame with FrameLockedError", () => {
+      expect(tf.isFrameLocked("camera")).toBe(true);
+      expect(() => tf.updateTransform("camera", translate(9, 9, 9))).toThrowError(
+        FrameLockedError,
+      );
+      expect(() => tf.updateTransforms({ camera: translate(9, 9, 9) })).toThrowError(
+        FrameLockedError,
+      );
+      expect(() => tf.setParentKeepWorld("camera")).toThrowError(FrameLockedError);
+      let error: unknown;
+      try {
+        tf.updateFrame("camera", translate(9, 9, 9));
+      } catch (err) {
+        error = err;
+      }
+      expect((error as FrameLockedError).frameId).toBe("camera");
+      expect(tf.getLocalTransform("camera").equals(translate(0, 0, 1))).toBe(true);
+    });
+
+    it("allows updates again after unlockFrame()", () => {
+      tf.unlockFrame("camera");
+      expect(tf.isFrameLocked("camera")).toBe(false);
+      tf.updateTransform("camera", translate(0, 0, 2));
+      expect(tf.getLocalTransform("camera").equals(translate(0, 0, 2))).toBe(true);
+    });
+
+    it("keeps the lock across renames and drops it on removal", () => {
+      tf.renameFrame("camera", "camera_optical");
+      expect(tf.isFrameLocked("camera_optical")).toBe(true);
+      tf.removeFrame("camera_optical");
+      tf.addFrame("camera_optical", "world");
+      expect(tf.isFrameLocked("camera_optical")).toBe(false);
+      expect(() => tf.lockFrame("ghost")).toThrow(/not found/);
+    });
+  });
+
+  // ── authority ────────────────────────────────────────────────────────────────
+
+  describe("frame authority", () => {
+    beforeEach(() => {
+      tf.addFrame("map");
+      tf.addFrame("odom", "map");
+      tf.addFrame("base", "odom");
+    });
+
+    it("records the source of the latest update", () => {
+      tf.updateTransform("odom", translate(1, 0, 0), { source: "slam" });
+      tf.updateTransforms(
+        { odom: translate(2, 0, 0), base: translate(0, 1, 0) },
+        { source: "amcl" },
+      );
+      expect(tf.getLastSource("odom")).toBe("amcl");
+      expect(tf.getLastSource("base")).toBe("amcl");
+      tf.updateTransform("odom", translate(3, 0, 0));
+      expect(tf.getLastSource("odom")).toBeUndefined();
+      expect(tf.getFrameOwner("odom")).toBeUndefined();
+    });
+
+    it("rejects non-owning sources under the owner-only policy", () => {
+      tf.setAuthorityPolicy("owner-only");
+      tf.updateTransform("odom", translate(1, 0, 0), { source: "slam" });
+      expect(tf.getFrameOwner("odom")).toBe("slam");
+
+      let err: unknown;
+      try {
+        tf.updateTransform("odom", translate(9, 0, 0), { source: "amcl" });
+      } catch (e) {
+        err = e;
+      }
+      expect(err).toBeInstanceOf(UnauthorizedSourceError);
+      expect(err).toMatchObject({
+        code: "UNAUTHORIZED_SOURCE",
+        frameId: "odom",
+        source: "amcl",
+        owner: "slam",
+      });
+      const batch = { base: translate(0, 1, 0), odom: translate(9, 0, 0) };
+      expect(() => tf.updateTransforms(batch, { source: "amcl" })).toThrowError(
+        UnauthorizedSourceError,
+      );
+      expect(tf.getLocalTransform("odom").equals(translate(1, 0, 0))).toBe(true);
+      expect(tf.getLocalTransform("base").equals(Transform.identity())).toBe(true);
+      expect(tf.getFrameOwner("base")).toBeUndefined();
+
+      // Updates without a source, and owner hand-overs, are always allowed.
+      tf.updateTransform("odom", translate(2, 0, 0));
+      tf.setFrameOwner("odom", "amcl");
+      tf.updateTransform("odom", translate(3, 0, 0), { source: "amcl" });
+      expect(tf.getLastSource("odom")).toBe("amcl");
+    });
+
+    it("keeps ownership across renames and rolls it back with transactions", () => {
+      tf.setFrameOwner("odom", "slam");
+      tf.renameFrame("odom", "odom_slam");
+      expect(tf.getFrameOwner("odom_slam")).toBe("slam");
+      expect(() =>
+        tf.transaction(() => {
+          tf.setFrameOwner("odom_slam", undefined);
+          throw new Error("abort");
+        }),
+      ).toThrow("abort");
+      expect(tf.getFrameOwner("odom_slam")).toBe("slam");
+      tf.removeFrame("base");
+      tf.removeFrame("odom_slam");
+      tf.addFrame("odom_slam", "map");
+      expect(tf.getFrameOwner("odom_slam")).toBeUndefined();
+      expect(() => tf.setFrameOwner("ghost", "slam")).toThrow(/not found/);
+    });
+  });
+
+  describe("namespaces", () => {
+    beforeEach(() => {
+      tf.addFrame("world");
+      tf.addFrame("map/origin", "world");
+      tf.addFrame("plugin-a/robot", "world");
+    });
+
+    it("rejects additions and updates in read-only namespaces", () => {
+      tf.setNamespacePolicy("map/", { readOnly: true });
+
+      let err: unknown;
+      try {
+        tf.updateTransform("map/origin", translate(1, 0, 0));
+      } catch (e) {
+        err = e;
+      }
+      expect(err).toBeInstanceOf(ReadOnlyNamespaceError);
+      expect(err).toMatchObject({ code: "READ_ONLY_NAMESPACE", namespace: "map/" });
+      expect(() => tf.addFrame("map/door", "map/origin")).toThrow(ReadOnlyNamespaceError);
+      expect(() => tf.renameFrame("plugin-a/robot", "map/robot")).toThrow(
+        ReadOnlyNamespaceError,
+      );
+      tf.updateTransform("plugin-a/robot", translate(1, 0, 0));
+      tf.removeFrame("plugin-a/robot");
+      expect(tf.frameIds()).toEqual(["world", "map/origin"]);
+    });
+
+    it("rejects removing, renaming and reparenting frames of read-only namespaces", () => {
+      tf.addFrame("map/door", "map/origin");
+      tf.setNamespacePolicy("map/", { readOnly: true });
+
+      expect(() => tf.removeFrame("map/door")).toThrow(ReadOnlyNamespaceError);
+      expect(() => tf.removeSubtree("map/origin")).toThrow(ReadOnlyNamespaceError);
+      expect(() => tf.removeFramesWithPrefix("map/")).toThrow(ReadOnlyNamespaceError);
+      expect(() => tf.removeFrame("world", { promoteChildren: true })).toThrow(
+        ReadOnlyNamespaceError,
+      );
+      expect(() => tf.renameFrame("map/door", "plugin-a/door")).toThrow(ReadOnlyNamespaceError);
+      expect(() => tf.setParent("map/door", "world")).toThrow(ReadOnlyNamespaceError);
+      expect(() => tf.clear()).toThrow(ReadOnlyNamespaceError);
+      expect(tf.frameIds()).toEqual(["world", "map/origin", "plugin-a/robot", "map/door"]);
+
+      tf.setNamespacePolicy("map/", undefined);
+      expect(tf.removeFramesWithPrefix("map/")).toEqual(["map/door", "map/origin"]);
+    });
+
+    it("enforces per-namespace frame quotas, longest prefix first", () => {
+      tf.setNamespacePolicy("plugin-a/", { maxFrames: 2 });
+      tf.setNamespacePolicy("plugin-a/scratch/", {});
+      tf.addFrame("plugin-a/arm", "plugin-a/robot");
+      tf.addFrame("plugin-a/scratch/1", "world");
+
+      let err: unknown;
+      try {
+        tf.addFrame("plugin-a/gripper", "plugin-a/arm");
+      } catch (e) {
+        err = e;
+      }
+      expect(err).toBeInstanceOf(QuotaExceededError);
+      expect(err).toMatchObject({ quota: "maxFrames", limit: 2, namespace: "plugin-a/" });
+      expect((err as Error).message).toMatch(/in namespace "plugin-a\/" is limited to 2/);
+      expect(tf.getNamespacePolicies()).toEqual({
+        "plugin-a/": { maxFrames: 2 },
+        "plugin-a/scratch/": {},
+      });
+    });
+
+    it("reports per-namespace statistics", () => {
+      tf.setNamespacePolicy("map/", { readOnly: true });
+      tf.setNamespacePolicy("plugin-a/", {});
+      tf.updateTransform("plugin-a/robot", translate(1, 0, 0));
+      tf.addFrame("plugin-a/arm", "plugin-a/robot");
+      expect(() => tf.updateTransform("map/origin", translate(1, 0, 0))).toThrow();
+
+      expect(tf.getNamespaceStats()).toEqual({
+        "map/": { frames: 1, mutations: 0, rejected: 1 },
+        "plugin-a/": { frames: 2, mutations: 2, rejected: 0 },
+      });
+      tf.setNamespacePolicy("plugin-a/", undefined);
+      expect(Object.keys(tf.getNamespaceStats())).toEqual(["map/"]);
+    });
+
+    it("does not count rolled-back or validateOnly mutations", () => {
+      tf.setNamespacePolicy("map/", { readOnly: true });
+      tf.setNamespacePolicy("plugin-a/", {});
+      tf.begin();
+      tf.updateTransform("plugin-a/robot", translate(1, 0, 0));
+      tf.setNamespacePolicy("plugin-a/", { maxFrames: 1 });
+      expect(() => tf.updateTransform("map/origin", translate(1, 0, 0))).toThrow();
+      tf.rollback();
+      tf.updateTransforms({ "plugin-a/robot": translate(2, 0, 0) }, { validateOnly: true });
+
+      expect(tf.getNamespacePolicies()).toEqual({ "map/": { readOnly: true }, "plugin-a/": {} });
+      expect(tf.getNamespaceStats()).toEqual({
+        "map/": { frames: 1, mutations: 0, rejected: 1 },
+        "plugin-a/": { frames: 1, mutations: 0, rejected: 0 },
+      });
+    });
+  });
+
+  // ── aliases ──────────────────────────────────────────────────────────────────
+
+  describe("aliases", () => {
+    beforeEach(() => {
+      tf.addFrame("world");
+      tf.addFrame("base_link", "world", translate(1, 0, 0));
+      tf.addAlias("base_footprint", "base_link");
+    });
+
+    it("resolves aliases transparently in lookups and updates", () => {
+      expect(tf.hasFrame("base_footprint")).toBe(true);
+      tf.addFrame("laser", "base_footprint", translate(0, 0, 1));
+      tf.updateTransform("base_footprint", translate(2, 0, 0));
+      expect(tf.getTransform("world", "laser").translation.equals(new Vec3(2, 0, 1))).toBe(true);
+      expect(tf.traverse("base_footprint")).toEqual(["base_link", "laser"]);
+      expect(tf.resolveAlias("base_footprint")).toBe("base_link");
+      expect(tf.resolveAlias("laser")).toBe("laser");
+    });
+
+    it("lists, removes and rejects conflicting aliases", () => {
+      tf.addAlias("footprint", "base_footprint");
+      expect(tf.getAliases()).toEqual({ base_footprint: "base_link", footprint: "base_link" });
+      expect(() => tf.addAlias("world", "base_link")).toThrow(/already registered/);
+      expect(() => tf.addFrame("footprint")).toThrow(/already registered/);
+      expect(() => tf.addAlias("ghost_alias", "ghost")).toThrow(/not found/);
+
+      tf.removeAlias("footprint");
+      expect(tf.hasFrame("footprint")).toBe(false);
+      expect(() => tf.removeAlias("footprint")).toThrow(/not found/);
+    });
+
+    it("aliases follow renames and disappear with their frame", () => {
+      tf.renameFrame("base_link", "base");
+      expect(tf.getAliases()).toEqual({ base_footprint: "base" });
+      tf.removeFrame("base_footprint");
+      expect(tf.hasFrame("base")).toBe(false);
+      expect(tf.getAliases()).toEqual({});
+    });
+  });
+
+  // ── diff / applyPatch ────────────────────────────────────────────────────────
+
+  describe("diff() / applyPatch()", () => {
+    it("reports added, removed, reparented and moved frames", () => {
+      tf.addFrame("world");
+      tf.addFrame("a", "world", translate(1, 0, 0));
+      tf.addFrame("b", "world");
+      tf.addFrame("gone", "a");
+
+      const other = new TFTree();
+      other.addFrame("world");
+      other.addFrame("a", "world", translate(2, 0, 0));
+      other.addFrame("b", "a");
+      other.addFrame("new", "b");
+
+      const changes = tf.diff(other);
+      expect(changes.map((c) => [c.type, c.id])).toEqual([
+        ["added", "new"],
+        ["reparented", "b"],
+        ["moved", "a"],
+        ["removed", "gone"],
+      ]);
+      expect(other.diff(other)).toEqual([]);
+    });
+
+    it("applyPatch() reconciles this tree with the diff target", () => {
+      tf.addFrame("world");
+      tf.addFrame("a", "world", translate(1, 0, 0));
+      tf.addFrame("b", "a", translate(0, 1, 0));
+      tf.addFrame("old", "b");
+      tf.addFrame("old-leaf", "old");
+
+      // Swapping a and b is only valid if b is detached from a first.
+      const other = new TFTree();
+      other.addFrame("world");
+      other.addFrame("b", "world", translate(0, 2, 0));
+      other.addFrame("a", "b", translate(1, 0, 0));
+      other.addFrame("fresh", "a");
+      other.addFrame("fresh-leaf", "fresh");
+
+      tf.applyPatch(tf.diff(other));
+      expect(tf.diff(other)).toEqual([]);
+      expect(tf.getTransform("world", "a").translation.equals(new Vec3(1, 2, 0))).toBe(true);
+    });
+
+    it("applyPatch() is atomic", () => {
+      tf.addFrame("world");
+      tf.addFrame("robot", "world");
+      expect(() =>
+        tf.applyPatch([
+          {
+            type: "moved",
+            id: "robot",
+            transform: { translation: [1, 0, 0], rotation: [0, 0, 0, 1] },
+          },
+          { type: "removed", id: "ghost" },
+        ]),
+      ).toThrow(/not found/);
+      expect(tf.getLocalTransform("robot").equals(Transform.identity())).toBe(true);
+    });
+
+    it("applyPatch() does not reparent locked frames", () => {
+      tf.addFrame("world");
+      tf.addFrame("table", "world");
+      tf.addFrame("camera", "world", translate(1, 0, 0));
+      tf.lockFrame("camera");
+      const reparented: TreeChange = {
+        type: "reparented",
+        id: "camera",
+        parentId: "table",
+        transform: { translation: [0, 0, 0], rotation: [0, 0, 0, 1] },
+      };
+      expect(() => tf.applyPatch([reparented])).toThrowError(FrameLockedError);
+      expect(tf.getTransform("table", "camera").translation.equals(new Vec3(1, 0, 0))).toBe(true);
+    });
+  });
+
+  // ── merge ────────────────────────────────────────────────────────────────────
+
+  describe("merge()", () => {
+    let now: number;
+    const replica = () => {
+      const tree = new TFTree();
+      tree.setClock(() => now);
+      tree.addFrame("map");
+      tree.addFrame("robot1/base", "map");
+      tree.addFrame("robot2/base", "map");
+      return tree;
+    };
+    // Two replicas edited offline: each changes a frame the other changed earlier.
+    const diverged = () => {
+      now = 0;
+      const [a, b] = [replica(), replica()];
+      now = 1;
+      a.updateTransform("robot1/base", translate(1, 0, 0));
+      now = 2;
+      b.updateTransform("robot1/base", translate(2, 0, 0));
+      b.updateTransform("robot2/base", translate(0, 2, 0));
+      b.addFrame("robot2/arm", "robot2/base");
+      now = 3;
+      a.updateTransform("robot2/base", translate(0, 3, 0));
+      return [a, b];
+    };
+
+    it("adds missing frames and lets the latest modification win", () => {
+      const [a, b] = diverged();
+      const report = a.merge(b);
+      expect(report.added).toEqual(["robot2/arm"]);
+      expect(report.conflicts.map((c) => [c.frameId, c.winner, c.reason])).toEqual([
+        ["robot1/base", "theirs", "timestamp"],
+        ["robot2/base", "ours", "timestamp"],
+      ]);
+      expect(report.conflicts[0].ours.transform.translation).toEqual([1, 0, 0]);
+      expect(a.getLocalTransform("robot1/base").equals(translate(2, 0, 0))).toBe(true);
+      expect(a.getLocalTransform("robot2/base").equals(translate(0, 3, 0))).toBe(true);
+      expect(a.getModifiedAt("robot1/base")).toBe(2);
+    });
+
+    it("converges whichever replica is merged into the other", () => {
+      const [a1, b1] = diverged();
+      const [a2, b2] = diverged();
+      a1.merge(b1);
+      b2.merge(a2);
+      expect(a1.diff(b2)).toEqual([]);
+
+      // Simultaneous edits are broken deterministically too.
+      now = 5;
+      a1.updateTransform("map", translate(1, 0, 0));
+      b2.updateTransform("map", translate(2, 0, 0));
+      const [tie] = a1.merge(b2).conflicts;
+      expect(tie).toMatchObject({ frameId: "map", winner: "theirs", reason: "tie" });
+      expect(b2.merge(a1).conflicts).toEqual([]);
+    });
+
+    it("gives namespace owners precedence and keeps locked frames", () => {
+      const [a, b] = diverged();
+      a.lockFrame("robot1/base");
+      const report = a.merge(b, { namespaces: { "robot2/": "theirs" } });
+      expect(report.conflicts.map((c) => [c.frameId, c.winner, c.reason])).toEqual([
+        ["robot1/base", "ours", "locked"],
+        ["robot2/base", "theirs", "namespace"],
+      ]);
+      expect(a.getLocalTransform("robot1/base").equals(translate(1, 0, 0))).toBe(true);
+      expect(a.getLocalTransform("robot2/base").equals(translate(0, 2, 0))).toBe(true);
+    });
+
+    it("keeps this tree's side when the other side would create a cycle", () => {
+      now = 0;
+      const [a, b] = [new TFTree(), new TFTree()];
+      for (const tree of [a, b]) {
+        tree.setClock(() => now);
+        tree.addFrame("map");
+        tree.addFrame("x", "map");
+        tree.addFrame("y", "x");
+      }
+      now = 1;
+      b.setParent("y", "map");
+      b.setParent("x", "y");
+      now = 2;
+      a.updateTransform("y", translate(0, 0, 1));
+
+      const report = a.merge(b);
+      expect(report.conflicts.map((c) => [c.frameId, c.winner, c.reason])).toEqual([
+        ["y", "ours", "timestamp"],
+        ["x", "ours", "cycle"],
+      ]);
+      expect(a.traverse("map")).toEqual(["map", "x", "y"]);
+    });
+  });
+
+  describe("fork()", () => {
+    beforeEach(() => {
+      tf.addFrame("world");
+      tf.addFrame("robot", "world", translate(1, 0, 0));
+      tf.addFrame("arm", "robot", translate(0, 1, 0));
+    });
+
+    it("records edits without touching the base tree", () => {
+      const fork = tf.fork();
+      fork.updateTransform("robot", translate(5, 0, 0));
+      fork.addFrame("gripper", "arm");
+
+      expect(fork.isFork()).toBe(true);
+      expect(tf.isFork()).toBe(false);
+      expect(fork.getTransform("world", "gripper").translation.x).toBeCloseTo(5);
+      expect(tf.hasFrame("gripper")).toBe(false);
+      expect(tf.getTransform("world", "robot").translation.x).toBeCloseTo(1);
+      expect(fork.pendingEdits().map((op) => op.op)).toEqual(["update", "add"]);
+    });
+
+    it("promote() applies the edits to the base and resets the fork", () => {
+      const fork = tf.fork();
+      fork.updateTransform("robot", translate(5, 0, 0));
+      fork.addFrame("gripper", "arm");
+      fork.promote();
+
+      expect(tf.getTransform("world", "gripper").translation.toArray()).toEqual([5, 1, 0]);
+      expect(fork.pendingEdits()).toEqual([]);
+      expect(fork.toJSON()).toEqual(tf.toJSON());
+    });
+
+    it("promote() is atomic and keeps the edits when the base has diverged", () => {
+      const fork = tf.fork();
+      fork.updateTransform("robot", translate(5, 0, 0));
+      fork.updateTransform("arm", translate(0, 5, 0));
+      tf.removeFrame("arm");
+
+      expect(() => fork.promote()).toThrow(FrameNotFoundError);
+      expect(tf.getTransform("world", "robot").translation.x).toBeCloseTo(1);
+      expect(fork.pendingEdits()).toHaveLength(2);
+    });
+
+    it("discard() resets the fork to the base and notifies moved frames", () => {
+      const fork = tf.fork();
+      const moved: string[] = [];
+      fork.onChange("arm", (id) => moved.push(id));
+      fork.updateTransform("robot", translate(5, 0, 0));
+      fork.addFrame("gripper", "arm");
+      moved.length = 0;
+
+      fork.discard();
+      expect(moved).toEqual(["arm"]);
+      expect(fork.hasFrame("gripper")).toBe(false);
+      expect(fork.pendingEdits()).toEqual([]);
+      expect(fork.toJSON()).toEqual(tf.toJSON());
+    });
+
+    it("drops edits rolled back on the fork", () => {
+      const fork = tf.fork();
+      fork.updateTransform("robot", translate(5, 0, 0));
+      expect(() =>
+        fork.transaction(() => {
+          fork.addFrame("gripper", "arm");
+          fork.addFrame("gripper", "arm");
+        }),
+      ).toThrow(DuplicateFrameError);
+      expect(fork.pendingEdits().map((op) => op.op)).toEqual(["update"]);
+    });
+
+    it("throws INVALID_STATE for a tree that is not a fork", () => {
+      let err: unknown;
+      try {
+        tf.promote();
+      } catch (e) {
+        err = e;
+      }
+      expect(err).toBeInstanceOf(TFError);
+      expect(err).toMatchObject({ code: "INVALID_STATE", context: { operation: "promote" } });
+      expect(() => tf.discard()).toThrow(TFError);
+    });
+  });
+
+  // ── removeSubtree ────────────────────────────────────────────────────────────
+
+  it("removeSubtree() removes a frame and all descendants, children first", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.addFrame("arm", "robot");
+    tf.addFrame("hand", "arm");
+    tf.addFrame("leg", "robot");
+    tf.addFrame("table", "world");
+
+    expect(tf.removeSubtree("robot")).toEqual(["hand", "arm", "leg", "robot"]);
+    expect(tf.frameIds()).toEqual(["world", "table"]);
+    expect(tf.subtreeSize("world")).toBe(2);
+  });
+
+  it("removeSubtree() on a leaf behaves like removeFrame()", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    expect(tf.removeSubtree("robot")).toEqual(["robot"]);
+    expect(tf.hasFrame("robot")).toBe(false);
+  });
+
+  it("removeSubtree() throws for an unknown frame", () => {
+    expect(() => tf.removeSubtree("ghost")).toThrow(/not found/);
+  });
+
+  it("getTransform() throws after a frame has been removed", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.removeFrame("robot");
+    expect(() => tf.getTransform("world", "robot")).toThrow(/not found/);
+  });
+
+  // ── removeFramesWithPrefix ───────────────────────────────────────────────────
+
+  it("removeFramesWithPrefix() removes a namespace children first", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot3/base", "world");
+    tf.addFrame("robot3/arm", "robot3/base");
+    tf.addFrame("robot3/camera", "robot3/base");
+    tf.addFrame("robot30/base", "world");
+    tf.addFrame("robot4/base", "world");
+
+    expect(tf.removeFramesWithPrefix("robot3/")).toEqual([
+      "robot3/arm",
+      "robot3/camera",
+      "robot3/base",
+    ]);
+    expect(tf.frameIds()).toEqual(["world", "robot30/base", "robot4/base"]);
+    expect(tf.removeFramesWithPrefix("robot9/")).toEqual([]);
+  });
+
+  it("removeFramesWithPrefix() removes nothing if a match has a foreign child", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot3/base", "world");
+    tf.addFrame("payload", "robot3/base");
+    expect(() => tf.removeFramesWithPrefix("robot3/")).toThrow(/does not match prefix/);
+    expect(tf.frameIds()).toEqual(["world", "robot3/base", "payload"]);
+  });
+
+  // ── revision ─────────────────────────────────────────────────────────────────
+
+  it("revision() increases on every structural or transform mutation", () => {
+    expect(tf.revision()).toBe(0);
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    const afterAdd = tf.revision();
+    expect(afterAdd).toBe(2);
+
+    tf.hasFrame("robot");
+    tf.getTransform("world", "robot");
+    expect(tf.revision()).toBe(afterAdd);
+
+    tf.updateTransform("robot", translate(1, 0, 0));
+    tf.setParent("robot");
+    tf.renameFrame("robot", "robot1");
+    tf.removeFrame("robot1");
+    expect(tf.revision()).toBe(afterAdd + 4);
+  });
+
+  // ── getVersion ───────────────────────────────────────────────────────────────
+
+  it("getVersion() increases when the frame or an ancestor changes", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.addFrame("camera", "robot");
+    tf.addFrame("tree", "world");
+
+    const camera = tf.getVersion("camera");
+    const tree = tf.getVersion("tree");
+    tf.updateTransform("robot", translate(1, 0, 0));
+    expect(tf.getVersion("camera")).toBeGreaterThan(camera);
+    expect(tf.getVersion("tree")).toBe(tree);
+
+    const beforeRollback = tf.getVersion("camera");
+    tf.begin();
+    tf.updateTransform("camera", translate(0, 0, 1));
+    tf.rollback();
+    expect(tf.getVersion("camera")).toBeGreaterThan(beforeRollback);
+  });
+
+  it("getVersion() never repeats for a re-registered frame", () => {
+    tf.addFrame("world");
+    const first = tf.getVersion("world");
+    tf.removeFrame("world");
+    tf.addFrame("world");
+    expect(tf.getVersion("world")).toBeGreaterThan(first);
+    expect(() => tf.getVersion("ghost")).toThrow(/not found/);
+  });
+
+  // ── caching & dirty flags ────────────────────────────────────────────────────
+
+  it("getTransform() returns consistent results on repeated calls (cache correctness)", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(2, 0, 0));
+    tf.addFrame("camera", "robot", translate(0, 0, 1));
+
+    const t1 = tf.getTransform("world", "camera");
+    const t2 = tf.getTransform("world", "camera");
+
+    expect(t1.equals(t2)).toBe(true);
+    expect(t1.transformPoint(Vec3.zero()).equals(new Vec3(2, 0, 1))).toBe(true);
+  });
+
+  it("updateTransform() invalidates cached world transform of descendants", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.addFrame("camera", "robot", translate(0, 0, 1));
+
+    // Warm the cache.
+    expect(
+      tf
+        .getTransform("world", "camera")
+        .transformPoint(Vec3.zero())
+        .equals(new Vec3(1, 0, 1)),
+    ).toBe(true);
+
+    // Move the robot – camera should follow.
+    tf.updateTransform("robot", translate(5, 0, 0));
+
+    expect(
+      tf
+        .getTransform("world", "camera")
+        .transformPoint(Vec3.zero())
+        .equals(new Vec3(5, 0, 1)),
+    ).toBe(true);
+  });
+
+  it("updateTransform() only dirties the updated subtree, not unrelated frames", () => {
+    tf.addFrame("world");
+    tf.addFrame("arm", "world", translate(1, 0, 0));
+    tf.addFrame("leg", "world", translate(0, 1, 0));
+
+    // Warm the cache for both branches.
+    expect(
+      tf
+        .getTransform("world", "arm")
+        .transformPoint(Vec3.zero())
+        .equals(new Vec3(1, 0, 0)),
+    ).toBe(true);
+    expect(
+      tf
+        .getTransform("world", "leg")
+        .transformPoint(Vec3.zero())
+        .equals(new Vec3(0, 1, 0)),
+    ).toBe(true);
+
+    // Update only arm.
+    tf.updateTransform("arm", translate(3, 0, 0));
+
+    // arm is updated; leg is unchanged.
+    expect(
+      tf
+        .getTransform("world", "arm")
+        .transformPoint(Vec3.zero())
+        .equals(new Vec3(3, 0, 0)),
+    ).toBe(true);
+    expect(
+      tf
+        .getTransform("world", "leg")
+        .transformPoint(Vec3.zero())
+        .equals(new Vec3(0, 1, 0)),
+    ).toBe(true);
+  });
+
+  // ── error handling ───────────────────────────────────────────────────────────
+
+  it("getTransform throws for unknown 'from' frame", () => {
+    tf.addFrame("world");
+    expect(() => tf.getTransform("ghost", "world")).toThrow(/not found/);
+  });
+
+  it("getTransform throws for unknown 'to' frame", () => {
+    tf.addFrame("world");
+    expect(() => tf.getTransform("world", "ghost")).toThrow(/not found/);
+  });
+
+  it("getTransform throws for disconnected frames", () => {
+    tf.addFrame("world");
+    tf.addFrame("island");
+    expect(() => tf.getTransform("world", "island")).toThrow(/not connected/);
+  });
+
+  // ── isConnected ──────────────────────────────────────────────────────────────
+
+  it("isConnected() is true for frames sharing a root", () => {
+    tf.addFrame("world");
+    tf.addFrame("arm", "world");
+    tf.addFrame("leg", "world");
+    tf.addFrame("hand", "arm");
+    expect(tf.isConnected("hand", "leg")).toBe(true);
+    expect(tf.isConnected("world", "hand")).toBe(true);
+    expect(tf.isConnected("world", "world")).toBe(true);
+  });
+
+  it("isConnected() is false for frames in different trees", () => {
+    tf.addFrame("world");
+    tf.addFrame("island");
+    tf.addFrame("reef", "island");
+    expect(tf.isConnected("world", "reef")).toBe(false);
+  });
+
+  it("isConnected() returns false instead of throwing for unknown frames", () => {
+    tf.addFrame("world");
+    expect(tf.isConnected("world", "ghost")).toBe(false);
+    expect(tf.isConnected("ghost", "ghost")).toBe(false);
+  });
+
+  // ── coveringSubtree ──────────────────────────────────────────────────────────
+
+  describe("coveringSubtree()", () => {
+    // world → robot → arm → hand
+    //               → base → wheel
+    //       → table
+    beforeEach(() => {
+      tf.addFrame("world");
+      tf.addFrame("robot", "world");
+      tf.addFrame("arm", "robot");
+      tf.addFrame("hand", "arm");
+      tf.addFrame("base", "robot");
+      tf.addFrame("wheel", "base");
+      tf.addFrame("table", "world");
+    });
+
+    it("roots the slice at the lowest common ancestor", () => {
+      expect(tf.coveringSubtree(["hand", "wheel"])).toEqual({
+        rootId: "robot",
+        frameIds: ["robot", "arm", "hand", "base", "wheel"],
+      });
+    });
+
+    it("includes an ancestor without widening the slice", () => {
+      expect(tf.coveringSubtree(["arm", "hand"])).toEqual({
+        rootId: "arm",
+        frameIds: ["arm", "hand"],
+      });
+    });
+
+    it("returns a single frame for a single id", () => {
+      expect(tf.coveringSubtree(["table"])).toEqual({ rootId: "table", frameIds: ["table"] });
+    });
+
+    it("throws for an empty list, unknown ids and disconnected frames", () => {
+      tf.addFrame("island");
+      expect(() => tf.coveringSubtree([])).toThrow(/at least one/);
+      expect(() => tf.coveringSubtree(["hand", "ghost"])).toThrow(/not found/);
+      expect(() => tf.coveringSubtree(["hand", "island"])).toThrow(/not connected/);
+    });
+  });
+
+  it("chainToRoot() detects a cycle via internal frame map manipulation", () => {
+    // Force a cycle by bypassing addFrame validation (direct map access via any cast)
+    tf.addFrame("world");
+    tf.addFrame("child", "world");
+    // Introduce cycle: child → world → child
+    (
+      tf as unknown as {
+        frames: Map<string, { id: string; parentId: string | undefined; transform: Transform }>;
+      }
+    ).frames.set("world", { id: "world", parentId: "child", transform: Transform.identity() });
+    expect(() => tf.getTransform("world", "child")).toThrow(/Cycle detected/);
+  });
+
+  // ── validate ─────────────────────────────────────────────────────────────────
+
+  describe("validate()", () => {
+    beforeEach(() => {
+      tf.addFrame("world");
+      tf.addFrame("robot", "world", translate(1, 0, 0));
+      tf.addFrame("camera", "robot", translate(0, 0, 1));
+      tf.getTransform("world", "camera");
+    });
+
+    it("reports a tree built through the public API as valid", () => {
+      tf.removeFrame("camera");
+      tf.renameFrame("robot", "rover");
+      expect(tf.validate()).toEqual({ valid: true, issues: [] });
+    });
+
+    it("collects every broken invariant instead of throwing", () => {
+      tf.updateTransform("camera", new Transform(Vec3.zero(), new Quaternion(0, 0, 0, 2)));
+      const internals = tf as unknown as {
+        frames: Map<string, { id: string; parentId?: string; transform: Transform }>;
+        worldTransformCache: Map<string, Transform>;
+      };
+      const robot = internals.frames.get("robot")!;
+      internals.frames.set("robot", { ...robot, parentId: "ghost" });
+      internals.worldTransformCache.set("gone", Transform.identity());
+
+      const { valid, issues } = tf.validate();
+      expect(valid).toBe(false);
+      expect(issues.map(({ kind, frameId }) => [kind, frameId])).toEqual([
+        ["children-mismatch", "world"],
+        ["missing-parent", "robot"],
+        ["children-mismatch", "robot"],
+        ["unnormalized-rotation", "camera"],
+        ["dangling-entry", "gone"],
+      ]);
+    });
+  });
+
+  // ── diagnose ─────────────────────────────────────────────────────────────────
+
+  it("diagnose() flags suspicious rotations, translations, depth and stale frames", () => {
+    tf.addFrame("world");
+    tf.addFrame("a", "world");
+    tf.addFrame("b", "a");
+    tf.addFrame("c", "b");
+    tf.updateTransforms({
+      a: new Transform(Vec3.zero(), new Quaternion(0, 0, 0, 0)),
+      b: new Transform(new Vec3(2e6, 0, 0), new Quaternion(0, 0, 0, 1.5)),
+    });
+
+    expect(tf.diagnose({ maxDepth: 2 }).map(({ kind, frameId }) => [kind, frameId])).toEqual([
+      ["degenerate-rotation", "a"],
+      ["unnormalized-rotation", "b"],
+      ["large-translation", "b"],
+      ["deep-chain", "c"],
+      ["never-updated", "c"],
+    ]);
+
+    tf.begin();
+    tf.updateTransform("c", translate(1, 0, 0));
+    expect(tf.diagnose().some(({ kind }) => kind === "never-updated")).toBe(false);
+    tf.rollback();
+    expect(tf.diagnose().filter(({ kind }) => kind === "never-updated")).toEqual([
+      {
+        kind: "never-updated",
+        severity: "info",
+        frameId: "c",
+        message: 'Frame "c" was never updated after registration.',
+      },
+    ]);
+  });
+
+  it("setDebugChecks() catches stale caches and degenerate transforms", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.addFrame("camera", "robot", translate(0, 0, 1));
+    tf.addFrame("map", "world");
+    tf.getTransform("world", "camera");
+    tf.setDebugChecks(true);
+    tf.updateTransform("map", translate(5, 0, 0));
+
+    // Bypass invalidation, leaving the cached poses of robot and camera stale.
+    const internals = tf as unknown as {
+      frames: Map<string, { id: string; parentId?: string; transform: Transform }>;
+    };
+    const robot = internals.frames.get("robot")!;
+    internals.frames.set("robot", { ...robot, transform: translate(2, 0, 0) });
+    let err: unknown;
+    try {
+      tf.updateTransform("map", translate(6, 0, 0));
+    } catch (e) {
+      err = e;
+    }
+    expect(err).toBeInstanceOf(TFError);
+    expect(err).toMatchObject({ code: "INVALID_STATE", context: { frameIds: ["robot"] } });
+
+    tf.setDebugChecks(false);
+    tf.updateTransform("robot", translate(2, 0, 0));
+    tf.setDebugChecks(true);
+    expect(() =>
+      tf.updateTransform("map", new Transform(Vec3.zero(), new Quaternion(0, 0, 0, 0))),
+    ).toThrow(/composed with its inverse/);
+  });
+
+  it("verifyCache() reports stale cached world transforms", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.addFrame("camera", "robot", translate(0, 0, 1));
+    tf.addFrame("map", "world");
+    tf.getTransform("map", "camera");
+    expect(tf.verifyCache()).toEqual([]);
+
+    const internals = tf as unknown as {
+      frames: Map<string, { id: string; parentId?: string; transform: Transform }>;
+    };
+    const robot = internals.frames.get("robot")!;
+    internals.frames.set("robot", { ...robot, transform: translate(2, 0, 0) });
+    const mismatches = tf.verifyCache();
+    expect(mismatches.map(({ frameId }) => frameId)).toEqual(["robot", "camera"]);
+    expect(mismatches[1].cached.translation.equals(new Vec3(1, 0, 1))).toBe(true);
+    expect(mismatches[1].expected.translation.equals(new Vec3(2, 0, 1))).toBe(true);
+
+    tf.updateTransform("robot", translate(2, 0, 0));
+    expect(tf.verifyCache()).toEqual([]);
+  });
+
+  it("debugDump() renders the hierarchy with transforms and flags", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.addFrame("lidar", "robot", translate(0, 0, 0.25));
+    tf.addFrame("camera", "robot");
+    tf.addFrame("map");
+    tf.getTransform("world", "robot");
+    tf.lockFrame("lidar");
+
+    expect(tf.debugDump({ precision: 2 }).split("\n")).toEqual([
+      "world t=(0.00, 0.00, 0.00) r=(0.00, 0.00, 0.00, 1.00)",
+      "└─ robot t=(1.00, 0.00, 0.00) r=(0.00, 0.00, 0.00, 1.00)",
+      "   ├─ lidar t=(0.00, 0.00, 0.25) r=(0.00, 0.00, 0.00, 1.00) [dirty, locked]",
+      "   └─ camera t=(0.00, 0.00, 0.00) r=(0.00, 0.00, 0.00, 1.00) [dirty]",
+      "map t=(0.00, 0.00, 0.00) r=(0.00, 0.00, 0.00, 1.00) [dirty]",
+    ]);
+  });
+
+  // ── CycleDetectedError ──────────────────────────────────────────────────────
+
+  it("getTransform() throws CycleDetectedError instance on cycle", () => {
+    tf.addFrame("world");
+    tf.addFrame("child", "world");
     (
       tf as unknown as {
         frames: Map<string, { id: string; parentId: string | undefined; transform: Transform }>;
       }
     ).frames.set("world", { id: "world", parentId: "child", transform: Transform.identity() });
-    expect(() => tf.getTransform("world", "child")).toThrow(/Cycle detected/);
+    expect(() => tf.getTransform("world", "child")).toThrowError(CycleDetectedError);
+  });
+
+  it("CycleDetectedError has the correct name", () => {
+    const err = new CycleDetectedError("someFrame");
+    expect(err.name).toBe("CycleDetectedError");
+    expect(err.message).toMatch(/someFrame/);
+    expect(err.frameId).toBe("someFrame");
+  });
+
+  // ── typed errors ─────────────────────────────────────────────────────────────
+
+  it("throws typed errors carrying the offending frame ids", () => {
+    tf.addFrame("world");
+    tf.addFrame("map");
+
+    const capture = (fn: () => unknown): unknown => {
+      try {
+        fn();
+      } catch (err) {
+        return err;
+      }
+      return undefined;
+    };
+    const missing = capture(() => tf.getTransform("world", "ghost"));
+    expect(missing).toBeInstanceOf(FrameNotFoundError);
+    expect((missing as FrameNotFoundError).frameId).toBe("ghost");
+    const parent = capture(() => tf.addFrame("robot", "base"));
+    expect((parent as FrameNotFoundError).frameId).toBe("base");
+
+    const duplicate = capture(() => tf.addFrame("world"));
+    expect(duplicate).toBeInstanceOf(DuplicateFrameError);
+    expect((duplicate as DuplicateFrameError).frameId).toBe("world");
+
+    const disconnected = capture(() => tf.getTransform("world", "map"));
+    expect(disconnected).toBeInstanceOf(DisconnectedFramesError);
+    expect(disconnected).toMatchObject({ frameA: "world", frameB: "map" });
+  });
+
+  it("rejects non-finite transforms unless input validation is off", () => {
+    tf.addFrame("world");
+    expect(() => tf.addFrame("robot", "world", translate(NaN, 0, 0))).toThrowError(
+      InvalidTransformError,
+    );
+    expect(tf.hasFrame("robot")).toBe(false);
+
+    tf.addFrame("robot", "world");
+    let err: unknown;
+    try {
+      tf.updateTransforms({ robot: new Transform(Vec3.zero(), new Quaternion(0, 0, Infinity, 1)) });
+    } catch (e) {
+      err = e;
+    }
+    expect(err).toBeInstanceOf(InvalidTransformError);
+    expect(err).toMatchObject({
+      code: "INVALID_TRANSFORM",
+      frameId: "robot",
+      component: "rotation.z",
+    });
+
+    tf.setInputValidation(false);
+    tf.updateTransform("robot", translate(Infinity, 0, 0));
+    expect(tf.getLocalTransform("robot").translation.x).toBe(Infinity);
+  });
+
+  it("enforces or repairs unit-norm rotations depending on the norm mode", () => {
+    const scaled = new Transform(Vec3.zero(), new Quaternion(0, 0, 0, 1.01));
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", scaled);
+
+    tf.setRotationNormMode("strict", 1e-3);
+    expect(() => tf.updateTransform("robot", scaled)).toThrow(/norm/);
+    tf.updateTransform("robot", new Transform(Vec3.zero(), new Quaternion(0, 0, 0, 1.0001)));
+
+    tf.setRotationNormMode("normalize");
+    tf.updateTransform("robot", scaled);
+    expect(tf.getLocalTransform("robot").rotation.w).toBeCloseTo(1, 6);
+    expect(() =>
+      tf.updateTransform("robot", new Transform(Vec3.zero(), new Quaternion(0, 0, 0, 0))),
+    ).toThrowError(InvalidTransformError);
+  });
+
+  it("enforces the frame id policy on addFrame() and renameFrame()", () => {
+    tf.addFrame("");
+    tf.setFrameIdPolicy({ maxLength: 8, pattern: /^[a-z_/]+$/, reservedPrefixes: ["sys/"] });
+    tf.addFrame("world");
+
+    expect(() => tf.addFrame("")).toThrow(/must not be empty/);
+    expect(() => tf.addFrame("a_very_long_id")).toThrow(/limited to 8/);
+    expect(() => tf.addFrame("bad id")).toThrowError(InvalidFrameIdError);
+    expect(() => tf.renameFrame("world", "sys/map")).toThrow(/"sys\/" is reserved/);
+    expect(tf.frameIds()).toEqual(["", "world"]);
+
+    tf.setFrameIdPolicy(undefined);
+    tf.addFrame("bad id");
+  });
+
+  it("normalizes ROS-style ids with setRosNaming()", () => {
+    tf.setRosNaming({});
+    tf.addFrame("/map");
+    expect(() => tf.addFrame("map")).toThrowError(DuplicateFrameError);
+
+    tf.setRosNaming({ tfPrefix: "/robot1/" });
+    tf.addFrame("base_link", "/map", translate(1, 0, 0));
+    tf.addFrame("robot1/camera", "base_link");
+    expect(tf.frameIds()).toEqual(["map", "robot1/base_link", "robot1/camera"]);
+    expect(tf.hasFrame("/robot1/base_link")).toBe(true);
+    const camera = tf.getTransform("/map", "camera");
+    expect(camera.equals(tf.getTransform("map", "/robot1/camera"))).toBe(true);
+    const view = tf.snapshot();
+    expect(view.getTransform("map", "camera").equals(camera)).toBe(true);
+    expect(view.resolveAlias("base_link")).toBe("robot1/base_link");
+
+    // Once a prefixed twin exists, relative ids find it, and internal paths
+    // keep addressing the shared frame by its registered id.
+    tf.addFrame("odom", "/map");
+    tf.addFrame("/odom", "/map");
+    tf.addFrame("map", "/map");
+    expect(tf.resolveAlias("map")).toBe("robot1/map");
+    tf.integrateTwist("/map", new Vec3(1, 0, 0), Vec3.zero(), 1);
+    expect(tf.getLocalTransform("/map").equals(translate(1, 0, 0))).toBe(true);
+    expect(tf.getLocalTransform("map").equals(Transform.identity())).toBe(true);
+    expect(tf.removeSubtree("/odom")).toEqual(["odom"]);
+    expect(tf.hasFrame("robot1/odom")).toBe(true);
+
+    tf.setRosNaming(undefined);
+    expect(tf.hasFrame("base_link")).toBe(false);
+  });
+
+  it("rewrites id prefixes on ingestion and lookup with setRemap()", () => {
+    tf.setRemap("robot/", "sim2/robot/");
+    tf.setRemap("robot/sensors/", "sim2/sensors/");
+    tf.addFrame("world");
+    tf.addFrame("robot/base", "world", translate(1, 0, 0));
+    tf.addFrame("robot/sensors/lidar", "robot/base", translate(0, 0, 1));
+    expect(tf.frameIds()).toEqual(["world", "sim2/robot/base", "sim2/sensors/lidar"]);
+    const lidar = tf.getTransform("world", "robot/sensors/lidar");
+    expect(lidar.translation.equals(new Vec3(1, 0, 1))).toBe(true);
+    expect(tf.getRemaps()).toEqual({ "robot/": "sim2/robot/", "robot/sensors/": "sim2/sensors/" });
+
+    tf.setRemap("robot/", undefined);
+    tf.setRemap("robot/sensors/", undefined);
+    expect(tf.hasFrame("robot/base")).toBe(false);
+    expect(tf.hasFrame("sim2/robot/base")).toBe(true);
+  });
+
+  it("loads unknown frames on demand from a frame resolver", () => {
+    const requested: string[] = [];
+    const map: Record<string, { parentId?: string; transform: Transform }> = {
+      map: { transform: Transform.identity() },
+      "map/tile_3": { parentId: "map", transform: translate(30, 0, 0) },
+      "map/tile_3/door": { parentId: "map/tile_3", transform: translate(0, 2, 0) },
+    };
+    tf.setFrameResolver((id) => {
+      requested.push(id);
+      return map[id];
+    });
+    tf.addFrame("robot", "map/tile_3", translate(1, 0, 0));
+
+    expect(tf.frameIds()).toEqual(["map", "map/tile_3", "robot"]);
+    expect(tf.getTransform("robot", "map/tile_3/door").translation.toArray()).toEqual([-1, 2, 0]);
+    expect(requested).toEqual(["map/tile_3", "map", "map/tile_3/door"]);
+    expect(tf.hasFrame("map/tile_9")).toBe(false);
+    expect(() => tf.getTransform("robot", "map/tile_9")).toThrow(FrameNotFoundError);
+    expect(() => tf.addFrame("ghost", "nowhere")).toThrow(/Register parents before children/);
+  });
+
+  it("loads unknown frames on demand from a secondary tree", () => {
+    const statics = new TFTree();
+    statics.addFrame("map");
+    statics.addFrame("dock", "map", translate(5, 0, 0));
+    tf.setFrameResolver(statics);
+
+    expect(tf.getTransform("map", "dock").translation.toArray()).toEqual([5, 0, 0]);
+    expect(tf.frameIds()).toEqual(["map", "dock"]);
+    tf.setFrameResolver(undefined);
+    expect(() => tf.getLocalTransform("elsewhere")).toThrow(FrameNotFoundError);
+  });
+
+  it("enforces resource quotas with QuotaExceededError", () => {
+    tf.setQuotas({ maxFrames: 4, maxChildren: 2, maxDepth: 2, maxBatchSize: 1 });
+    tf.addFrame("world");
+    tf.addFrame("a", "world");
+    tf.addFrame("b", "world");
+    expect(() => tf.addFrame("c", "world")).toThrow(/maxChildren is limited to 2/);
+    tf.addFrame("a1", "a");
+    expect(() => tf.addFrame("extra")).toThrowError(QuotaExceededError);
+    expect(tf.frameIds()).toEqual(["world", "a", "b", "a1"]);
+
+    let err: unknown;
+    try {
+      tf.setParent("a", "b");
+    } catch (e) {
+      err = e;
+    }
+    expect(err).toMatchObject({ code: "QUOTA_EXCEEDED", quota: "maxDepth", limit: 2 });
+    expect(tf.traverse("b")).toEqual(["b"]);
+    expect(() =>
+      tf.updateTransforms({ a: translate(1, 0, 0), b: translate(2, 0, 0) }),
+    ).toThrow(/maxBatchSize/);
+
+    tf.setQuotas({});
+    tf.addFrame("extra");
+  });
+
+  it("validateOnly batches run every check but apply nothing", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.lockFrame("world");
+    const batches: string[][] = [];
+    tf.onAnyChange((ids) => batches.push(ids));
+    const revision = tf.revision();
+    const version = tf.getVersion("robot");
+
+    tf.updateTransforms({ robot: translate(1, 0, 0) }, { validateOnly: true });
+    const updates = { robot: translate(1, 0, 0), world: translate(2, 0, 0) };
+    expect(() => tf.updateTransforms(updates, { validateOnly: true })).toThrowError(
+      FrameLockedError,
+    );
+    const added: TreeChange = {
+      type: "added",
+      id: "camera",
+      parentId: "robot",
+      transform: { translation: [0, 0, 0], rotation: [0, 0, 0, 1] },
+    };
+    tf.applyPatch([added], { validateOnly: true });
+    expect(() =>
+      tf.applyPatch([added, { type: "removed", id: "ghost" }], { validateOnly: true }),
+    ).toThrow(/not found/);
+
+    expect(tf.hasFrame("camera")).toBe(false);
+    expect(tf.getLocalTransform("robot").translation.equals(Vec3.zero())).toBe(true);
+    expect(batches).toEqual([]);
+    expect(tf.revision()).toBeGreaterThan(revision);
+    expect(tf.getVersion("robot")).toBe(version);
+    const afterDryRuns = tf.revision();
+    tf.updateTransform("robot", translate(1, 0, 0));
+    expect(tf.revision()).toBeGreaterThan(afterDryRuns);
+  });
+
+  it("rejects validateOnly batches inside a transaction", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.begin();
+    let err: unknown;
+    try {
+      tf.updateTransforms({ robot: translate(1, 0, 0) }, { validateOnly: true });
+    } catch (e) {
+      err = e;
+    }
+    expect(err).toMatchObject({
+      code: "INVALID_STATE",
+      context: { operation: "updateTransforms" },
+    });
+    expect(() => tf.applyPatch([{ type: "removed", id: "robot" }], { validateOnly: true })).toThrow(
+      /not available inside a transaction/,
+    );
+    expect(tf.inTransaction()).toBe(true);
+    tf.commit();
+    expect(tf.hasFrame("robot")).toBe(true);
+  });
+
+  it("every error carries a stable code and structured context", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+
+    const capture = (fn: () => unknown): TFError | undefined => {
+      try {
+        fn();
+      } catch (err) {
+        return err as TFError;
+      }
+      return undefined;
+    };
+    const codeOf = (fn: () => unknown) => capture(fn)?.code;
+    expect(codeOf(() => tf.getTransform("world", "ghost"))).toBe("FRAME_NOT_FOUND");
+    expect(codeOf(() => tf.addFrame("robot"))).toBe("DUPLICATE_FRAME");
+    expect(codeOf(() => tf.setParent("world", "robot"))).toBe("CYCLE_DETECTED");
+    expect(codeOf(() => tf.removeAlias("ghost"))).toBe("ALIAS_NOT_FOUND");
+    expect(codeOf(() => tf.eventsSince(-1))).toBe("OUT_OF_RANGE");
+
+    const err = capture(() => tf.removeFrame("world"));
+    expect(err).toBeInstanceOf(TFError);
+    expect(err?.code).toBe("INVALID_STATE");
+    expect(err?.context).toEqual({ frameIds: ["world"], operation: "removeFrame" });
+  });
+
+  // ── toJSON / fromJSON ────────────────────────────────────────────────────────
+
+  it("toJSON() produces a plain object with all registered frames", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 2, 3));
+    const json = tf.toJSON();
+    expect(json.frames).toHaveLength(2);
+    expect(json.frames[0].id).toBe("world");
+    expect(json.frames[0].parentId).toBeNull();
+    expect(json.frames[1].id).toBe("robot");
+    expect(json.frames[1].parentId).toBe("world");
+    expect(json.frames[1].transform.translation).toEqual([1, 2, 3]);
+  });
+
+  it("toJSON() serializes rotation correctly", () => {
+    tf.addFrame("world");
+    tf.addFrame("rotated", "world", rotate90Z());
+    const json = tf.toJSON();
+    const q = json.frames[1].transform.rotation;
+    expect(q).toHaveLength(4);
+    // Rotation quaternion for 90° around Z: x≈0, y≈0, z≈0.707, w≈0.707
+    expect(Math.abs(q[2])).toBeCloseTo(Math.SQRT2 / 2, 5);
+    expect(Math.abs(q[3])).toBeCloseTo(Math.SQRT2 / 2, 5);
+  });
+
+  it("fromJSON() reconstructs a tree with equivalent getTransform results", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.addFrame("camera", "robot", translate(0, 0, 1));
+
+    const restored = TFTree.fromJSON(tf.toJSON());
+
+    expect(restored.hasFrame("world")).toBe(true);
+    expect(restored.hasFrame("robot")).toBe(true);
+    expect(restored.hasFrame("camera")).toBe(true);
+
+    const original = tf.getTransform("world", "camera");
+    const copy = restored.getTransform("world", "camera");
+    expect(copy.transformPoint(Vec3.zero()).equals(original.transformPoint(Vec3.zero()))).toBe(
+      true,
+    );
+  });
+
+  it("fromJSON() handles a root frame with null parentId", () => {
+    tf.addFrame("island");
+    const restored = TFTree.fromJSON(tf.toJSON());
+    expect(restored.hasFrame("island")).toBe(true);
+    expect(restored.frameIds()).toEqual(["island"]);
+  });
+
+  it("toJSON() followed by fromJSON() round-trips a rotation transform", () => {
+    tf.addFrame("world");
+    tf.addFrame("rotated", "world", rotate90Z());
+
+    const restored = TFTree.fromJSON(tf.toJSON());
+    const original = tf.getTransform("world", "rotated");
+    const copy = restored.getTransform("world", "rotated");
+    expect(
+      copy.transformPoint(new Vec3(1, 0, 0)).equals(original.transformPoint(new Vec3(1, 0, 0))),
+    ).toBe(true);
+  });
+
+  it("fromJSON() preserves parent-child relationships", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(5, 0, 0));
+    tf.addFrame("sensor", "robot", translate(0, 1, 0));
+
+    const restored = TFTree.fromJSON(tf.toJSON());
+    const t = restored.getTransform("world", "sensor");
+    expect(t.transformPoint(Vec3.zero()).equals(new Vec3(5, 1, 0))).toBe(true);
+  });
+
+  it("fromJSON() throws when frame data references an unknown parent", () => {
+    expect(() =>
+      TFTree.fromJSON({
+        frames: [
+          {
+            id: "child",
+            parentId: "missing",
+            transform: { translation: [0, 0, 0], rotation: [0, 0, 0, 1] },
+          },
+        ],
+      }),
+    ).toThrow(/not found/);
+  });
+
+  it("fromJSON() throws on duplicate frame ids", () => {
+    expect(() =>
+      TFTree.fromJSON({
+        frames: [
+          {
+            id: "world",
+            parentId: null,
+            transform: { translation: [0, 0, 0], rotation: [0, 0, 0, 1] },
+          },
+          {
+            id: "world",
+            parentId: null,
+            transform: { translation: [0, 0, 0], rotation: [0, 0, 0, 1] },
+          },
+        ],
+      }),
+    ).toThrow(/already registered/);
+  });
+
+  it("repairJSON() fixes dangling parents, cycles and duplicates", () => {
+    const identity: TransformJSON = { translation: [0, 0, 0], rotation: [0, 0, 0, 1] };
+    const { data, repairs } = TFTree.repairJSON({
+      frames: [
+        { id: "arm", parentId: "base", transform: identity },
+        { id: "base", parentId: null, transform: identity },
+        { id: "orphan", parentId: "missing", transform: identity },
+        { id: "a", parentId: "b", transform: identity },
+        { id: "b", parentId: "a", transform: identity },
+        { id: "base", parentId: "arm", transform: identity },
+      ],
+    });
+    expect(repairs).toEqual([
+      { type: "duplicate-dropped", id: "base", parentId: "arm" },
+      { type: "dangling-parent", id: "orphan", parentId: "missing" },
+      { type: "cycle-broken", id: "a", parentId: "b" },
+    ]);
+    const restored = TFTree.fromJSON(data);
+    expect(restored.frameIds().sort()).toEqual(["a", "arm", "b", "base", "orphan"]);
+    expect(restored.traverse("arm")).toEqual(["arm"]);
+    expect(restored.traverse("a")).toEqual(["a", "b"]);
+  });
+
+  it("repairJSON() leaves valid data unchanged apart from ordering", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    const { data, repairs } = TFTree.repairJSON(tf.toJSON());
+    expect(repairs).toEqual([]);
+    expect(data).toEqual(tf.toJSON());
+  });
+
+  it("toJSON() on an empty tree returns an empty frames array", () => {
+    const json = tf.toJSON();
+    expect(json.frames).toHaveLength(0);
+  });
+
+  // ── getComponents ────────────────────────────────────────────────────────────
+
+  it("getComponents() returns a single component for a connected tree", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    expect(tf.getComponents()).toEqual([{ rootId: "world", frameIds: ["world", "robot"] }]);
+  });
+
+  it("getComponents() groups frames of a forest by root", () => {
+    tf.addFrame("map");
+    tf.addFrame("odom", "map");
+    tf.addFrame("base_link");
+    tf.addFrame("laser", "base_link");
+    expect(tf.getComponents()).toEqual([
+      { rootId: "map", frameIds: ["map", "odom"] },
+      { rootId: "base_link", frameIds: ["base_link", "laser"] },
+    ]);
+  });
+
+  it("getComponents() returns an empty array for an empty tree", () => {
+    expect(tf.getComponents()).toEqual([]);
+  });
+
+  // ── framesWithinRadius ───────────────────────────────────────────────────────
+
+  it("framesWithinRadius() returns frames whose origins are inside the sphere", () => {
+    tf.addFrame("world");
+    tf.addFrame("near", "world", translate(1, 0, 0));
+    tf.addFrame("far", "world", translate(10, 0, 0));
+    tf.addFrame("child", "near", translate(0, 1, 0));
+    expect(tf.framesWithinRadius("world", Vec3.zero(), 1.5)).toEqual(["world", "near", "child"]);
+  });
+
+  it("framesWithinRadius() interprets the point in the reference frame", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(10, 0, 0));
+    tf.addFrame("tool", "robot", translate(0, 2, 0));
+    // (0, 2, 0) in robot coordinates is exactly the tool origin.
+    expect(tf.framesWithinRadius("robot", new Vec3(0, 2, 0), 0.1)).toEqual(["tool"]);
+  });
+
+  it("framesWithinRadius() ignores frames in other trees", () => {
+    tf.addFrame("world");
+    tf.addFrame("island");
+    expect(tf.framesWithinRadius("world", Vec3.zero(), 100)).toEqual(["world"]);
+  });
+
+  it("framesWithinRadius() throws for unknown reference frame", () => {
+    expect(() => tf.framesWithinRadius("ghost", Vec3.zero(), 1)).toThrow(/not found/);
+  });
+
+  // ── nearestFrames ────────────────────────────────────────────────────────────
+
+  it("nearestFrames() returns the k closest frames, nearest first", () => {
+    tf.addFrame("world");
+    tf.addFrame("a", "world", translate(3, 0, 0));
+    tf.addFrame("b", "world", translate(1, 0, 0));
+    tf.addFrame("c", "world", translate(2, 0, 0));
+    expect(tf.nearestFrames("world", new Vec3(1.9, 0, 0), 2)).toEqual(["c", "b"]);
+  });
+
+  it("nearestFrames() returns every candidate when k exceeds the tree size", () => {
+    tf.addFrame("world");
+    tf.addFrame("a", "world", translate(1, 0, 0));
+    tf.addFrame("island");
+    expect(tf.nearestFrames("world", Vec3.zero(), 10)).toEqual(["world", "a"]);
+    expect(tf.nearestFrames("world", Vec3.zero(), 0)).toEqual([]);
+  });
+
+  it("nearestFrames() throws for unknown reference frame", () => {
+    expect(() => tf.nearestFrames("ghost", Vec3.zero(), 1)).toThrow(/not found/);
+  });
+
+  // ── framesByDistance ─────────────────────────────────────────────────────────
+
+  it("framesByDistance() sorts frames by distance to the reference origin", () => {
+    tf.addFrame("world");
+    tf.addFrame("camera", "world", translate(1, 0, 0));
+    tf.addFrame("a", "world", translate(4, 0, 0));
+    tf.addFrame("b", "world", translate(1, 2, 0));
+
+    const result = tf.framesByDistance("camera");
+    expect(result.map(({ id }) => id)).toEqual(["world", "b", "a"]);
+    expect(result[0].distance).toBeCloseTo(1, 5);
+    expect(result[1].distance).toBeCloseTo(2, 5);
+    expect(result[2].distance).toBeCloseTo(3, 5);
+  });
+
+  it("framesByDistance() honours the limit", () => {
+    tf.addFrame("world");
+    tf.addFrame("a", "world", translate(1, 0, 0));
+    tf.addFrame("b", "world", translate(2, 0, 0));
+    expect(tf.framesByDistance("world", 1).map(({ id }) => id)).toEqual(["a"]);
+  });
+
+  it("framesByDistance() throws for unknown reference frame", () => {
+    expect(() => tf.framesByDistance("ghost")).toThrow(/not found/);
+  });
+
+  // ── distanceBetween / relativePosition ───────────────────────────────────────
+
+  it("distanceBetween() and relativePosition() measure between frame origins", () => {
+    tf.addFrame("world");
+    tf.addFrame("leader", "world", translate(4, 3, 0));
+    tf.addFrame("follower", "world", new Transform(Vec3.zero(), rotate90Z().rotation));
+
+    expect(tf.distanceBetween("follower", "leader")).toBeCloseTo(5, 5);
+    // The follower faces +y, so the leader is 3 ahead and 4 to its right.
+    expect(tf.relativePosition("follower", "leader").equals(new Vec3(3, -4, 0), 1e-5)).toBe(true);
+    const inWorld = tf.relativePosition("follower", "leader", "world");
+    expect(inWorld.equals(new Vec3(4, 3, 0), 1e-5)).toBe(true);
+    expect(() => tf.distanceBetween("world", "ghost")).toThrowError(FrameNotFoundError);
+  });
+
+  // ── subtreeAabb ──────────────────────────────────────────────────────────────
+
+  it("subtreeAabb() bounds the origins of a subtree", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(5, 0, 0));
+    tf.addFrame("left", "robot", translate(0, 1, 0));
+    tf.addFrame("right", "robot", translate(0, -1, 2));
+    tf.addFrame("other", "world", translate(100, 0, 0));
+
+    const box = tf.subtreeAabb("robot", "world");
+    expect(box.min.equals(new Vec3(5, -1, 0), 1e-5)).toBe(true);
+    expect(box.max.equals(new Vec3(5, 1, 2), 1e-5)).toBe(true);
+  });
+
+  it("subtreeAabb() is expressed in the reference frame", () => {
+    tf.addFrame("world");
+    tf.addFrame("rotated", "world", rotate90Z());
+    tf.addFrame("tip", "rotated", translate(1, 0, 0));
+
+    // The tip sits at (1, 0, 0) in rotated, i.e. (0, 1, 0) in world.
+    const box = tf.subtreeAabb("rotated", "world");
+    expect(box.min.equals(Vec3.zero(), 1e-5)).toBe(true);
+    expect(box.max.equals(new Vec3(0, 1, 0), 1e-5)).toBe(true);
+  });
+
+  it("subtreeAabb() throws for disconnected frames", () => {
+    tf.addFrame("world");
+    tf.addFrame("island");
+    expect(() => tf.subtreeAabb("island", "world")).toThrow(/not connected/);
+  });
+
+  // ── transformPoint ───────────────────────────────────────────────────────────
+
+  it("transformPoint() re-expresses a point in the target frame", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", new Transform(new Vec3(1, 0, 0), rotate90Z().rotation));
+    tf.addFrame("camera", "robot", translate(0, 0, 1));
+
+    const inWorld = tf.transformPoint("camera", "world", new Vec3(1, 0, 0));
+    expect(inWorld.equals(new Vec3(1, 1, 1), 1e-5)).toBe(true);
+    const back = tf.transformPoint("world", "camera", inWorld);
+    expect(back.equals(new Vec3(1, 0, 0), 1e-5)).toBe(true);
+    const same = tf.transformPoint("robot", "robot", new Vec3(2, 3, 4));
+    expect(same.equals(new Vec3(2, 3, 4))).toBe(true);
   });
 
-  // ── CycleDetectedError ──────────────────────────────────────────────────────
+  it("transformPoints() transforms packed xyz triples into a buffer", () => {
+    tf.addFrame("world");
+    tf.addFrame("lidar", "world", new Transform(new Vec3(1, 0, 0), rotate90Z().rotation));
+    const points = new Float64Array([1, 0, 0, 0, 2, 3]);
+    const rounded = (values: Float64Array) =>
+      Array.from(values, (v) => Math.round(v * 1e5) / 1e5 + 0);
+
+    const out = tf.transformPoints("lidar", "world", points);
+    expect(out).not.toBe(points);
+    expect(rounded(out)).toEqual([1, 1, 0, -1, 0, 3]);
+
+    expect(tf.transformPoints("lidar", "world", points, points)).toBe(points);
+    const back = tf.transformPoints("world", "lidar", points);
+    expect(rounded(back)).toEqual([1, 0, 0, 0, 2, 3]);
+    expect(() => tf.transformPoints("lidar", "world", new Float64Array(4))).toThrow(/packed xyz/);
+  });
 
-  it("getTransform() throws CycleDetectedError instance on cycle", () => {
+  it("transformPointsMulti() resolves each point from its own frame", () => {
+    tf.addFrame("map");
+    tf.addFrame("front_cam", "map", translate(1, 0, 0));
+    tf.addFrame("rear_cam", "map", new Transform(new Vec3(-1, 0, 0), rotate90Z().rotation));
+    tf.addFrame("island");
+
+    const out = tf.transformPointsMulti(
+      "map",
+      ["front_cam", "rear_cam", "front_cam"],
+      new Float64Array([0, 0, 0, 1, 0, 0, 0, 0, 5]),
+    );
+    expect(Array.from(out, (v) => Math.round(v * 1e5) / 1e5 + 0)).toEqual([
+      1, 0, 0, -1, 1, 0, 1, 0, 5,
+    ]);
+    expect(() => tf.transformPointsMulti("map", ["front_cam"], new Float64Array(6))).toThrowError(
+      TFError,
+    );
+    expect(() => tf.transformPointsMulti("map", ["island"], new Float64Array(3))).toThrowError(
+      DisconnectedFramesError,
+    );
+  });
+
+  it("projectPoints() applies the chain and the camera's pinhole intrinsics", () => {
     tf.addFrame("world");
-    tf.addFrame("child", "world");
-    (
-      tf as unknown as {
-        frames: Map<string, { id: string; parentId: string | undefined; transform: Transform }>;
+    tf.addFrame("camera", "world", translate(0, 0, -2));
+    const intrinsics = { fx: 100, fy: 100, cx: 320, cy: 240, width: 640, height: 480 };
+    tf.setCameraIntrinsics("camera", intrinsics);
+    expect(tf.getCameraIntrinsics("camera")).toEqual(intrinsics);
+
+    const points = new Float64Array([0, 0, 0, 1, -0.5, 0, 0, 0, -3, 10, 0, -1]);
+    const { pixels, valid } = tf.projectPoints("world", "camera", points);
+    expect(Array.from(valid)).toEqual([1, 1, 0, 0]);
+    expect(Array.from(pixels.subarray(0, 4))).toEqual([320, 240, 370, 215]);
+    expect(pixels.subarray(4).every(Number.isNaN)).toBe(true);
+
+    expect(() => tf.projectPoints("world", "world", points)).toThrow(/no camera intrinsics/);
+    let err: unknown;
+    try {
+      tf.projectPoints("world", "camera", new Float64Array(4));
+    } catch (e) {
+      err = e;
+    }
+    expect(err).toMatchObject({
+      code: "INVALID_ARGUMENT",
+      context: { operation: "projectPoints" },
+    });
+    expect(() => tf.setCameraIntrinsics("camera", { ...intrinsics, fx: 0 })).toThrowError(
+      TFError,
+    );
+  });
+
+  it("camera intrinsics follow renames, removals and rollbacks", () => {
+    tf.addFrame("world");
+    tf.addFrame("camera", "world");
+    tf.setCameraIntrinsics("camera", { fx: 1, fy: 1, cx: 0, cy: 0 });
+
+    tf.begin();
+    tf.setCameraIntrinsics("camera", undefined);
+    tf.rollback();
+    tf.renameFrame("camera", "front_camera");
+    expect(tf.getCameraIntrinsics("front_camera")).toEqual({ fx: 1, fy: 1, cx: 0, cy: 0 });
+
+    tf.removeFrame("front_camera");
+    tf.addFrame("front_camera", "world");
+    expect(tf.getCameraIntrinsics("front_camera")).toBeUndefined();
+  });
+
+  describe("joints", () => {
+    beforeEach(() => {
+      tf.addFrame("base");
+      tf.addFrame("link1", "base", translate(0, 0, 1));
+      tf.addFrame("link2", "link1", translate(1, 0, 0));
+      tf.addFrame("slider", "link2");
+    });
+
+    it("setJointPosition() recomputes the frame from origin, axis and position", () => {
+      tf.addJoint("shoulder", "link1", { type: "revolute", axis: new Vec3(0, 0, 2) });
+      tf.addJoint("extend", "slider", { type: "prismatic", position: 0.5 });
+      const slider = () => tf.getTransform("base", "slider").translation;
+      expect(slider().equals(new Vec3(1.5, 0, 1), 1e-9)).toBe(true);
+
+      tf.setJointPosition("shoulder", Math.PI / 2);
+      expect(slider().equals(new Vec3(0, 1.5, 1), 1e-9)).toBe(true);
+      expect(tf.getJointPosition("shoulder")).toBe(Math.PI / 2);
+      expect(tf.getJoint("shoulder")).toMatchObject({ frameId: "link1", type: "revolute" });
+      expect(tf.getJoint("shoulder")!.axis.equals(new Vec3(0, 0, 1))).toBe(true);
+      expect(tf.getJoint("shoulder")!.origin.equals(translate(0, 0, 1))).toBe(true);
+      expect(tf.getJoints().map((joint) => joint.name)).toEqual(["shoulder", "extend"]);
+    });
+
+    it("rejects invalid joints and positions", () => {
+      tf.addJoint("shoulder", "link1", { type: "revolute" });
+      const revolute = { type: "revolute" } as const;
+      expect(() => tf.addJoint("shoulder", "link2", revolute)).toThrow(/already registered/);
+      expect(() => tf.addJoint("elbow", "link1", revolute)).toThrow(/already driven/);
+      expect(() => tf.addJoint("elbow", "link2", { ...revolute, axis: Vec3.zero() })).toThrow(
+        /invalid axis/,
+      );
+      expect(() => tf.addJoint("elbow", "ghost", revolute)).toThrowError(FrameNotFoundError);
+
+      let err: unknown;
+      try {
+        tf.setJointPosition("wrist", 0);
+      } catch (e) {
+        err = e;
       }
-    ).frames.set("world", { id: "world", parentId: "child", transform: Transform.identity() });
-    expect(() => tf.getTransform("world", "child")).toThrowError(CycleDetectedError);
+      expect(err).toMatchObject({
+        code: "INVALID_ARGUMENT",
+        context: { operation: "setJointPosition" },
+      });
+      expect(() => tf.setJointPosition("shoulder", NaN)).toThrow(/Invalid position/);
+      tf.lockFrame("link1");
+      expect(() => tf.setJointPosition("shoulder", 1)).toThrowError(FrameLockedError);
+      expect(tf.getJointPosition("shoulder")).toBe(0);
+    });
+
+    it("setJointPositions() moves every joint in one batch", () => {
+      tf.addJoint("shoulder", "link1", { type: "revolute", axis: new Vec3(0, 0, 1) });
+      tf.addJoint("extend", "slider", { type: "prismatic", limits: { upper: 1 } });
+      const batches: string[][] = [];
+      tf.onAnyChange((frameIds) => batches.push([...frameIds]));
+
+      const dirty = tf.setJointPositions(new Float64Array([Math.PI / 2, 0]));
+      expect(dirty.sort()).toEqual(["link1", "link2", "slider"]);
+      expect(batches).toHaveLength(1);
+      expect(tf.setJointPositions([Math.PI / 2, 2])).toEqual(["slider"]);
+      expect(tf.getJointPosition("extend")).toBe(1);
+      const slider = tf.getTransform("base", "slider").translation;
+      expect(slider.equals(new Vec3(0, 2, 1), 1e-9)).toBe(true);
+
+      expect(() => tf.setJointPositions([0])).toThrow(/Expected 2 joint coordinates/);
+      tf.lockFrame("slider");
+      expect(() => tf.setJointPositions([0, 0])).toThrowError(FrameLockedError);
+      expect(tf.getJointPosition("shoulder")).toBe(Math.PI / 2);
+      expect(tf.getLocalTransform("link1").rotation.angleTo(Quaternion.identity())).toBeCloseTo(
+        Math.PI / 2,
+      );
+    });
+
+    it("planar and floating joints take several coordinates", () => {
+      tf.addFrame("rover", "base", translate(0, 0, 0.1));
+      tf.addFrame("drone", "base");
+      tf.addJoint("drive", "rover", { type: "planar", axis: new Vec3(0, 0, 1) });
+      tf.addJoint("fly", "drone", { type: "floating" });
+      expect(tf.getJoint("fly")!.positions).toEqual([0, 0, 0, 0, 0, 0, 1]);
+
+      tf.setJointPosition("drive", [1, 2, Math.PI / 2]);
+      const rover = tf.getLocalTransform("rover");
+      expect(rover.translation.equals(new Vec3(1, 2, 0.1), 1e-9)).toBe(true);
+      expect(rover.rotation.equals(Quaternion.fromAxisAngle(new Vec3(0, 0, 1), Math.PI / 2))).toBe(
+        true,
+      );
+      const s = Math.SQRT1_2;
+      const dirty = tf.setJointPositions([1, 2, Math.PI / 2, 0, 0, 5, s, 0, 0, s]);
+      expect(dirty).toEqual(["drone"]);
+      const drone = tf.getLocalTransform("drone");
+      expect(drone.translation.equals(new Vec3(0, 0, 5))).toBe(true);
+      expect(drone.rotation.equals(new Quaternion(s, 0, 0, s))).toBe(true);
+      expect(tf.getJoint("drive")).toMatchObject({ position: 1, positions: [1, 2, Math.PI / 2] });
+
+      expect(() => tf.setJointPosition("drive", 1)).toThrow(/takes 3 coordinate/);
+      expect(() => tf.setJointPosition("fly", [0, 0, 0, 0, 0, 0, 0])).toThrow(/Invalid position/);
+      expect(() => tf.getJointPosition("drive")).toThrow(/planar joint/);
+      expect(() => tf.setJointPositions([1, 2, 3])).toThrow(/Expected 10 joint coordinates/);
+      expect(() =>
+        tf.addJoint("j", "link1", { type: "planar", limits: { lower: 0, upper: 1 } }),
+      ).toThrow(/takes no limits or mimic/);
+    });
+
+    it("mimic joints follow the joint they mimic", () => {
+      tf.addJoint("extend", "slider", { type: "prismatic", position: 0.2 });
+      tf.addJoint("elbow", "link2", {
+        type: "revolute",
+        mimic: { joint: "extend", multiplier: 2, offset: 0.1 },
+      });
+      expect(tf.getJointPosition("elbow")).toBeCloseTo(0.5);
+      tf.setJointPosition("extend", 0.3);
+      expect(tf.getJointPosition("elbow")).toBeCloseTo(0.7);
+      tf.setJointPositions([1, 99]);
+      expect(tf.getJointPosition("elbow")).toBeCloseTo(2.1);
+      expect(tf.getJoint("elbow")!.mimic).toEqual({ joint: "extend", multiplier: 2, offset: 0.1 });
+
+      expect(() => tf.setJointPosition("elbow", 0)).toThrow(/can't be moved directly/);
+      const mimic = { joint: "extend" };
+      expect(() => tf.addJoint("j", "link1", { type: "revolute", mimic, position: 1 })).toThrow(
+        /takes no position/,
+      );
+      expect(() =>
+        tf.addJoint("j", "link1", { type: "revolute", mimic: { joint: "wrist" } }),
+      ).toThrow(/not registered/);
+
+      tf.addJoint("shoulder", "link1", {
+        type: "revolute",
+        mimic,
+        limits: { upper: 1.5, policy: "throw" },
+      });
+      expect(() => tf.setJointPosition("extend", 2)).toThrowError(OutOfRangeError);
+      expect(tf.getJointPosition("extend")).toBe(1);
+      expect(tf.getJointPosition("elbow")).toBeCloseTo(2.1);
+
+      tf.removeJoint("extend");
+      expect(tf.getJoint("elbow")!.mimic).toBeUndefined();
+      tf.setJointPosition("elbow", 0);
+      expect(tf.getJointPosition("shoulder")).toBe(1);
+    });
+
+    it("joint limits clamp, warn or throw", () => {
+      const revolute = { type: "revolute" } as const;
+      tf.addJoint("shoulder", "link1", { ...revolute, limits: { lower: -1, upper: 1 } });
+      tf.addJoint("elbow", "link2", { ...revolute, limits: { upper: 2, policy: "warn" } });
+      tf.addJoint("extend", "slider", {
+        type: "prismatic",
+        limits: { lower: 0, upper: 0.5, policy: "throw" },
+      });
+      const warnings: [string, number, number][] = [];
+      tf.onJointLimit((joint, requested) => warnings.push([joint.name, requested, joint.position]));
+
+      tf.setJointPosition("shoulder", 3);
+      expect(tf.getJointPosition("shoulder")).toBe(1);
+      tf.setJointPosition("elbow", -5);
+      tf.setJointPosition("elbow", 5);
+      expect(tf.getJointPosition("elbow")).toBe(2);
+      expect(warnings).toEqual([["elbow", 5, 2]]);
+      expect(tf.getJoint("elbow")!.limits).toEqual({ lower: -Infinity, upper: 2, policy: "warn" });
+
+      tf.begin();
+      tf.setJointPosition("elbow", 6);
+      tf.rollback();
+      tf.begin();
+      tf.setJointPosition("elbow", 7);
+      expect(warnings).toHaveLength(1);
+      tf.commit();
+      expect(warnings).toEqual([
+        ["elbow", 5, 2],
+        ["elbow", 7, 2],
+      ]);
+
+      let err: unknown;
+      try {
+        tf.setJointPosition("extend", 0.75);
+      } catch (e) {
+        err = e;
+      }
+      expect(err).toBeInstanceOf(OutOfRangeError);
+      expect(err).toMatchObject({
+        code: "OUT_OF_RANGE",
+        context: { operation: "setJointPosition" },
+      });
+      expect(tf.getJointPosition("extend")).toBe(0);
+
+      tf.setJointLimits("shoulder", { lower: 0, upper: 0.5 });
+      expect(tf.getJointPosition("shoulder")).toBe(0.5);
+      expect(() => tf.setJointLimits("elbow", { lower: 1, upper: 0 })).toThrow(/Invalid limits/);
+      expect(() => tf.setJointLimits("elbow", { upper: 1, policy: "throw" })).toThrowError(
+        OutOfRangeError,
+      );
+      expect(tf.getJoint("elbow")!.limits.upper).toBe(2);
+    });
+
+    it("addDhChain() builds frames from standard and modified DH parameters", () => {
+      const planar = (frameId: string, a: number, theta: number) =>
+        ({ frameId, a, alpha: 0, d: 0, theta }) as const;
+      const origin = (id: string) => tf.getTransform("base", id).translation;
+      const ids = tf.addDhChain("base", [planar("s1", 1, Math.PI / 2), planar("s2", 1, 0)]);
+      expect(ids).toEqual(["s1", "s2"]);
+      expect(origin("s2").equals(new Vec3(0, 2, 0), 1e-9)).toBe(true);
+
+      const revolute = (name: string) => ({ name, type: "revolute" }) as const;
+      tf.addDhChain(
+        "base",
+        [
+          { ...planar("m1", 0, 0), joint: revolute("j1") },
+          { ...planar("m2", 1, 0), joint: revolute("j2") },
+          {
+            ...planar("m3", 0, 0),
+            d: 0.5,
+            joint: { name: "j3", type: "prismatic", limits: { lower: 0, upper: 0.3 } },
+          },
+          planar("tip", 1, 0),
+        ],
+        { convention: "modified" },
+      );
+      expect(origin("tip").equals(new Vec3(2, 0, 0.5), 1e-9)).toBe(true);
+      tf.setJointPosition("j1", Math.PI / 2);
+      tf.setJointPosition("j2", Math.PI / 2);
+      tf.setJointPosition("j3", 0.25);
+      expect(origin("tip").equals(new Vec3(-1, 1, 0.75), 1e-9)).toBe(true);
+      expect(tf.getJoint("j2")).toMatchObject({ frameId: "m2", position: Math.PI / 2 });
+      expect(tf.getJoint("j3")!.limits).toMatchObject({ lower: 0, upper: 0.3 });
+    });
+
+    it("addDhChain() is atomic", () => {
+      const link = (frameId: string) => ({ frameId, a: 1, alpha: 0, d: 0, theta: 0 });
+      expect(() => tf.addDhChain("base", [link("a1"), link("link2")])).toThrowError(
+        DuplicateFrameError,
+      );
+      expect(tf.hasFrame("a1")).toBe(false);
+      expect(() =>
+        tf.addDhChain("base", [{ ...link("a1"), joint: { name: "j1", type: "revolute" } }]),
+      ).toThrow(/"modified" DH convention/);
+      expect(() => tf.addDhChain("base", [{ ...link("a1"), theta: NaN }])).toThrow(
+        /Invalid DH parameters/,
+      );
+      expect(tf.frameIds()).toEqual(["base", "link1", "link2", "slider"]);
+    });
+
+    it("getChain() lists the frames and joints from base to tip", () => {
+      tf.addJoint("shoulder", "link1", { type: "revolute", position: 0.5 });
+      tf.addJoint("extend", "slider", { type: "prismatic" });
+      const chain = tf.getChain("base", "slider");
+      expect(chain.frameIds).toEqual(["base", "link1", "link2", "slider"]);
+      expect(chain.links.map((link) => link.frameId)).toEqual(["link1", "link2", "slider"]);
+      expect(chain.links[1].joint).toBeUndefined();
+      expect(chain.links[1].transform.equals(translate(1, 0, 0))).toBe(true);
+      expect(chain.joints.map((joint) => [joint.name, joint.position])).toEqual([
+        ["shoulder", 0.5],
+        ["extend", 0],
+      ]);
+      expect(tf.getChain("link2", "link2").links).toEqual([]);
+      expect(() => tf.getChain("slider", "base")).toThrow(/not an ancestor/);
+    });
+
+    it("solveIk() reaches a target pose with damped least squares", () => {
+      tf.addFrame("hand", "slider", translate(1, 0, 0));
+      const z = new Vec3(0, 0, 1);
+      tf.addJoint("shoulder", "link1", { type: "revolute", axis: z });
+      tf.addJoint("elbow", "link2", { type: "revolute", axis: z, limits: { lower: 0 } });
+
+      tf.setJointPositions([0.3, 0.8]);
+      const target = tf.getTransform("base", "hand");
+      tf.setJointPositions([0, 0.1]);
+      const result = tf.solveIk("base", "hand", target);
+      expect(result).toMatchObject({ success: true });
+      expect(result.positions.shoulder).toBeCloseTo(0.3, 3);
+      expect(result.positions.elbow).toBeCloseTo(0.8, 3);
+      expect(tf.getJointPosition("shoulder")).toBe(0);
+
+      const reach = new Transform(new Vec3(1, 1, 1));
+      const dragged = tf.solveIk("base", "hand", reach, { positionOnly: true, apply: true });
+      expect(dragged.success).toBe(true);
+      expect(dragged.positionError).toBeLessThanOrEqual(1e-4);
+      expect(tf.getTransform("base", "hand").translation.equals(reach.translation, 1e-3)).toBe(
+        true,
+      );
+      expect(tf.getJointPosition("elbow")).toBeGreaterThanOrEqual(0);
+
+      const tooFar = tf.solveIk("base", "hand", new Transform(new Vec3(3, 0, 1)), {
+        positionOnly: true,
+      });
+      expect(tooFar.success).toBe(false);
+      expect(tooFar.reason).toBeDefined();
+      expect(tooFar.positionError).toBeCloseTo(1, 2);
+
+      expect(() => tf.solveIk("link2", "link2", target)).toThrow(/No joint to solve for/);
+    });
+
+    it("joints follow renames, removals and rollbacks", () => {
+      tf.addJoint("elbow", "link2", { type: "revolute" });
+      tf.begin();
+      tf.setJointPosition("elbow", 1);
+      tf.rollback();
+      expect(tf.getJointPosition("elbow")).toBe(0);
+
+      tf.renameFrame("link2", "forearm");
+      expect(tf.getJoint("elbow")?.frameId).toBe("forearm");
+      tf.setJointPosition("elbow", 1);
+      tf.removeJoint("elbow");
+      expect(tf.getJoint("elbow")).toBeUndefined();
+      const { rotation } = tf.getLocalTransform("forearm");
+      expect(rotation.angleTo(Quaternion.identity())).toBeCloseTo(1);
+
+      tf.addJoint("elbow", "forearm", { type: "revolute" });
+      tf.removeSubtree("forearm");
+      expect(tf.getJoints()).toEqual([]);
+    });
   });
 
-  it("CycleDetectedError has the correct name", () => {
-    const err = new CycleDetectedError("someFrame");
-    expect(err.name).toBe("CycleDetectedError");
-    expect(err.message).toMatch(/someFrame/);
+  describe("hand-eye calibration", () => {
+    const pose = (t: [number, number, number], axis: Vec3, angle: number) =>
+      new Transform(new Vec3(...t), Quaternion.fromAxisAngle(axis, angle));
+    const camera = pose([0.05, -0.02, 0.1], new Vec3(0.3, 1, 0.2).normalize(), 0.7);
+    const hand = [
+      pose([0.5, 0, 0.5], new Vec3(1, 0, 0), 0),
+      pose([0.4, 0.1, 0.6], new Vec3(1, 0, 0), 0.5),
+      pose([0.6, -0.1, 0.4], new Vec3(0, 1, 0), 0.6),
+      pose([0.5, 0.2, 0.5], new Vec3(1, 1, 1).normalize(), -0.4),
+    ];
+
+    it("solveHandEye() recovers an eye-in-hand camera and installs it", () => {
+      // The target is fixed in the workspace: base → hand → camera → target.
+      const board = pose([1, 0.5, 0], new Vec3(0, 0, 1), 0.4);
+      const eye = hand.map((h) => camera.invert().compose(h.invert()).compose(board));
+      tf.addFrame("base");
+      tf.addFrame("gripper", "base");
+
+      const result = tf.solveHandEye(hand, eye, {
+        install: { frameId: "camera", parentId: "gripper" },
+      });
+      expect(result.transform.equals(camera, 1e-4)).toBe(true);
+      expect(result.rotationResidual).toBeLessThan(1e-4);
+      expect(result.translationResidual).toBeLessThan(1e-4);
+      expect(tf.getParentId("camera")).toBe("gripper");
+      expect(tf.getTransform("gripper", "camera").equals(camera, 1e-4)).toBe(true);
+      expect(tf.getCalibration("camera")).toMatchObject({
+        method: "hand-eye",
+        residualRms: result.translationResidual,
+      });
+    });
+
+    it("solveHandEye() recovers an eye-to-hand camera", () => {
+      // The target is held by the hand: base → camera → target ← hand.
+      const held = pose([0, 0, 0.1], new Vec3(1, 0, 0), 0.2);
+      const eye = hand.map((h) => camera.invert().compose(h).compose(held));
+      const { transform } = tf.solveHandEye(hand, eye, { setup: "eye-to-hand" });
+      expect(transform.equals(camera, 1e-4)).toBe(true);
+    });
+
+    it("solveHandEye() rejects mismatched or degenerate captures", () => {
+      const eye = hand.map((h) => h.invert());
+      expect(() => tf.solveHandEye(hand, eye.slice(1))).toThrow(/3 eye poses/);
+      expect(() => tf.solveHandEye(hand.slice(2), eye.slice(2))).toThrow(/at least 3 poses/);
+      const yaw = [0, 0.3, 0.8].map((a) => pose([a, 0, 0], new Vec3(0, 0, 1), a));
+      let err: unknown;
+      try {
+        tf.solveHandEye(yaw, yaw);
+      } catch (e) {
+        err = e;
+      }
+      expect(err).toMatchObject({
+        code: "INVALID_ARGUMENT",
+        context: { operation: "solveHandEye" },
+      });
+      expect(String(err)).toMatch(/non-parallel axes/);
+    });
   });
 
-  // ── toJSON / fromJSON ────────────────────────────────────────────────────────
+  describe("geo anchors", () => {
+    beforeEach(() => {
+      tf.addFrame("earth");
+    });
 
-  it("toJSON() produces a plain object with all registered frames", () => {
-    tf.addFrame("world");
-    tf.addFrame("robot", "world", translate(1, 2, 3));
-    const json = tf.toJSON();
-    expect(json.frames).toHaveLength(2);
-    expect(json.frames[0].id).toBe("world");
-    expect(json.frames[0].parentId).toBeNull();
-    expect(json.frames[1].id).toBe("robot");
-    expect(json.frames[1].parentId).toBe("world");
-    expect(json.frames[1].transform.translation).toEqual([1, 2, 3]);
+    it("addGeoAnchor() places the east-north-up frame in ECEF coordinates", () => {
+      tf.addGeoAnchor("site", "earth", 45, 90, 0);
+      const { translation, rotation } = tf.getLocalTransform("site");
+      expect(translation.x).toBeCloseTo(0, 6);
+      expect(translation.y).toBeCloseTo(4517590.879, 3);
+      expect(translation.z).toBeCloseTo(4487348.409, 3);
+      // East is +y there, up points away from the axis at 45°.
+      expect(rotation.rotateVec3(new Vec3(1, 0, 0)).equals(new Vec3(0, 1, 0))).toBe(true);
+      const up = rotation.rotateVec3(new Vec3(0, 0, 1));
+      expect(up.equals(new Vec3(0, Math.SQRT1_2, Math.SQRT1_2))).toBe(true);
+      expect(tf.getGeoAnchor("site")).toEqual({
+        latitude: 45,
+        longitude: 90,
+        altitude: 0,
+        heading: 0,
+      });
+    });
+
+    it("addGeoAnchor() places anchors relative to a reference anchor", () => {
+      tf.addGeoAnchor("origin", "earth", 0, 0, 0);
+      tf.addGeoAnchor("east", "origin", 0, 0.001, 0);
+      const east = tf.getLocalTransform("east").translation;
+      expect(east.x).toBeCloseTo(111.3195, 4);
+      expect(east.y).toBeCloseTo(0, 9);
+      // The surface curves away below the reference's horizon.
+      expect(east.z).toBeCloseTo(-0.00097, 5);
+
+      tf.addGeoAnchor("mast", "origin", 0, 0, 5, 90);
+      const mast = tf.getLocalTransform("mast");
+      expect(mast.translation.equals(new Vec3(0, 0, 5), 1e-9)).toBe(true);
+      expect(mast.rotation.equals(Quaternion.fromAxisAngle(new Vec3(0, 0, 1), Math.PI / 2))).toBe(
+        true,
+      );
+
+      tf.renameFrame("origin", "home");
+      expect(tf.getGeoAnchor("home")?.latitude).toBe(0);
+      tf.removeFrame("mast");
+      expect(tf.getGeoAnchor("mast")).toBeUndefined();
+      expect(() => tf.addGeoAnchor("pole", "home", 91, 0, 0)).toThrowError(TFError);
+      expect(tf.hasFrame("pole")).toBe(false);
+    });
   });
 
-  it("toJSON() serializes rotation correctly", () => {
-    tf.addFrame("world");
-    tf.addFrame("rotated", "world", rotate90Z());
-    const json = tf.toJSON();
-    const q = json.frames[1].transform.rotation;
-    expect(q).toHaveLength(4);
-    // Rotation quaternion for 90° around Z: x≈0, y≈0, z≈0.707, w≈0.707
-    expect(Math.abs(q[2])).toBeCloseTo(Math.SQRT2 / 2, 5);
-    expect(Math.abs(q[3])).toBeCloseTo(Math.SQRT2 / 2, 5);
+  describe("calibrations", () => {
+    let now: number;
+    const mount = new Transform(new Vec3(0.2, 0, 0.1));
+
+    beforeEach(() => {
+      now = 1000;
+      tf.setClock(() => now);
+      tf.addFrame("base");
+    });
+
+    it("setCalibration() adds a locked, static frame with its metadata", () => {
+      tf.setCalibration("lidar", "base", mount, { method: "target-based", residualRms: 0.01 });
+      expect(tf.getCalibration("lidar")).toEqual({
+        frameId: "lidar",
+        transform: mount,
+        method: "target-based",
+        date: 1000,
+        residualRms: 0.01,
+      });
+      expect(tf.isFrameLocked("lidar")).toBe(true);
+      expect(() => tf.updateTransform("lidar", Transform.identity())).toThrowError(
+        FrameLockedError,
+      );
+
+      // Recalibrating an existing frame replaces its transform and metadata.
+      tf.addFrame("camera", "base");
+      tf.updateTransform("camera", mount);
+      tf.setCalibration("camera", "base", mount, { method: "manual", date: 500 });
+      expect(tf.getCalibration("camera")).toEqual({
+        frameId: "camera",
+        transform: mount,
+        method: "manual",
+        date: 500,
+      });
+      expect(tf.diagnose().filter((d) => d.kind === "never-updated")).toHaveLength(2);
+      const moved = new Transform(new Vec3(0.3, 0, 0.1));
+      tf.setCalibration("lidar", "base", moved, { method: "target-based" });
+      expect(tf.getLocalTransform("lidar")).toEqual(moved);
+      expect(tf.getCalibrations().map((c) => c.frameId)).toEqual(["lidar", "camera"]);
+
+      expect(() =>
+        tf.setCalibration("radar", "base", mount, { method: "manual", residualRms: -1 }),
+      ).toThrowError(TFError);
+      expect(tf.hasFrame("radar")).toBe(false);
+    });
+
+    it("calibrations follow renames and go with their frame", () => {
+      tf.setCalibration("lidar", "base", mount, { method: "manual" });
+      tf.renameFrame("lidar", "front_lidar");
+      expect(tf.getCalibration("front_lidar")?.frameId).toBe("front_lidar");
+      expect(tf.getCalibration("lidar")).toBeUndefined();
+
+      tf.begin();
+      tf.removeFrame("front_lidar");
+      expect(tf.getCalibrations()).toEqual([]);
+      tf.rollback();
+      expect(tf.getCalibrations()).toHaveLength(1);
+
+      expect(tf.clearCalibration("front_lidar")).toBe(true);
+      expect(tf.clearCalibration("front_lidar")).toBe(false);
+      expect(tf.isFrameLocked("front_lidar")).toBe(false);
+      expect(tf.hasFrame("front_lidar")).toBe(true);
+    });
+
+    it("auditCalibrations() reports tampered, stale and inaccurate calibrations", () => {
+      tf.setCalibration("lidar", "base", mount, { method: "manual", residualRms: 0.5 });
+      tf.setCalibration("camera", "base", mount, { method: "manual" });
+      expect(tf.auditCalibrations()).toEqual([]);
+
+      tf.unlockFrame("camera");
+      tf.updateTransform("camera", Transform.identity());
+      now += 10_000;
+      const issues = tf.auditCalibrations({ maxAge: 5_000, maxResidual: 0.1 });
+      expect(issues.map(({ kind, frameId }) => [kind, frameId])).toEqual([
+        ["stale", "lidar"],
+        ["high-residual", "lidar"],
+        ["unlocked", "camera"],
+        ["modified", "camera"],
+        ["stale", "camera"],
+      ]);
+    });
   });
 
-  it("fromJSON() reconstructs a tree with equivalent getTransform results", () => {
+  it("transformVector() and transformVectors() apply only the rotation", () => {
     tf.addFrame("world");
-    tf.addFrame("robot", "world", translate(1, 0, 0));
-    tf.addFrame("camera", "robot", translate(0, 0, 1));
+    tf.addFrame("robot", "world", new Transform(new Vec3(5, 5, 5), rotate90Z().rotation));
 
-    const restored = TFTree.fromJSON(tf.toJSON());
+    const heading = tf.transformVector("robot", "world", new Vec3(1, 0, 0));
+    expect(heading.equals(new Vec3(0, 1, 0), 1e-5)).toBe(true);
+    const point = tf.transformPoint("robot", "world", new Vec3(1, 0, 0));
+    expect(point.equals(new Vec3(5, 6, 5), 1e-5)).toBe(true);
 
-    expect(restored.hasFrame("world")).toBe(true);
-    expect(restored.hasFrame("robot")).toBe(true);
-    expect(restored.hasFrame("camera")).toBe(true);
+    const vectors = tf.transformVectors("robot", "world", new Float64Array([1, 0, 0, 0, 0, 2]));
+    expect(Array.from(vectors, (v) => Math.round(v * 1e5) / 1e5 + 0)).toEqual([0, 1, 0, 0, 0, 2]);
+    expect(() => tf.transformVectors("robot", "world", new Float64Array(2))).toThrow(/packed xyz/);
+  });
 
-    const original = tf.getTransform("world", "camera");
-    const copy = restored.getTransform("world", "camera");
-    expect(copy.transformPoint(Vec3.zero()).equals(original.transformPoint(Vec3.zero()))).toBe(
-      true,
+  it("transformQuaternion() and transformQuaternions() re-express orientations", () => {
+    tf.addFrame("world");
+    tf.addFrame("imu", "world", new Transform(new Vec3(3, 0, 0), rotate90Z().rotation));
+    const pitch = Quaternion.fromAxisAngle(new Vec3(0, 1, 0), Math.PI / 2);
+    const expected = rotate90Z().rotation.multiply(pitch);
+
+    expect(tf.transformQuaternion("imu", "world", pitch).equals(expected, 1e-5)).toBe(true);
+    const back = tf.transformQuaternion("world", "imu", expected);
+    expect(back.equals(pitch, 1e-5)).toBe(true);
+
+    const orientations = new Float64Array([...pitch.toArray(), 0, 0, 0, 1]);
+    const packed = tf.transformQuaternions("imu", "world", orientations);
+    const [first, second] = [0, 4].map(
+      (i) => new Quaternion(packed[i], packed[i + 1], packed[i + 2], packed[i + 3]),
+    );
+    expect(first.equals(expected, 1e-5)).toBe(true);
+    expect(second.equals(rotate90Z().rotation, 1e-5)).toBe(true);
+    expect(() => tf.transformQuaternions("imu", "world", new Float64Array(3))).toThrowError(
+      TFError,
     );
   });
 
-  it("fromJSON() handles a root frame with null parentId", () => {
-    tf.addFrame("island");
-    const restored = TFTree.fromJSON(tf.toJSON());
-    expect(restored.hasFrame("island")).toBe(true);
-    expect(restored.frameIds()).toEqual(["island"]);
+  it("transformAabb() encloses the rotated box in the target frame", () => {
+    tf.addFrame("world");
+    const yaw45 = Quaternion.fromAxisAngle(new Vec3(0, 0, 1), Math.PI / 4);
+    tf.addFrame("shelf", "world", new Transform(new Vec3(10, 0, 0), yaw45));
+    const box = { min: new Vec3(-1, -1, 0), max: new Vec3(1, 1, 2) };
+
+    const inWorld = tf.transformAabb("shelf", "world", box);
+    expect(inWorld.min.equals(new Vec3(10 - Math.SQRT2, -Math.SQRT2, 0), 1e-5)).toBe(true);
+    expect(inWorld.max.equals(new Vec3(10 + Math.SQRT2, Math.SQRT2, 2), 1e-5)).toBe(true);
+
+    const same = tf.transformAabb("shelf", "shelf", box);
+    expect(same.min.equals(box.min) && same.max.equals(box.max)).toBe(true);
   });
 
-  it("toJSON() followed by fromJSON() round-trips a rotation transform", () => {
+  it("transformObb() moves the centre and composes the rotation", () => {
     tf.addFrame("world");
-    tf.addFrame("rotated", "world", rotate90Z());
+    tf.addFrame("robot", "world", new Transform(new Vec3(1, 0, 0), rotate90Z().rotation));
+    const box = {
+      center: new Vec3(2, 0, 0),
+      halfExtents: new Vec3(1, 0.5, 0.25),
+      rotation: rotate90Z().rotation,
+    };
+
+    const inWorld = tf.transformObb("robot", "world", box);
+    expect(inWorld.center.equals(new Vec3(1, 2, 0), 1e-5)).toBe(true);
+    expect(inWorld.halfExtents).toBe(box.halfExtents);
+    const yaw180 = Quaternion.fromAxisAngle(new Vec3(0, 0, 1), Math.PI);
+    expect(inWorld.rotation.equals(yaw180, 1e-5)).toBe(true);
+
+    const back = tf.transformObb("world", "robot", inWorld);
+    expect(back.center.equals(box.center, 1e-5)).toBe(true);
+    expect(back.rotation.equals(box.rotation, 1e-5)).toBe(true);
+  });
 
-    const restored = TFTree.fromJSON(tf.toJSON());
-    const original = tf.getTransform("world", "rotated");
-    const copy = restored.getTransform("world", "rotated");
-    expect(
-      copy.transformPoint(new Vec3(1, 0, 0)).equals(original.transformPoint(new Vec3(1, 0, 0))),
-    ).toBe(true);
+  it("transformRay() and transformRays() translate origins but only rotate directions", () => {
+    tf.addFrame("world");
+    tf.addFrame("camera", "world", new Transform(new Vec3(0, 0, 2), rotate90Z().rotation));
+
+    const ray = tf.transformRay("camera", "world", {
+      origin: new Vec3(1, 0, 0),
+      direction: new Vec3(1, 0, 0),
+    });
+    expect(ray.origin.equals(new Vec3(0, 1, 2), 1e-5)).toBe(true);
+    expect(ray.direction.equals(new Vec3(0, 1, 0), 1e-5)).toBe(true);
+
+    const rays = new Float64Array([1, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, -1]);
+    expect(tf.transformRays("camera", "world", rays, rays)).toBe(rays);
+    expect(Array.from(rays, (v) => Math.round(v * 1e5) / 1e5 + 0)).toEqual([
+      0, 1, 2, 0, 1, 0, 0, 0, 2, 0, 0, -1,
+    ]);
+    expect(() => tf.transformRays("camera", "world", new Float64Array(3))).toThrowError(TFError);
   });
 
-  it("fromJSON() preserves parent-child relationships", () => {
+  it("transformCovariance() rotates a 6x6 pose covariance", () => {
     tf.addFrame("world");
-    tf.addFrame("robot", "world", translate(5, 0, 0));
-    tf.addFrame("sensor", "robot", translate(0, 1, 0));
+    tf.addFrame("robot", "world", new Transform(new Vec3(3, 4, 5), rotate90Z().rotation));
+    // Variances: 1 along x, 4 along y, 9 along z; 0.1 roll, 0.2 pitch, 0.3 yaw.
+    const covariance = new Array<number>(36).fill(0);
+    const variances = [1, 4, 9, 0.1, 0.2, 0.3];
+    for (let i = 0; i < 6; i++) covariance[i * 7] = variances[i];
+    covariance[1] = 0.5;
+    covariance[6] = 0.5;
+
+    const rotated = tf.transformCovariance("robot", "world", covariance);
+    const entry = (r: number, c: number) => Math.round(rotated[r * 6 + c] * 1e5) / 1e5 + 0;
+    // A quarter turn about z swaps the x/y variances and flips their correlation.
+    expect([0, 1, 2, 3, 4, 5].map((i) => entry(i, i))).toEqual([4, 1, 9, 0.2, 0.1, 0.3]);
+    expect([entry(0, 1), entry(1, 0)]).toEqual([-0.5, -0.5]);
+
+    const back = tf.transformCovariance("world", "robot", rotated);
+    expect(Array.from(back, (v) => Math.round(v * 1e5) / 1e5 + 0)).toEqual(covariance);
+    expect(() => tf.transformCovariance("robot", "world", [1, 2, 3])).toThrow(/36 entries/);
+  });
 
-    const restored = TFTree.fromJSON(tf.toJSON());
-    const t = restored.getTransform("world", "sensor");
-    expect(t.transformPoint(Vec3.zero()).equals(new Vec3(5, 1, 0))).toBe(true);
+  it("bulk geometry methods write into caller-provided Float32 and shared views", () => {
+    tf.addFrame("world");
+    tf.addFrame("lidar", "world", translate(1, 2, 3));
+    const points = new Float64Array([0, 0, 0, 1, 1, 1]);
+
+    // A window into a larger GPU staging buffer.
+    const staging = new Float32Array(12);
+    const view = staging.subarray(3, 9);
+    expect(tf.transformPoints("lidar", "world", points, view)).toBe(view);
+    expect(Array.from(staging)).toEqual([0, 0, 0, 1, 2, 3, 2, 3, 4, 0, 0, 0]);
+
+    const shared = new Float64Array(new SharedArrayBuffer(6 * 8));
+    expect(tf.transformVectors("lidar", "world", points, shared)).toBe(shared);
+    expect(Array.from(shared)).toEqual([0, 0, 0, 1, 1, 1]);
+
+    const covariance = new Float64Array(36).fill(1);
+    expect(tf.transformCovariance("lidar", "world", covariance, covariance)).toBe(covariance);
+    expect(Array.from(covariance)).toEqual(new Array(36).fill(1));
   });
 
-  it("fromJSON() throws when frame data references an unknown parent", () => {
+  it("transformPointCloud() handles strided Float32 clouds in place or into a buffer", () => {
+    tf.addFrame("world");
+    tf.addFrame("lidar", "world", translate(10, 20, 30));
+    // XYZI layout; the intensity channel must survive untouched.
+    const cloud = new Float32Array([1, 2, 3, 0.5, 4, 5, 6, 0.75]);
+
+    const copy = new Float32Array(cloud.length);
+    expect(tf.transformPointCloud("lidar", "world", cloud, { stride: 4, out: copy })).toBe(copy);
+    expect(Array.from(copy)).toEqual([11, 22, 33, 0.5, 14, 25, 36, 0.75]);
+    expect(Array.from(cloud)).toEqual([1, 2, 3, 0.5, 4, 5, 6, 0.75]);
+
+    // Intensity first, xyz after it.
+    const ixyz = new Float32Array([0.5, 1, 2, 3]);
+    expect(tf.transformPointCloud("lidar", "world", ixyz, { stride: 4, offset: 1 })).toBe(ixyz);
+    expect(Array.from(ixyz)).toEqual([0.5, 11, 22, 33]);
+
+    expect(() => tf.transformPointCloud("lidar", "world", cloud, { stride: 3 })).toThrow(
+      /Invalid point cloud layout/,
+    );
     expect(() =>
-      TFTree.fromJSON({
-        frames: [
-          {
-            id: "child",
-            parentId: "missing",
-            transform: { translation: [0, 0, 0], rotation: [0, 0, 0, 1] },
-          },
-        ],
-      }),
-    ).toThrow(/not found/);
+      tf.transformPointCloud("lidar", "world", cloud, { stride: 4, offset: 2 }),
+    ).toThrowError(TFError);
   });
 
-  it("fromJSON() throws on duplicate frame ids", () => {
-    expect(() =>
-      TFTree.fromJSON({
-        frames: [
-          {
-            id: "world",
-            parentId: null,
-            transform: { translation: [0, 0, 0], rotation: [0, 0, 0, 1] },
-          },
-          {
-            id: "world",
-            parentId: null,
-            transform: { translation: [0, 0, 0], rotation: [0, 0, 0, 1] },
-          },
-        ],
-      }),
-    ).toThrow(/already registered/);
+  it("transformPoint() throws for disconnected frames", () => {
+    tf.addFrame("world");
+    tf.addFrame("island");
+    expect(() => tf.transformPoint("island", "world", Vec3.zero())).toThrowError(
+      DisconnectedFramesError,
+    );
   });
 
-  it("toJSON() on an empty tree returns an empty frames array", () => {
-    const json = tf.toJSON();
-    expect(json.frames).toHaveLength(0);
+  // ── watched pairs ────────────────────────────────────────────────────────────
+
+  describe("watched pairs", () => {
+    beforeEach(() => {
+      tf.addFrame("world");
+      tf.addFrame("robot", "world", translate(1, 0, 0));
+      tf.addFrame("camera", "robot", translate(0, 0, 1));
+      tf.addFrame("tree", "world", translate(5, 5, 0));
+    });
+
+    it("flushWatches() reports new pairs once, then only changed ones", () => {
+      const camera = tf.watchTransform("world", "camera");
+      const tree = tf.watchTransform("world", "tree");
+      expect(tf.flushWatches().slots).toEqual([camera, tree]);
+      expect(tf.flushWatches().slots).toEqual([]);
+
+      tf.updateTransform("robot", translate(2, 0, 0));
+      const { slots, data } = tf.flushWatches();
+      expect(slots).toEqual([camera]);
+      expect(data).toHaveLength(7);
+      expect(new Vec3(data[0], data[1], data[2]).equals(new Vec3(2, 0, 1))).toBe(true);
+      expect(data[6]).toBe(1); // identity rotation: w = 1
+    });
+
+    it("does not report pairs whose value is unchanged", () => {
+      const slot = tf.watchTransform("robot", "camera");
+      tf.flushWatches();
+      tf.updateTransform("robot", translate(3, 0, 0));
+      tf.updateTransform("camera", translate(0, 0, 1));
+      expect(tf.flushWatches().slots).toEqual([]);
+      tf.unwatchTransform(slot);
+      tf.updateTransform("camera", translate(0, 0, 2));
+      expect(tf.flushWatches().slots).toEqual([]);
+    });
+
+    it("keeps slot indices stable and drops watches of removed frames", () => {
+      const a = tf.watchTransform("world", "camera");
+      const b = tf.watchTransform("world", "tree");
+      tf.flushWatches();
+      tf.removeFrame("camera");
+      expect(tf.watchTransform("robot", "tree")).toBe(b + 1);
+      tf.updateTransform("tree", translate(6, 5, 0));
+      expect(tf.flushWatches().slots).toEqual([b, b + 1]);
+      expect(a).toBe(0);
+    });
+
+    it("recomputes high-priority pairs on change and low-priority ones on flush", () => {
+      const camera = tf.watchTransform("world", "camera", "high");
+      const tree = tf.watchTransform("world", "tree");
+      expect(tf.getWatchedTransform(camera)?.translation.equals(new Vec3(1, 0, 1))).toBe(true);
+      expect(tf.getWatchedTransform(tree)).toBeUndefined();
+
+      tf.updateTransform("robot", translate(2, 0, 0));
+      tf.updateTransform("tree", translate(6, 5, 0));
+      expect(tf.getWatchedTransform(camera)?.translation.equals(new Vec3(2, 0, 1))).toBe(true);
+      expect(tf.getWatchedTransform(tree)).toBeUndefined();
+
+      expect(tf.flushWatches().slots).toEqual([camera, tree]);
+      expect(tf.getWatchedTransform(tree)?.translation.equals(new Vec3(6, 5, 0))).toBe(true);
+    });
+
+    it("rollback() restores watches of removed or renamed frames", () => {
+      const camera = tf.watchTransform("world", "camera");
+      const tree = tf.watchTransform("world", "tree");
+      tf.flushWatches();
+
+      tf.begin();
+      tf.removeFrame("camera");
+      tf.renameFrame("tree", "oak");
+      const added = tf.watchTransform("world", "oak");
+      tf.rollback();
+      tf.updateTransform("camera", translate(0, 0, 2));
+      tf.updateTransform("tree", translate(6, 5, 0));
+      expect(tf.flushWatches().slots).toEqual([camera, tree]);
+      expect(tf.getWatchedTransform(added)).toBeUndefined();
+      expect(tf.watchTransform("world", "robot")).toBe(added + 1);
+
+      // A dry run rolls back the same way.
+      tf.applyPatch([{ type: "removed", id: "camera" }], { validateOnly: true });
+      tf.updateTransform("camera", translate(0, 0, 3));
+      expect(tf.flushWatches().slots).toContain(camera);
+    });
   });
 
   // ── onChange ─────────────────────────────────────────────────────────────────
@@ -674,6 +3644,212 @@ describe("TFTree", () => {
     expect(() => tf.onChange("ghost", () => {})).toThrow(/not found/);
   });
 
+  it("onChange() passes the previous and current world transform", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.addFrame("camera", "robot", translate(0, 0, 1));
+
+    const deltas: Vec3[] = [];
+    tf.onChange("camera", (_id, { previous, current }) => {
+      deltas.push(current.translation.subtract(previous.translation));
+    });
+
+    tf.updateTransform("robot", translate(3, 0, 0));
+    tf.updateTransform("camera", translate(0, 0, 2));
+    expect(deltas).toHaveLength(2);
+    expect(deltas[0].equals(new Vec3(2, 0, 0))).toBe(true);
+    expect(deltas[1].equals(new Vec3(0, 0, 1))).toBe(true);
+  });
+
+  it("onChange() with thresholds ignores jitter but accumulates drift", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+
+    const positions: number[] = [];
+    tf.onChange("robot", (_id, { current }) => positions.push(current.translation.x), {
+      minTranslation: 0.01,
+    });
+
+    tf.updateTransform("robot", translate(0.004, 0, 0)); // jitter
+    tf.updateTransform("robot", translate(0.008, 0, 0)); // jitter
+    tf.updateTransform("robot", translate(0.012, 0, 0)); // drifted past 0.01
+    tf.updateTransform("robot", new Transform(new Vec3(0.012, 0, 0), rotate90Z().rotation));
+    expect(positions).toHaveLength(2);
+    expect(positions[0]).toBeCloseTo(0.012, 6);
+  });
+
+  it("onAnyChange() is invoked once per mutation with every affected frame", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.addFrame("camera", "robot");
+    tf.addFrame("tree", "world");
+
+    const batches: string[][] = [];
+    const unsubscribe = tf.onAnyChange((ids) => batches.push(ids));
+
+    tf.updateTransform("robot", translate(1, 0, 0));
+    tf.updateTransforms({ camera: translate(0, 1, 0), tree: translate(2, 0, 0) });
+    tf.transaction(() => {
+      tf.updateTransform("tree", translate(3, 0, 0));
+      tf.updateTransform("camera", translate(0, 2, 0));
+    });
+    expect(batches).toEqual([
+      ["robot", "camera"],
+      ["camera", "tree"],
+      ["tree", "camera"],
+    ]);
+
+    unsubscribe();
+    tf.updateTransform("robot", translate(2, 0, 0));
+    expect(batches).toHaveLength(3);
+  });
+
+  it("onAnyChange() can be scoped to a namespace pattern or a subtree", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot1/base", "world");
+    tf.addFrame("robot1/arm", "robot1/base");
+    tf.addFrame("robot2/base", "world");
+
+    const byPattern: string[][] = [];
+    const bySubtree: string[][] = [];
+    tf.onAnyChange((ids) => byPattern.push(ids), { pattern: "robot1/**" });
+    tf.onAnyChange((ids) => bySubtree.push(ids), { subtreeRoot: "robot1/arm" });
+
+    tf.updateTransform("robot2/base", translate(1, 0, 0));
+    expect(byPattern).toEqual([]);
+    tf.updateTransform("world", translate(1, 0, 0));
+    expect(byPattern).toEqual([["robot1/base", "robot1/arm"]]);
+    expect(bySubtree).toEqual([["robot1/arm"]]);
+    expect(() => tf.onAnyChange(() => {}, { subtreeRoot: "ghost" })).toThrow(/not found/);
+  });
+
+  it("change batches carry sequence numbers and can be replayed with eventsSince()", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.addFrame("camera", "robot");
+
+    const seqs: number[] = [];
+    tf.onAnyChange((_ids, seq) => seqs.push(seq), { subtreeRoot: "camera" });
+    tf.updateTransform("robot", translate(1, 0, 0));
+    expect(() => tf.eventsSince(0)).toThrow(RangeError);
+
+    tf.enableEventLog(2);
+    tf.updateTransform("camera", translate(0, 1, 0));
+    tf.updateTransform("robot", translate(2, 0, 0));
+    tf.updateTransform("robot", translate(3, 0, 0));
+    expect(seqs).toEqual([1, 2, 3, 4]);
+    expect(tf.lastEventSeq()).toBe(4);
+    expect(tf.eventsSince(2)).toEqual([
+      { seq: 3, frameIds: ["robot", "camera"] },
+      { seq: 4, frameIds: ["robot", "camera"] },
+    ]);
+    expect(tf.eventsSince(4)).toEqual([]);
+    expect(() => tf.eventsSince(1)).toThrow(/no longer retained/);
+  });
+
+  it("deferred notifications are merged until flushNotifications()", () => {
+    tf.addFrame("world");
+    tf.addFrame("robot", "world");
+    tf.addFrame("camera", "robot");
+
+    const calls: string[] = [];
+    const batches: string[][] = [];
+    tf.onChange("camera", (id) => calls.push(id));
+    tf.onAnyChange((ids) => batches.push(ids));
+
+    tf.setDeferredNotifications(true);
+    tf.updateTransform("robot", translate(1, 0, 0));
+    tf.updateTransform("camera", translate(0, 1, 0));
+    tf.updateTransform("robot", translate(2, 0, 0));
+    expect(calls).toEqual([]);
+
+    expect(tf.flushNotifications()).toEqual(["robot", "camera"]);
+    expect(calls).toEqual(["camera"]);
+    expect(batches).toEqual([["robot", "camera"]]);
+    expect(tf.flushNotifications()).toEqual([]);
+
+    tf.updateTransform("camera", translate(0, 2, 0));
+    tf.setDeferredNotifications(false);
+    expect(calls).toEqual(["camera", "camera"]);
+    tf.updateTransform("camera", translate(0, 3, 0));
+    expect(calls).toHaveLength(3);
+  });
+
+  it("onStructureChange() reports added, reparented, renamed and removed frames", () => {
+    const events: StructureEvent[] = [];
+    tf.onStructureChange((event) => events.push(event));
+
+    tf.addFrame("world");
+    tf.addFrame("table", "world");
+    tf.addFrame("cup", "world");
+    tf.updateTransform("cup", translate(1, 0, 0)); // not structural
+    tf.setParent("cup", "table");
+    tf.renameFrame("cup", "mug");
+    tf.removeFrame("mug");
+    tf.clear();
+
+    expect(events).toEqual([
+      { type: "added", id: "world", parentId: null },
+      { type: "added", id: "table", parentId: "world" },
+      { type: "added", id: "cup", parentId: "world" },
+      { type: "reparented", id: "cup", oldParentId: "world", parentId: "table" },
+      { type: "renamed", oldId: "cup", id: "mug" },
+      { type: "removed", id: "mug", parentId: "table" },
+      { type: "cleared" },
+    ]);
+  });
+
+  it("onStructureChange() delivers transaction events on commit only", () => {
+    const events: StructureEvent[] = [];
+    tf.onStructureChange((event) => events.push(event));
+
+    tf.begin();
+    tf.addFrame("temp");
+    expect(events).toEqual([]);
+    tf.rollback();
+    tf.transaction(() => tf.addFrame("world"));
+    expect(events).toEqual([{ type: "added", id: "world", parentId: null }]);
+  });
+
+  it("onRegionChange() fires only when a frame enters or leaves the box", () => {
+    tf.addFrame("world");
+    tf.addFrame("zone", "world", translate(10, 0, 0));
+    tf.addFrame("robot", "world");
+    tf.addFrame("gripper", "robot", translate(0, 0, 1));
+
+    const region = { min: new Vec3(-1, -1, -1), max: new Vec3(1, 1, 2) };
+    const calls: Array<[string, boolean]> = [];
+    tf.onRegionChange("gripper", "zone", region, (id, inside) => calls.push([id, inside]));
+
+    tf.updateTransform("robot", translate(5, 0, 0));
+    expect(calls).toEqual([]);
+    tf.updateTransform("robot", translate(10, 0, 0));
+    tf.updateTransform("robot", translate(10.5, 0, 0));
+    expect(calls).toEqual([["gripper", true]]);
+    // Moving the region away counts as leaving it.
+    tf.updateTransform("zone", translate(20, 0, 0));
+    expect(calls).toEqual([
+      ["gripper", true],
+      ["gripper", false],
+    ]);
+  });
+
+  it("onConnectivityChange() fires when frames become connected or disconnected", () => {
+    const calls: boolean[] = [];
+    tf.onConnectivityChange("map", "camera", (connected) => calls.push(connected));
+
+    tf.addFrame("camera");
+    tf.addFrame("map");
+    tf.addFrame("robot", "map");
+    expect(calls).toEqual([]);
+    tf.setParent("camera", "robot");
+    expect(calls).toEqual([true]);
+    tf.setParent("camera", "map");
+    expect(calls).toEqual([true]);
+    tf.removeFrame("camera");
+    expect(calls).toEqual([true, false]);
+  });
+
   it("onChange() does not fire after the watched frame is removed", () => {
     tf.addFrame("world");
     tf.addFrame("robot", "world");
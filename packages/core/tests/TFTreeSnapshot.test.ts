@@ -0,0 +1,99 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import { TFTree } from "../src/TFTree.js";
+import { Transform } from "../src/math/Transform.js";
+import { Vec3 } from "../src/math/Vec3.js";
+import { FrameNotFoundError } from "../src/FrameNotFoundError.js";
+import { DisconnectedFramesError } from "../src/DisconnectedFramesError.js";
+
+// ── helpers ──────────────────────────────────────────────────────────────────
+
+function translate(x: number, y: number, z: number): Transform {
+  return new Transform(new Vec3(x, y, z));
+}
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("TFTreeSnapshot", () => {
+  let tf: TFTree;
+
+  beforeEach(() => {
+    tf = new TFTree();
+    tf.addFrame("world");
+    tf.addFrame("robot", "world", translate(1, 0, 0));
+    tf.addFrame("camera", "robot", translate(0, 0, 1));
+    tf.addAlias("base_link", "robot");
+  });
+
+  it("answers queries from the state it was taken at", () => {
+    const view = tf.snapshot();
+    tf.updateTransform("robot", translate(5, 0, 0));
+    tf.addFrame("arm", "robot");
+    tf.removeFrame("camera");
+
+    expect(view.getTransform("world", "camera").equals(translate(1, 0, 1))).toBe(true);
+    expect(view.getWorldTransform("base_link").equals(translate(1, 0, 0))).toBe(true);
+    expect(view.getLocalTransform("camera").equals(translate(0, 0, 1))).toBe(true);
+    expect(view.frameIds()).toEqual(["world", "robot", "camera"]);
+    expect(view.hasFrame("arm")).toBe(false);
+    expect(tf.getTransform("world", "robot").equals(translate(5, 0, 0))).toBe(true);
+    expect(tf.snapshot().getTransform("world", "arm").equals(translate(5, 0, 0))).toBe(true);
+  });
+
+  it("is reused until the tree changes", () => {
+    const view = tf.snapshot();
+    expect(view.revision).toBe(tf.revision());
+    expect(tf.snapshot()).toBe(view);
+    tf.updateTransform("camera", translate(0, 0, 2));
+    expect(tf.snapshot()).not.toBe(view);
+
+    const before = tf.snapshot();
+    tf.addAlias("optical", "camera");
+    expect(tf.snapshot().hasFrame("optical")).toBe(true);
+    expect(before.hasFrame("optical")).toBe(false);
+  });
+
+  it("throws like the tree for unknown and disconnected frames", () => {
+    tf.addFrame("island");
+    const view = tf.snapshot();
+    expect(() => view.getTransform("world", "ghost")).toThrowError(FrameNotFoundError);
+    expect(() => view.getTransform("world", "island")).toThrowError(DisconnectedFramesError);
+    expect(view.isConnected("camera", "base_link")).toBe(true);
+    expect(view.isConnected("camera", "island")).toBe(false);
+    expect(view.isConnected("camera", "ghost")).toBe(false);
+  });
+
+  it("publishes the back buffer with swap()", () => {
+    expect(tf.front().frameIds()).toEqual([]);
+    const published = tf.swap();
+    expect(tf.front()).toBe(published);
+    tf.updateTransform("robot", translate(2, 0, 0));
+    expect(tf.front().getTransform("world", "robot").equals(translate(1, 0, 0))).toBe(true);
+    tf.swap();
+    expect(tf.front().getTransform("world", "robot").equals(translate(2, 0, 0))).toBe(true);
+  });
+
+  it("diffAgainst() reports changes since a baseline above the thresholds", () => {
+    tf.addFrame("lidar", "robot", translate(0, 1, 0));
+    tf.addFrame("old", "world");
+    const baseline = tf.snapshot();
+    expect(tf.diffAgainst(baseline)).toEqual([]);
+
+    tf.updateTransform("robot", translate(1.5, 0, 0));
+    tf.updateTransform("camera", translate(0, 0, 1.001));
+    tf.setParent("lidar", "world");
+    tf.removeFrame("old");
+    tf.addFrame("new", "camera");
+
+    expect(tf.diffAgainst(baseline, { minTranslation: 0.01 })).toEqual([
+      { type: "added", id: "new", parentId: "camera" },
+      { type: "reparented", id: "lidar", parentId: "world", previousParentId: "robot" },
+      { type: "moved", id: "robot", translation: 0.5, rotation: 0 },
+      { type: "removed", id: "old", parentId: "world" },
+    ]);
+    const inWorld = tf
+      .diffAgainst(baseline, { minTranslation: 0.01, space: "world" })
+      .filter((d) => d.type === "moved")
+      .map((d) => d.id);
+    expect(inWorld).toEqual(["robot", "camera"]);
+  });
+});